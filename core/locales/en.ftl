@@ -0,0 +1,634 @@
+# English messages, also used as the fallback for other languages.
+
+# relative time
+time-in-future = in the future
+time-just-now = just now
+time-minutes-ago = { $count } min ago
+time-hours-ago = { $count } h ago
+time-days-ago = { $count } days ago
+
+# status section
+section-unavailable = unavailable: { $reason }
+status-title = Status
+status-service = Service
+status-running = is running
+status-not-running = is not running
+status-stale = collected { $age } ago, the next collection is taking longer than --watch
+status-network-view = Network view
+status-seen-online = seen online
+status-seen-online-at = seen online { $ago }
+status-not-seen-online = not seen online by the network
+status-new-version = New Version
+status-new-version-released = { $version } released!
+status-version = Version
+status-commit = Commit
+status-date = Date
+status-build = Build
+status-node-name = Node Name
+status-subnet = Subnet
+status-account = Account
+status-account-identity = node identity
+status-presets = Presets
+status-no-presets = none active, no offers are published
+status-payment-networks = Paid on
+status-earning-potential = Earning potential
+status-earning-value = up to { $glm } GLM/h at full utilization
+status-earning-value-fiat = up to { $glm } GLM/h (~{ $fiat } { $currency }/h) at full utilization
+status-environment = Environment
+status-env-limitation = Warning: { $limitation }
+status-vm = VM
+status-vm-valid = valid
+status-vm-no-access = no access
+status-vm-invalid-env = invalid environment
+status-vm-problem = VM problem: { $problem }
+subnet-not-public = not the { $public } subnet, only requestors sending their tasks to { $subnet } will find this node
+subnet-not-public-proposals = not the { $public } subnet, { $count } proposals from requestors on { $subnet } in the last day
+terms-title = Terms of use
+terms-accepted = accepted ({ $version })
+terms-not-accepted = not accepted, run `golemsp run` in a terminal to review them
+terms-waiting = golemsp waits for the terms of use to be accepted, run it in a terminal to accept them
+
+# wallet section
+wallet-title = Wallet
+wallet-no-backup = the key was never backed up, run `yagna-usd wallet backup`
+account-bad-checksum = WARNING: the ya-provider account { $account } has a broken checksum, did you mean { $expected }?
+account-differs = WARNING: earnings go to { $account }, configured in ya-provider, not to the node identity { $identity }
+account-not-receiving = WARNING: earnings go to { $account }, but yagna only receives payments on { $receiving }, run `yagna payment init --receiver`
+address-no-prefix = { $address } is not an address, it has to start with 0x
+address-malformed = { $address } is not an address, it has to have 40 hex digits after 0x
+address-bad-checksum = { $address } has a broken checksum (mixed case has to follow EIP-55)
+wallet-network = network
+wallet-glm-price = GLM price
+wallet-total-value = total value
+wallet-unsupported-currency = Unsupported currency: { $currency }
+wallet-prices-unavailable = Prices currently unavailable
+wallet-amount-total = amount (total)
+wallet-pending = pending
+wallet-issued = issued
+wallet-debit-notes = debit notes
+wallet-debit-notes-issued = issued
+wallet-debit-notes-accepted = accepted
+wallet-debit-notes-rejected = rejected
+wallet-acceptance = acceptance (median, { $days }d)
+wallet-acceptance-value = { $median } ({ $count } invoices)
+rates-footnote = Rates from { $source }: { $rates }, fetched { $fetched } ({ $ago })
+rates-stale = stale
+
+# profitability section
+profit-title = Profitability
+profit-power = Power
+profit-power-value = { $watts } W at { $price } { $currency }/kWh
+profit-energy-cost = Energy cost
+profit-energy-cost-value = { $hour } { $currency }/h, { $day } { $currency }/day
+profit-earned = Earnings
+profit-earned-value = { $glm } GLM/day ({ $fiat } { $currency })
+profit-earned-glm = { $glm } GLM/day
+profit-no-data = not enough data yet
+profit-break-even = Break-even price
+profit-break-even-value = { $price } { $currency }/GLM
+profit-net = Net profit
+profit-net-value = { $net } { $currency }/day
+
+# tasks section
+tasks-title = Tasks
+tasks-utilization = running / limit
+tasks-utilization-value = { $running } of { $limit } simultaneous agreements ({ $percent }%)
+tasks-last-1h-processed = last 1h processed
+tasks-last-1h-in-progress = last 1h in progress
+tasks-total-processed = total processed
+tasks-including-failures = (including failures)
+tasks-last-activity = last activity
+tasks-by-runtime = by runtime
+tasks-runtime-value = { $activities } activities, { $glm } GLM invoiced
+
+# requestor section
+requestor-title = Requestor
+requestor-spent = paid
+requestor-to-pay = accepted, to pay
+requestor-to-accept = invoices to accept
+requestor-glm-value = { $glm } GLM ({ $count } agreements)
+requestor-debit-notes = debit notes to accept
+requestor-debit-notes-value = { $glm } GLM due ({ $count } activities)
+requestor-allocations = open allocations
+requestor-allocations-value = { $count }, { $glm } GLM remaining
+requestor-demands = active demands
+allocation-expiring = allocation { $id } expires in { $left }
+allocation-low = allocation { $id } is almost used up, { $remaining } GLM left
+
+# progress indicator
+progress-provider-config = querying provider configuration
+progress-yagna-version = querying yagna version
+progress-payment-network = detecting payment network
+progress-identity = querying node identity
+progress-invoices = querying invoice status
+progress-debit-notes = counting debit notes
+progress-acceptance = measuring invoice acceptance
+progress-payment-failures = looking for failed payments
+progress-drivers = checking payment drivers
+progress-payment-status = querying payment status on { $network }
+progress-glm-price = fetching GLM price
+progress-net-status = querying network status
+progress-net-sessions = querying network sessions
+progress-processes = sampling process metrics
+progress-services = querying service manager
+progress-hardware = detecting hardware
+progress-gpu = checking GPU runtimes
+progress-ranking = fetching network statistics
+progress-market = counting market events
+progress-rejections = reading ya-provider logs
+progress-problems = scanning logs for errors
+progress-observation = asking the network about this node
+progress-latest-release = checking for yagna updates
+progress-activity = querying activity status
+progress-workload = scanning work per runtime
+progress-requestor = querying allocations, debit notes and demands
+
+# p2p section
+p2p-title = P2P network
+p2p-mode = mode
+p2p-peers = connected peers
+p2p-relay = relay
+p2p-public-address = public address
+p2p-no-public-address = none (relayed)
+
+# hardware section
+hardware-title = Hardware
+hardware-cpu = CPU
+hardware-cores = Cores
+hardware-cores-threads = { $cores } cores, { $threads } threads
+hardware-threads = { $threads } threads
+hardware-memory = RAM
+hardware-disk = Disk
+hardware-free-of = { $free } free of { $total }
+hardware-offered = Offered ({ $profile })
+hardware-offered-resources = { $threads } threads, { $memory } GiB RAM, { $storage } GiB disk
+hardware-offer-threads = offers { $offered } threads, the machine has { $available }
+hardware-offer-memory = offers { $offered } GiB RAM, the machine has { $available } GiB
+hardware-offer-storage = offers { $offered } GiB disk, only { $available } GiB are free
+hardware-offer-underused = offers only { $cpu }% of the threads and { $memory }% of the RAM, see `yagna-usd profile set`
+hardware-gpu = GPU
+hardware-no-gpu = none detected
+hardware-bench = Benchmark
+hardware-bench-cpu = { $single } MB/s, { $multi } MB/s on { $threads } threads
+hardware-bench-mb-s = { $value } MB/s
+hardware-bench-disk = { $value } MB/s write
+
+# gpu section
+gpu-title = GPU
+gpu-runtime = Runtime
+gpu-no-runtime = no GPU runtime installed
+gpu-driver = Driver
+gpu-cuda = CUDA
+gpu-not-detected = not detected
+gpu-preset = Active preset
+gpu-preset-inactive = no active GPU preset
+
+# ranking section
+ranking-title = Ranking
+ranking-online = providers online
+ranking-not-online = node not listed among online providers
+ranking-tasks = tasks
+ranking-earnings = earnings (GLM)
+ranking-uptime = uptime (%)
+ranking-compared = median { $median }, top { $top }%
+
+# market section
+market-title = Market
+market-active-offers = active offers
+market-last-hour = 1h
+market-last-day = 24h
+market-offers = offers published
+market-proposals = proposals received
+market-rejected = proposals rejected
+market-agreements = agreements
+market-no-proposals = no proposals in the last 24h, check pricing with `yagna-usd price suggest`
+
+# rejections section
+rejections-title = Rejections (24h)
+rejections-none = no rejected proposals
+
+# problems section
+problems-title = Recent problems ({ $minutes } min)
+problems-window-too-large = problems window of { $minutes } minutes is too large
+problems-none = no errors or warnings logged
+
+# payment failures section
+payment-failures-title = Payment failures ({ $hours }h)
+payment-failures-provider = agreements with failed invoices
+payment-failures-requestor = agreements the node failed to pay
+
+# payment drivers section
+drivers-title = Payment drivers
+drivers-endpoint = RPC endpoint
+drivers-block = last block
+drivers-pending = pending on chain
+drivers-unreachable = unreachable
+drivers-skipped = not asked, public endpoint
+drivers-rpc-failed = { $network } RPC endpoint failed: { $error }
+drivers-stale-block = { $network } RPC endpoint is behind the chain, last block { $block }
+drivers-problem = payment driver reports { $kind } on { $network }
+
+# offline screen
+offline-title = yagna is not running
+offline-cause = Probable cause
+offline-start = To start it
+offline-start-manual = yagna-usd service start
+offline-cause-not-installed = yagna was not found, it doesn't seem to be installed
+offline-cause-api = the yagna process runs but its API at { $url } doesn't answer, it may still be starting or listen elsewhere (YAGNA_API_URL)
+offline-cause-service-failed = the { $name } service stopped: { $error }
+offline-cause-service-stopped = the { $name } service is stopped
+offline-cause-error = yagna logged: { $message }
+offline-cause-stopped = yagna was stopped or never started
+offline-last-known = Last known, { $ago } (stale)
+offline-no-history = no data from a previous run
+
+# uptime section
+uptime-title = Availability
+uptime-24h = last 24h
+uptime-days = last { $days } days
+uptime-no-data = no data
+
+# changes since the previous run
+changes-title = Changes
+changes-title-since = Changes since { $ago }
+changes-none = nothing changed
+changes-first-run = no previous status to compare with yet
+changes-running = yagna running
+changes-version = version
+changes-update = new version
+changes-node-name = node name
+changes-subnet = subnet
+changes-online = seen online
+changes-tasks = tasks processed
+changes-offers = active offers
+changes-earnings = earnings (stats)
+changes-balance = balance ({ $network })
+
+# alerts printed by status --quiet
+alert-not-running = yagna is not running
+alert-section-failed = { $section } could not be collected: { $error }
+alert-payments-failing = invoices failed or payments could not be checked
+alert-idle = no task computed recently
+alert-below-break-even = GLM trades at { $price } { $currency }, below the break-even price of { $break_even } { $currency }
+alert-payment-failures = payments failing: { $invoices } failed agreements, { $errors } errors logged, eg. { $reason }
+
+# processes section
+processes-title = Processes
+processes-not-running = not running
+processes-name-pid = { $name } ({ $pid })
+processes-cpu = { $cpu }% CPU
+processes-fds = { $count } open files
+
+# service section
+service-title = Service
+service-unit = { $name } ({ $manager })
+service-state = state
+service-since = since
+service-restarts = restarts
+service-recent-error = error
+
+# platform environment
+env-bare-metal = bare metal
+env-unknown-hypervisor = virtual machine
+env-container-limits = running inside { $container }; the VM runtime needs /dev/kvm passed to the container and the P2P port published
+env-wsl1-limits = WSL1 does not support KVM, the VM runtime will not work
+env-wsl2-limits = WSL2 needs nested virtualization enabled for the VM runtime
+env-nested-virt = running under { $hypervisor }; the VM runtime needs nested virtualization enabled on the host
+
+# errors
+error-no-offers = Provider is not functioning properly. No offers Subscribed.
+error-unknown-payment-network = Unable to determine payment network used by the Yagna Provider.
+http-bad-proxy = Invalid proxy { $proxy }: { $reason }, check YAGNA_USD_PROXY or HTTPS_PROXY/HTTP_PROXY
+http-socks-unsupported = SOCKS proxies are not supported, use an HTTP(S) proxy
+http-ca-bundle-unreadable = Unable to load CA certificates from { $path } (YAGNA_USD_CA_BUNDLE)
+http-no-roots = No trusted certificates: YAGNA_USD_NO_BUILTIN_ROOTS is set, but YAGNA_USD_CA_BUNDLE holds none
+http-api-error = { $url } answered { $status } { $reason }
+http-tls-error = TLS connection to { $url } failed: { $reason }. Behind a proxy intercepting TLS point YAGNA_USD_CA_BUNDLE to its CA certificate
+
+# platform
+vm-xen-unsupported = unsupported virtualization type: XEN
+vm-docker-no-kvm = running inside Docker without access to /dev/kvm. For additional help see: https://handbook.golem.network/troubleshooting/provider-troubleshooting#invalid-vm
+vm-kvm-not-installed = kvm kernel module is not installed
+vm-kvm-no-access = the user has no access to /dev/kvm. For additional help see: https://handbook.golem.network/troubleshooting/provider-troubleshooting#invalid-vm
+
+# doctor
+doctor-binary = { $program } binary
+doctor-binary-not-found = not found
+doctor-binary-not-found-hint = install yagna with the golem installer or put its binaries on PATH
+doctor-binary-not-executable = { $path } is not executable
+doctor-binary-not-executable-hint = fix file permissions, eg. `chmod +x`
+doctor-install = install method
+doctor-install-mixed = yagna: { $yagna }, ya-provider: { $provider }
+doctor-install-mixed-hint = binaries from different installations may be incompatible, remove one of them or set --yagna-bin and --provider-bin
+doctor-daemon = yagna daemon
+doctor-daemon-running = reachable
+doctor-daemon-not-running = not reachable
+doctor-daemon-hint = start the provider with `golemsp run` or check YAGNA_API_URL
+doctor-appkey = app key
+doctor-appkey-valid = { $name } ({ $key }) accepted by the REST API
+doctor-appkey-rejected = { $name } ({ $key }) rejected: { $error }
+doctor-appkey-hint = check `yagna app-key list` and the YAGNA_APPKEY variable
+doctor-appkey-missing = no { $name } app key
+doctor-appkey-create-hint = create it with `yagna app-key create { $name }`
+doctor-payment-accounts = payment accounts
+doctor-payment-accounts-none = no payment accounts initialized
+doctor-payment-accounts-no-receive = no account initialized for receiving payments
+doctor-payment-accounts-hint = run `yagna payment init --receiver`
+doctor-payment-networks = payment networks
+doctor-payment-networks-missing = ya-provider expects payments on { $platforms }, but yagna doesn't receive there
+doctor-account-hint = set the account with `yagna-usd provider set --account <address>` or receive on it with `yagna payment init --receiver`
+doctor-presets = active presets
+doctor-presets-none = no preset is active
+doctor-presets-hint = activate one with `ya-provider preset activate <name>`
+doctor-presets-runtime-inactive = no active preset for { $runtimes }
+doctor-presets-price-hint = compare with the network with `yagna-usd price suggest`
+doctor-clock = clock
+doctor-clock-skew = { $seconds }s off
+doctor-clock-unknown = unable to check: { $reason }
+doctor-clock-hint = enable time synchronization (NTP); agreements fail with a skewed clock
+doctor-visibility = market visibility
+doctor-visibility-last-seen = last seen in a market scan { $ago }
+doctor-visibility-never = never seen in market scans
+doctor-visibility-unknown = unable to check: { $reason }
+doctor-visibility-hint = check that ya-provider is running with an active preset and the node can reach the relay (`yagna-usd net check`)
+doctor-disk = disk space
+doctor-disk-free = { $free } free in { $path }
+doctor-disk-hint = free some space in the yagna data directory
+
+# net check
+net-relay = relay
+net-relay-unresolved = cannot resolve { $host }
+net-relay-dns-hint = check DNS configuration or YA_NET_RELAY_HOST
+net-relay-connected = connected to { $relay }
+net-relay-not-connected = no connection with { $relay }
+net-relay-resolved-only = { $relay } resolved, daemon not running
+net-relay-firewall-hint = allow outgoing UDP traffic to the relay
+net-port = port { $port }/udp
+net-port-free = nothing is listening
+net-port-listening = yagna is listening
+net-port-hint = forward the UDP port on your router to accept direct connections
+net-nat = NAT
+net-nat-unknown = unknown
+net-nat-unknown-hint = public address is known only while yagna is connected to the relay
+net-nat-none = none, public address { $address }
+net-nat-port-preserving = port preserving, public address { $address }
+net-nat-port-translating = port translating, public address { $address }
+net-inbound = inbound connections
+net-inbound-public = accepted on { $address }
+net-inbound-relayed = not reachable directly, traffic goes through the relay
+net-inbound-unknown = unknown
+
+# runtime test
+runtime-testing = Testing runtime { $name }…
+runtime-test-passed = self-test passed
+runtime-no-supervisor = no exe-unit supervisor or runtime binary configured
+runtime-test-hint = check the runtime installation; the VM runtime also needs /dev/kvm access (see `yagna-usd doctor`)
+runtime-provider-version = ya-provider { $version }
+runtime-binary-missing = runtime binary { $path } does not exist
+runtime-incompatible = version { $min_version } or newer is required by ya-provider { $provider_version }+
+runtime-reinstall-hint = reinstall the runtime with the golem installer
+runtime-unknown = runtime { $name } is not installed
+runtime-none-installed = no runtimes installed for ya-provider
+
+# presets
+presets-name = Preset
+presets-runtime = Runtime
+presets-active = Active
+presets-yes = yes
+presets-no = no
+presets-start-price = Start (GLM)
+presets-hourly-glm = GLM/h
+presets-hourly-fiat = { $currency }/h
+presets-no-rate = GLM price unavailable, fiat prices not shown
+presets-hourly-note = Hourly prices assume a task keeping one CPU thread busy.
+presets-active-count = { $active } of { $defined } presets active, only active ones are offered
+presets-runtime-inactive = the { $runtime } runtime is installed but no active preset offers it
+presets-unknown = no preset named { $name }, defined: { $defined }
+presets-already-active = { $name } is already active
+presets-already-inactive = { $name } is not active
+presets-last-active = { $name } is the only active preset, without it the node offers nothing
+presets-confirm-activate = Offer { $name } on the market?
+presets-confirm-deactivate = Stop offering { $name }?
+presets-free = { $name } is priced at zero, tasks run on it earn nothing
+presets-metric-free = { $name }: { $metric } is free while other providers charge for it
+presets-metric-high = { $name }: { $metric } is { $factor }x the network median, requestors will pick other providers
+presets-metric-low = { $name }: { $metric } is 1/{ $factor } of the network median, check the coefficients are per second
+
+# price suggest
+price-cpu-hour = CPU/h
+price-env-hour = Env/h
+price-start = Start
+price-current = current
+price-suggested = suggested
+price-bad-percentile = percentile must be between 0 and 100
+price-no-network-data = the stats API returned no provider prices
+price-providers-seen = Prices of { $count } online providers (GLM):
+price-applied = Updated prices of preset { $preset }.
+price-apply-hint = Run with --apply to update the active presets with the suggested prices.
+
+# logs
+logs-not-found = no log files or journal entries found for { $daemon }
+
+# self-update
+self-update-up-to-date = yagna-usd { $version } is up to date
+self-update-updating = Updating yagna-usd { $from } → { $to }…
+self-update-done = Updated.
+self-update-no-asset = release has no { $name } asset for this platform
+self-update-checksum-mismatch = checksum of { $name } does not match, not updating
+
+# install methods
+install-custom = custom path
+install-bundled = next to yagna-usd
+install-installer = golem installer
+install-snap = snap
+install-package = system package
+install-cargo = cargo install
+install-unknown = unknown
+
+# exe-units command
+exe-units-name = exe-unit
+exe-units-binary = binary
+exe-units-state = state
+exe-units-supervisor = supervisor
+exe-units-runtime = runtime
+exe-units-extra-args = extra args
+exe-units-ok = executable
+exe-units-not-executable = not executable
+exe-units-missing = missing
+exe-units-not-set = not set
+
+# profile command
+resources-profile = profile
+resources-threads = threads
+resources-mem = RAM (GiB)
+resources-storage = disk (GiB)
+resources-bad-unit = unknown size unit { $unit }, use m, g or t
+resources-bad-size = not a size: { $size }
+resources-set-nothing = nothing to change, pass --cores, --mem or --storage
+resources-unknown = no resource profile named { $name }
+resources-updated = Profile { $name } now offers { $threads } threads, { $mem } GiB RAM and { $storage } GiB disk
+resources-hourly-before = GLM/h on { $threads } threads before
+resources-hourly-after = GLM/h on { $threads } threads now
+resources-hourly-note = Hourly prices assume a task keeping all offered threads busy.
+
+# outbound command
+outbound-everyone = Outbound access for any requestor:
+outbound-disabled = disabled
+outbound-mode = { $mode }
+outbound-audited = Audited payloads signed by { $cert }: { $mode }
+outbound-whitelist = Whitelist
+outbound-pattern = pattern
+outbound-type = type
+outbound-keystore = Keystore
+outbound-no-certs = no certificates, audited payloads can't be verified
+outbound-subject = subject
+outbound-not-after = valid until
+outbound-cert-expired = expired { $date }
+outbound-blocked = payloads of any requestor can't reach { $domains }, see `yagna-usd outbound everyone` and `yagna-usd outbound whitelist add`
+
+# which
+which-program = program
+which-path = path
+which-install = installed with
+which-version = version
+which-executable = executable
+which-yes = yes
+which-no = no
+which-not-found = not found
+which-running = yagna running as pid { $pid }, API { $api }, datadir { $datadir }
+which-default = default
+
+# snapshot
+snapshot-saved = Status snapshot saved to { $path }
+snapshot-invalid = { $path } is not a yagna-usd status snapshot: { $error }
+snapshot-header = Status collected { $date } by yagna-usd { $version }
+
+# selftest
+selftest-mainnet = { $network } is not a test network, selftest only runs on test networks
+selftest-provider-network = ya-provider is paid on { $networks }, not on { $network }; start it with YA_PAYMENT_NETWORK_GROUP=testnet
+selftest-subnet-not-restored = could not put ya-provider back into subnet { $subnet }, run `ya-provider config set --subnet { $subnet }`
+selftest-interrupted = interrupted, cleaning up
+selftest-passed = { $stage }: ok
+selftest-failed = { $stage } failed
+selftest-identity = identity
+selftest-identity-detail = node { $node }, moved to private subnet { $subnet } for the test
+selftest-identity-hint = check that yagna runs and ya-provider is configured (`golemsp settings show`)
+selftest-allocation = test funds
+selftest-allocation-detail = { $amount } { $token } allocated
+selftest-allocation-hint = fund the node with `yagna payment fund` and try again
+selftest-demand = demand
+selftest-demand-detail = published in subnet { $subnet }
+selftest-demand-hint = check the market connection with `yagna-usd net check`
+selftest-agreement = negotiation
+selftest-agreement-detail = agreement { $agreement }
+selftest-agreement-hint = make sure the provider is running and has an offer on the test network, see `yagna-usd logs provider`
+selftest-no-offer = no offer from the local provider
+selftest-no-counter-proposal = the local provider didn't answer the counter proposal
+selftest-rejected = the local provider rejected the demand: { $reason }
+selftest-activity = task execution
+selftest-activity-executed = command executed in the VM
+selftest-activity-no-package = activity started and stopped, pass --package to run a command
+selftest-activity-hint = test the runtime with `yagna-usd runtime test`
+selftest-exec-timeout = the command didn't finish in time
+selftest-invoice = invoice
+selftest-invoice-detail = { $amount } invoiced and accepted
+selftest-invoice-hint = the provider didn't send an invoice, see `yagna-usd logs provider`
+selftest-no-invoice = no invoice for the agreement
+
+# earnings
+earnings-name = Runtime / preset
+earnings-total = Total GLM
+earnings-last-days = Last { $days } days
+earnings-runtimes = Runtimes
+earnings-presets = Presets
+
+# agreements
+since-invalid = invalid time { $value }, use eg. "12h", "7d" or "2021-06-01"
+since-too-early = { $value } reaches too far back
+minutes-invalid = invalid number of minutes { $value }, expected 1 to { $max }
+decimals-invalid = invalid number of decimal places { $value }, expected 0 to { $max }
+agreements-id = Agreement
+agreements-requestor = Requestor
+agreements-state = State
+agreements-created = Created
+agreements-duration = Duration
+agreements-usage = Usage
+agreements-invoiced = Invoiced GLM
+agreements-none = no agreements found
+agreements-not-found = agreement { $id } not found: { $error }
+agreements-title = Agreement { $id }
+agreements-offer = Offer
+agreements-demand = Demand
+agreements-debit-notes = Debit notes
+agreements-invoices = Invoices
+agreements-payments = Payments
+
+# payments
+payments-median = median
+payments-confirmation = Payment confirmation after acceptance
+payments-platform = platform
+payments-confirmed = confirmed
+payments-p50 = p50
+payments-p95 = p95
+payments-none-confirmed = no payment confirmed yet
+payments-gas = Gas spent on sent transactions
+payments-network = network
+payments-transactions = transactions
+payments-gas-native = gas
+payments-gas-fiat = { $currency }
+payments-gas-failed = explorer unavailable: { $error }
+
+# allocations
+allocations-id = Allocation
+allocations-platform = Platform
+allocations-total = Total GLM
+allocations-remaining = Remaining GLM
+allocations-timeout = Timeout
+allocations-expires-in = in { $left }
+allocations-expired = expired
+allocations-none = no allocations
+
+# top
+top-header = { $time }  { $count } running, { $total } GLM accrued
+top-idle = no activity running
+top-error = refresh failed, showing the last activities: { $error }
+top-activity = Activity
+top-agreement = Agreement
+top-runtime = Runtime
+top-state = State
+top-elapsed = Elapsed
+top-cost = Cost GLM
+
+# profiles
+profile-not-found = profile { $name } not found, create { $path }
+
+# bench
+bench-download = download
+bench-upload = upload
+bench-net-previous = previous run { $date }: download { $download } Mbit/s, upload { $upload } Mbit/s
+bench-cpu-single = CPU, 1 thread
+bench-cpu-multi = CPU, { $threads } threads
+bench-memory = memory
+bench-disk-write = disk write
+bench-was = (previously { $value })
+
+# service command
+service-already-running = yagna is already running
+service-not-installed = yagna was not found, install it first
+service-waiting = waiting for the daemons to get ready...
+service-ready = yagna and ya-provider are running, API at { $url }
+service-not-ready = the daemons did not get ready within { $seconds } s, see `yagna-usd logs`
+service-stopping = stopping { $name }
+service-stopped = yagna and ya-provider are stopped
+service-still-running = still running: { $names }
+
+# wallet command
+wallet-backup-unlocked = The key of this node has no password, the backup would hold it unencrypted.
+wallet-backup-lock = Set a password now? yagna will then ask for it after every restart (yagna id unlock)
+wallet-backup-written = Key written to { $path }, keep it offline and restore it with `yagna id import`
+wallet-backup-replay = A replayed key is no backup, run `wallet backup` without --replay
+wallet-backup-exists = { $path } already exists, remove it or choose another file with --out
+
+# provider command
+provider-set-nothing = nothing to change, pass --node-name, --subnet or --account
+provider-set-done = ya-provider now runs as { $node_name } in subnet { $subnet }, paid to { $account }
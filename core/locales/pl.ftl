@@ -0,0 +1,634 @@
+# Polish messages
+
+# relative time
+time-in-future = w przyszłości
+time-just-now = przed chwilą
+time-minutes-ago = { $count } min temu
+time-hours-ago = { $count } godz. temu
+time-days-ago = { $count } dni temu
+
+# status section
+section-unavailable = niedostępne: { $reason }
+status-title = Status
+status-service = Usługa
+status-running = działa
+status-not-running = nie działa
+status-stale = zebrano { $age } temu, kolejne zbieranie trwa dłużej niż --watch
+status-network-view = Widok z sieci
+status-seen-online = widoczny online
+status-seen-online-at = widoczny online { $ago }
+status-not-seen-online = niewidoczny w sieci
+status-new-version = Nowa wersja
+status-new-version-released = wydano { $version }!
+status-version = Wersja
+status-commit = Commit
+status-date = Data
+status-build = Kompilacja
+status-node-name = Nazwa węzła
+status-subnet = Podsieć
+status-account = Konto
+status-account-identity = tożsamość węzła
+status-presets = Presety
+status-no-presets = brak aktywnych, żadne oferty nie są publikowane
+status-payment-networks = Płatności w
+status-earning-potential = Potencjał zarobku
+status-earning-value = do { $glm } GLM/h przy pełnym wykorzystaniu
+status-earning-value-fiat = do { $glm } GLM/h (~{ $fiat } { $currency }/h) przy pełnym wykorzystaniu
+status-environment = Środowisko
+status-env-limitation = Uwaga: { $limitation }
+status-vm = VM
+status-vm-valid = poprawna
+status-vm-no-access = brak dostępu
+status-vm-invalid-env = nieprawidłowe środowisko
+status-vm-problem = Problem z VM: { $problem }
+subnet-not-public = to nie podsieć { $public }, węzeł znajdą tylko zleceniodawcy wysyłający zadania do { $subnet }
+subnet-not-public-proposals = to nie podsieć { $public }, { $count } propozycji od zleceniodawców z { $subnet } w ostatniej dobie
+terms-title = Warunki użytkowania
+terms-accepted = zaakceptowane ({ $version })
+terms-not-accepted = niezaakceptowane, uruchom `golemsp run` w terminalu, aby je przejrzeć
+terms-waiting = golemsp czeka na akceptację warunków użytkowania, uruchom go w terminalu, aby je zaakceptować
+
+# wallet section
+wallet-title = Portfel
+wallet-no-backup = klucz nie ma kopii zapasowej, uruchom `yagna-usd wallet backup`
+account-bad-checksum = UWAGA: konto { $account } w ya-provider ma błędną sumę kontrolną, czy chodziło o { $expected }?
+account-differs = UWAGA: zarobki trafiają na { $account } ustawione w ya-provider, a nie na tożsamość węzła { $identity }
+account-not-receiving = UWAGA: zarobki trafiają na { $account }, a yagna odbiera płatności tylko na { $receiving }, uruchom `yagna payment init --receiver`
+address-no-prefix = { $address } nie jest adresem, musi zaczynać się od 0x
+address-malformed = { $address } nie jest adresem, po 0x musi mieć 40 cyfr szesnastkowych
+address-bad-checksum = { $address } ma błędną sumę kontrolną (wielkość liter musi być zgodna z EIP-55)
+wallet-network = sieć
+wallet-glm-price = cena GLM
+wallet-total-value = wartość całkowita
+wallet-unsupported-currency = Nieobsługiwana waluta: { $currency }
+wallet-prices-unavailable = Ceny są obecnie niedostępne
+wallet-amount-total = kwota (razem)
+wallet-pending = oczekujące
+wallet-issued = wystawione
+wallet-debit-notes = noty debetowe
+wallet-debit-notes-issued = wystawione
+wallet-debit-notes-accepted = zaakceptowane
+wallet-debit-notes-rejected = odrzucone
+wallet-acceptance = akceptacja (mediana, { $days } dni)
+wallet-acceptance-value = { $median } (faktury: { $count })
+rates-footnote = Kursy z { $source }: { $rates }, pobrane { $fetched } ({ $ago })
+rates-stale = nieaktualny
+
+# profitability section
+profit-title = Opłacalność
+profit-power = Pobór mocy
+profit-power-value = { $watts } W po { $price } { $currency }/kWh
+profit-energy-cost = Koszt energii
+profit-energy-cost-value = { $hour } { $currency }/h, { $day } { $currency }/dzień
+profit-earned = Zarobki
+profit-earned-value = { $glm } GLM/dzień ({ $fiat } { $currency })
+profit-earned-glm = { $glm } GLM/dzień
+profit-no-data = za mało danych
+profit-break-even = Cena progu opłacalności
+profit-break-even-value = { $price } { $currency }/GLM
+profit-net = Zysk netto
+profit-net-value = { $net } { $currency }/dzień
+
+# tasks section
+tasks-title = Zadania
+tasks-utilization = aktywne / limit
+tasks-utilization-value = { $running } z { $limit } jednoczesnych umów ({ $percent }%)
+tasks-last-1h-processed = przetworzone w ostatniej 1h
+tasks-last-1h-in-progress = w toku w ostatniej 1h
+tasks-total-processed = przetworzone łącznie
+tasks-including-failures = (łącznie z nieudanymi)
+tasks-last-activity = ostatnia aktywność
+tasks-by-runtime = według środowiska
+tasks-runtime-value = aktywności: { $activities }, zafakturowano { $glm } GLM
+
+# requestor section
+requestor-title = Zleceniodawca
+requestor-spent = zapłacono
+requestor-to-pay = zaakceptowane, do zapłaty
+requestor-to-accept = faktury do akceptacji
+requestor-glm-value = { $glm } GLM (umowy: { $count })
+requestor-debit-notes = noty debetowe do akceptacji
+requestor-debit-notes-value = { $glm } GLM należności (aktywności: { $count })
+requestor-allocations = otwarte alokacje
+requestor-allocations-value = { $count }, pozostało { $glm } GLM
+requestor-demands = aktywne zapotrzebowania
+allocation-expiring = alokacja { $id } wygasa za { $left }
+allocation-low = alokacja { $id } jest prawie wyczerpana, pozostało { $remaining } GLM
+
+# progress indicator
+progress-provider-config = odczytywanie konfiguracji providera
+progress-yagna-version = odczytywanie wersji yagna
+progress-payment-network = ustalanie sieci płatności
+progress-identity = odczytywanie tożsamości węzła
+progress-invoices = odczytywanie statusu faktur
+progress-debit-notes = zliczanie not debetowych
+progress-acceptance = mierzenie czasu akceptacji faktur
+progress-payment-failures = szukanie nieudanych płatności
+progress-drivers = sprawdzanie sterowników płatności
+progress-payment-status = odczytywanie statusu płatności w sieci { $network }
+progress-glm-price = pobieranie ceny GLM
+progress-net-status = odczytywanie statusu sieci
+progress-net-sessions = odczytywanie sesji sieciowych
+progress-processes = pomiar zużycia zasobów procesów
+progress-services = odpytywanie menedżera usług
+progress-hardware = wykrywanie sprzętu
+progress-gpu = sprawdzanie środowisk GPU
+progress-ranking = pobieranie statystyk sieci
+progress-market = zliczanie zdarzeń rynku
+progress-rejections = czytanie logów ya-provider
+progress-problems = szukanie błędów w logach
+progress-observation = sprawdzanie widoczności węzła w sieci
+progress-latest-release = sprawdzanie aktualizacji yagna
+progress-activity = odczytywanie statusu aktywności
+progress-workload = przeglądanie pracy według środowiska
+progress-requestor = sprawdzanie alokacji, not debetowych i zapotrzebowań
+
+# p2p section
+p2p-title = Sieć P2P
+p2p-mode = tryb
+p2p-peers = połączone węzły
+p2p-relay = relay
+p2p-public-address = adres publiczny
+p2p-no-public-address = brak (przez relay)
+
+# hardware section
+hardware-title = Sprzęt
+hardware-cpu = CPU
+hardware-cores = Rdzenie
+hardware-cores-threads = { $cores } rdzeni, { $threads } wątków
+hardware-threads = { $threads } wątków
+hardware-memory = RAM
+hardware-disk = Dysk
+hardware-free-of = { $free } wolne z { $total }
+hardware-offered = Oferowane ({ $profile })
+hardware-offered-resources = wątki: { $threads }, { $memory } GiB RAM, { $storage } GiB dysku
+hardware-offer-threads = oferuje { $offered } wątków, maszyna ma { $available }
+hardware-offer-memory = oferuje { $offered } GiB RAM, maszyna ma { $available } GiB
+hardware-offer-storage = oferuje { $offered } GiB dysku, wolne jest tylko { $available } GiB
+hardware-offer-underused = oferuje tylko { $cpu }% wątków i { $memory }% RAM, zobacz `yagna-usd profile set`
+hardware-gpu = GPU
+hardware-no-gpu = nie wykryto
+hardware-bench = Test wydajności
+hardware-bench-cpu = { $single } MB/s, { $multi } MB/s na { $threads } wątkach
+hardware-bench-mb-s = { $value } MB/s
+hardware-bench-disk = { $value } MB/s zapis
+
+# gpu section
+gpu-title = GPU
+gpu-runtime = Środowisko
+gpu-no-runtime = brak zainstalowanego środowiska GPU
+gpu-driver = Sterownik
+gpu-cuda = CUDA
+gpu-not-detected = nie wykryto
+gpu-preset = Aktywny preset
+gpu-preset-inactive = brak aktywnego presetu GPU
+
+# ranking section
+ranking-title = Ranking
+ranking-online = dostawcy online
+ranking-not-online = węzeł nie jest widoczny wśród dostawców online
+ranking-tasks = zadania
+ranking-earnings = zarobki (GLM)
+ranking-uptime = dostępność (%)
+ranking-compared = mediana { $median }, górne { $top }%
+
+# market section
+market-title = Rynek
+market-active-offers = aktywne oferty
+market-last-hour = 1h
+market-last-day = 24h
+market-offers = opublikowane oferty
+market-proposals = otrzymane propozycje
+market-rejected = odrzucone propozycje
+market-agreements = umowy
+market-no-proposals = brak propozycji w ciągu ostatnich 24h, sprawdź ceny przez `yagna-usd price suggest`
+
+# rejections section
+rejections-title = Odrzucenia (24h)
+rejections-none = brak odrzuconych propozycji
+
+# problems section
+problems-title = Ostatnie problemy ({ $minutes } min)
+problems-window-too-large = okno problemów { $minutes } minut jest zbyt duże
+problems-none = brak błędów i ostrzeżeń w logach
+
+# payment failures section
+payment-failures-title = Nieudane płatności ({ $hours }h)
+payment-failures-provider = umowy z nieopłaconymi fakturami
+payment-failures-requestor = umowy, których węzeł nie opłacił
+
+# payment drivers section
+drivers-title = Sterowniki płatności
+drivers-endpoint = punkt RPC
+drivers-block = ostatni blok
+drivers-pending = oczekujące w łańcuchu
+drivers-unreachable = nieosiągalny
+drivers-skipped = nie pytany, publiczny punkt
+drivers-rpc-failed = punkt RPC sieci { $network } nie odpowiada: { $error }
+drivers-stale-block = punkt RPC sieci { $network } nie nadąża za łańcuchem, ostatni blok { $block }
+drivers-problem = sterownik płatności zgłasza { $kind } w sieci { $network }
+
+# offline screen
+offline-title = yagna nie działa
+offline-cause = Prawdopodobna przyczyna
+offline-start = Uruchomienie
+offline-start-manual = yagna-usd service start
+offline-cause-not-installed = nie znaleziono yagna, wygląda na to, że nie jest zainstalowana
+offline-cause-api = proces yagna działa, ale jego API pod { $url } nie odpowiada, może się jeszcze uruchamiać albo nasłuchiwać gdzie indziej (YAGNA_API_URL)
+offline-cause-service-failed = usługa { $name } zatrzymała się: { $error }
+offline-cause-service-stopped = usługa { $name } jest zatrzymana
+offline-cause-error = yagna zapisała w logu: { $message }
+offline-cause-stopped = yagna została zatrzymana lub nie była uruchamiana
+offline-last-known = Ostatnie znane dane, { $ago } (nieaktualne)
+offline-no-history = brak danych z poprzednich uruchomień
+
+# uptime section
+uptime-title = Dostępność
+uptime-24h = ostatnie 24h
+uptime-days = ostatnie { $days } dni
+uptime-no-data = brak danych
+
+# changes since the previous run
+changes-title = Zmiany
+changes-title-since = Zmiany od { $ago }
+changes-none = nic się nie zmieniło
+changes-first-run = brak poprzedniego statusu do porównania
+changes-running = yagna działa
+changes-version = wersja
+changes-update = nowa wersja
+changes-node-name = nazwa węzła
+changes-subnet = podsieć
+changes-online = widoczny w sieci
+changes-tasks = przetworzone zadania
+changes-offers = aktywne oferty
+changes-earnings = zarobki (statystyki)
+changes-balance = saldo ({ $network })
+
+# alerts printed by status --quiet
+alert-not-running = yagna nie działa
+alert-section-failed = nie udało się zebrać sekcji { $section }: { $error }
+alert-payments-failing = faktury nie zostały opłacone lub nie udało się sprawdzić płatności
+alert-idle = brak zadań w ostatnim czasie
+alert-below-break-even = GLM kosztuje { $price } { $currency }, poniżej progu opłacalności { $break_even } { $currency }
+alert-payment-failures = płatności nie przechodzą: nieudane umowy: { $invoices }, błędy w logach: { $errors }, np. { $reason }
+
+# processes section
+processes-title = Procesy
+processes-not-running = nie działa
+processes-name-pid = { $name } ({ $pid })
+processes-cpu = { $cpu }% CPU
+processes-fds = { $count } otwartych plików
+
+# service section
+service-title = Usługa
+service-unit = { $name } ({ $manager })
+service-state = stan
+service-since = od
+service-restarts = restarty
+service-recent-error = błąd
+
+# platform environment
+env-bare-metal = fizyczna maszyna
+env-unknown-hypervisor = maszyna wirtualna
+env-container-limits = uruchomiono w { $container }; środowisko VM wymaga przekazania /dev/kvm do kontenera i opublikowania portu P2P
+env-wsl1-limits = WSL1 nie obsługuje KVM, środowisko VM nie będzie działać
+env-wsl2-limits = WSL2 wymaga włączenia zagnieżdżonej wirtualizacji dla środowiska VM
+env-nested-virt = uruchomiono pod { $hypervisor }; środowisko VM wymaga włączenia zagnieżdżonej wirtualizacji na hoście
+
+# errors
+error-no-offers = Provider nie działa poprawnie. Brak opublikowanych ofert.
+error-unknown-payment-network = Nie można ustalić sieci płatności używanej przez Yagna Provider.
+http-bad-proxy = Nieprawidłowe proxy { $proxy }: { $reason }, sprawdź YAGNA_USD_PROXY lub HTTPS_PROXY/HTTP_PROXY
+http-socks-unsupported = proxy SOCKS nie są obsługiwane, użyj proxy HTTP(S)
+http-ca-bundle-unreadable = Nie można wczytać certyfikatów CA z { $path } (YAGNA_USD_CA_BUNDLE)
+http-no-roots = Brak zaufanych certyfikatów: ustawiono YAGNA_USD_NO_BUILTIN_ROOTS, ale YAGNA_USD_CA_BUNDLE nie zawiera żadnego
+http-api-error = { $url } odpowiedział { $status } { $reason }
+http-tls-error = Połączenie TLS z { $url } nie powiodło się: { $reason }. Za proxy przechwytującym TLS wskaż jego certyfikat CA w YAGNA_USD_CA_BUNDLE
+
+# platform
+vm-xen-unsupported = nieobsługiwany typ wirtualizacji: XEN
+vm-docker-no-kvm = uruchomiono w Dockerze bez dostępu do /dev/kvm. Więcej informacji: https://handbook.golem.network/troubleshooting/provider-troubleshooting#invalid-vm
+vm-kvm-not-installed = moduł jądra kvm nie jest zainstalowany
+vm-kvm-no-access = użytkownik nie ma dostępu do /dev/kvm. Więcej informacji: https://handbook.golem.network/troubleshooting/provider-troubleshooting#invalid-vm
+
+# doctor
+doctor-binary = plik wykonywalny { $program }
+doctor-binary-not-found = nie znaleziono
+doctor-binary-not-found-hint = zainstaluj yagna instalatorem golem albo dodaj jego pliki wykonywalne do PATH
+doctor-binary-not-executable = { $path } nie jest wykonywalny
+doctor-binary-not-executable-hint = popraw uprawnienia pliku, np. `chmod +x`
+doctor-install = sposób instalacji
+doctor-install-mixed = yagna: { $yagna }, ya-provider: { $provider }
+doctor-install-mixed-hint = pliki z różnych instalacji mogą być niezgodne, usuń jedną z nich albo ustaw --yagna-bin i --provider-bin
+doctor-daemon = demon yagna
+doctor-daemon-running = osiągalny
+doctor-daemon-not-running = nieosiągalny
+doctor-daemon-hint = uruchom dostawcę przez `golemsp run` albo sprawdź YAGNA_API_URL
+doctor-appkey = klucz aplikacji
+doctor-appkey-valid = { $name } ({ $key }) przyjęty przez REST API
+doctor-appkey-rejected = { $name } ({ $key }) odrzucony: { $error }
+doctor-appkey-hint = sprawdź `yagna app-key list` i zmienną YAGNA_APPKEY
+doctor-appkey-missing = brak klucza aplikacji { $name }
+doctor-appkey-create-hint = utwórz go przez `yagna app-key create { $name }`
+doctor-payment-accounts = konta płatności
+doctor-payment-accounts-none = brak zainicjowanych kont płatności
+doctor-payment-accounts-no-receive = żadne konto nie jest zainicjowane do odbioru płatności
+doctor-payment-accounts-hint = uruchom `yagna payment init --receiver`
+doctor-payment-networks = sieci płatności
+doctor-payment-networks-missing = ya-provider oczekuje płatności na { $platforms }, ale yagna ich tam nie odbiera
+doctor-account-hint = ustaw konto przez `yagna-usd provider set --account <adres>` albo odbieraj na nim przez `yagna payment init --receiver`
+doctor-presets = aktywne presety
+doctor-presets-none = żaden preset nie jest aktywny
+doctor-presets-hint = aktywuj któryś przez `ya-provider preset activate <nazwa>`
+doctor-presets-runtime-inactive = brak aktywnego presetu dla { $runtimes }
+doctor-presets-price-hint = porównaj z siecią przez `yagna-usd price suggest`
+doctor-clock = zegar
+doctor-clock-skew = odchyłka { $seconds }s
+doctor-clock-unknown = nie można sprawdzić: { $reason }
+doctor-clock-hint = włącz synchronizację czasu (NTP); przy rozjechanym zegarze umowy się nie udają
+doctor-visibility = widoczność na rynku
+doctor-visibility-last-seen = ostatnio widoczny w skanie rynku { $ago }
+doctor-visibility-never = nigdy niewidoczny w skanach rynku
+doctor-visibility-unknown = nie można sprawdzić: { $reason }
+doctor-visibility-hint = sprawdź, czy ya-provider działa z aktywnym presetem, a węzeł łączy się z przekaźnikiem (`yagna-usd net check`)
+doctor-disk = miejsce na dysku
+doctor-disk-free = { $free } wolne w { $path }
+doctor-disk-hint = zwolnij trochę miejsca w katalogu danych yagna
+
+# net check
+net-relay = przekaźnik
+net-relay-unresolved = nie można rozwiązać nazwy { $host }
+net-relay-dns-hint = sprawdź konfigurację DNS albo YA_NET_RELAY_HOST
+net-relay-connected = połączony z { $relay }
+net-relay-not-connected = brak połączenia z { $relay }
+net-relay-resolved-only = nazwa { $relay } rozwiązana, demon nie działa
+net-relay-firewall-hint = zezwól na wychodzący ruch UDP do przekaźnika
+net-port = port { $port }/udp
+net-port-free = nic nie nasłuchuje
+net-port-listening = yagna nasłuchuje
+net-port-hint = przekieruj port UDP na routerze, żeby przyjmować bezpośrednie połączenia
+net-nat = NAT
+net-nat-unknown = nieznany
+net-nat-unknown-hint = adres publiczny jest znany tylko, gdy yagna jest połączona z przekaźnikiem
+net-nat-none = brak, adres publiczny { $address }
+net-nat-port-preserving = zachowujący porty, adres publiczny { $address }
+net-nat-port-translating = tłumaczący porty, adres publiczny { $address }
+net-inbound = połączenia przychodzące
+net-inbound-public = przyjmowane na { $address }
+net-inbound-relayed = brak bezpośredniego dostępu, ruch idzie przez przekaźnik
+net-inbound-unknown = nieznane
+
+# runtime test
+runtime-testing = Testowanie środowiska { $name }…
+runtime-test-passed = autotest zaliczony
+runtime-no-supervisor = nie skonfigurowano nadzorcy exe-unit ani pliku środowiska
+runtime-test-hint = sprawdź instalację środowiska; środowisko VM potrzebuje też dostępu do /dev/kvm (zobacz `yagna-usd doctor`)
+runtime-provider-version = ya-provider { $version }
+runtime-binary-missing = plik środowiska { $path } nie istnieje
+runtime-incompatible = ya-provider { $provider_version }+ wymaga wersji { $min_version } lub nowszej
+runtime-reinstall-hint = zainstaluj środowisko ponownie instalatorem golem
+runtime-unknown = środowisko { $name } nie jest zainstalowane
+runtime-none-installed = brak środowisk zainstalowanych dla ya-provider
+
+# presets
+presets-name = Preset
+presets-runtime = Środowisko
+presets-active = Aktywny
+presets-yes = tak
+presets-no = nie
+presets-start-price = Start (GLM)
+presets-hourly-glm = GLM/h
+presets-hourly-fiat = { $currency }/h
+presets-no-rate = cena GLM niedostępna, ceny w walutach nie są pokazane
+presets-hourly-note = Ceny godzinowe zakładają zadanie zajmujące jeden wątek CPU.
+presets-active-count = aktywne presety: { $active } z { $defined }, oferowane są tylko aktywne
+presets-runtime-inactive = środowisko { $runtime } jest zainstalowane, ale żaden aktywny preset go nie oferuje
+presets-unknown = brak presetu { $name }, zdefiniowane: { $defined }
+presets-already-active = { $name } jest już aktywny
+presets-already-inactive = { $name } nie jest aktywny
+presets-last-active = { $name } to jedyny aktywny preset, bez niego węzeł nic nie oferuje
+presets-confirm-activate = Oferować { $name } na rynku?
+presets-confirm-deactivate = Przestać oferować { $name }?
+presets-free = { $name } ma zerową cenę, zadania w nim nic nie zarabiają
+presets-metric-free = { $name }: { $metric } jest za darmo, a inni dostawcy za to pobierają opłaty
+presets-metric-high = { $name }: { $metric } to { $factor }x mediana sieci, zleceniodawcy wybiorą innych dostawców
+presets-metric-low = { $name }: { $metric } to 1/{ $factor } mediany sieci, sprawdź, czy współczynniki są na sekundę
+
+# price suggest
+price-cpu-hour = CPU/h
+price-env-hour = Środ./h
+price-start = Start
+price-current = obecna
+price-suggested = sugerowana
+price-bad-percentile = percentyl musi być między 0 a 100
+price-no-network-data = API statystyk nie zwróciło cen dostawców
+price-providers-seen = Ceny dostawców online: { $count } (GLM):
+price-applied = Zaktualizowano ceny presetu { $preset }.
+price-apply-hint = Uruchom z --apply, żeby ustawić sugerowane ceny w aktywnych presetach.
+
+# logs
+logs-not-found = nie znaleziono plików logów ani wpisów dziennika dla { $daemon }
+
+# self-update
+self-update-up-to-date = yagna-usd { $version } jest aktualna
+self-update-updating = Aktualizacja yagna-usd { $from } → { $to }…
+self-update-done = Zaktualizowano.
+self-update-no-asset = wydanie nie ma pliku { $name } dla tej platformy
+self-update-checksum-mismatch = suma kontrolna { $name } się nie zgadza, aktualizacja przerwana
+
+# install methods
+install-custom = własna ścieżka
+install-bundled = obok yagna-usd
+install-installer = instalator golem
+install-snap = snap
+install-package = pakiet systemowy
+install-cargo = cargo install
+install-unknown = nieznany
+
+# exe-units command
+exe-units-name = exe-unit
+exe-units-binary = plik
+exe-units-state = stan
+exe-units-supervisor = nadzorca
+exe-units-runtime = środowisko
+exe-units-extra-args = dodatkowe argumenty
+exe-units-ok = wykonywalny
+exe-units-not-executable = niewykonywalny
+exe-units-missing = brak
+exe-units-not-set = nie ustawiono
+
+# profile command
+resources-profile = profil
+resources-threads = wątki
+resources-mem = RAM (GiB)
+resources-storage = dysk (GiB)
+resources-bad-unit = nieznana jednostka rozmiaru { $unit }, użyj m, g lub t
+resources-bad-size = to nie jest rozmiar: { $size }
+resources-set-nothing = nic do zmiany, podaj --cores, --mem lub --storage
+resources-unknown = brak profilu zasobów { $name }
+resources-updated = Profil { $name } oferuje teraz { $threads } wątków, { $mem } GiB RAM i { $storage } GiB dysku
+resources-hourly-before = GLM/h na { $threads } wątkach przed zmianą
+resources-hourly-after = GLM/h na { $threads } wątkach teraz
+resources-hourly-note = Ceny godzinowe zakładają zadanie zajmujące wszystkie oferowane wątki.
+
+# outbound command
+outbound-everyone = Dostęp wychodzący dla każdego zleceniodawcy:
+outbound-disabled = wyłączony
+outbound-mode = { $mode }
+outbound-audited = Audytowane ładunki podpisane przez { $cert }: { $mode }
+outbound-whitelist = Biała lista
+outbound-pattern = wzorzec
+outbound-type = typ
+outbound-keystore = Magazyn certyfikatów
+outbound-no-certs = brak certyfikatów, audytowanych ładunków nie da się zweryfikować
+outbound-subject = podmiot
+outbound-not-after = ważny do
+outbound-cert-expired = wygasł { $date }
+outbound-blocked = ładunki zleceniodawców nie mają dostępu do { $domains }, zobacz `yagna-usd outbound everyone` i `yagna-usd outbound whitelist add`
+
+# which
+which-program = program
+which-path = ścieżka
+which-install = zainstalowany przez
+which-version = wersja
+which-executable = wykonywalny
+which-yes = tak
+which-no = nie
+which-not-found = nie znaleziono
+which-running = yagna działa jako pid { $pid }, API { $api }, katalog danych { $datadir }
+which-default = domyślny
+
+# snapshot
+snapshot-saved = Migawkę statusu zapisano w { $path }
+snapshot-invalid = { $path } nie jest migawką statusu yagna-usd: { $error }
+snapshot-header = Status zebrany { $date } przez yagna-usd { $version }
+
+# selftest
+selftest-mainnet = { $network } nie jest siecią testową, selftest działa tylko w sieciach testowych
+selftest-provider-network = ya-provider otrzymuje płatności w { $networks }, nie w { $network }; uruchom go z YA_PAYMENT_NETWORK_GROUP=testnet
+selftest-subnet-not-restored = nie udało się przywrócić ya-provider do podsieci { $subnet }, uruchom `ya-provider config set --subnet { $subnet }`
+selftest-interrupted = przerwano, sprzątanie
+selftest-passed = { $stage }: ok
+selftest-failed = { $stage } nie powiodło się
+selftest-identity = tożsamość
+selftest-identity-detail = węzeł { $node }, na czas testu przeniesiony do prywatnej podsieci { $subnet }
+selftest-identity-hint = sprawdź, czy yagna działa, a ya-provider jest skonfigurowany (`golemsp settings show`)
+selftest-allocation = środki testowe
+selftest-allocation-detail = zaalokowano { $amount } { $token }
+selftest-allocation-hint = zasil węzeł przez `yagna payment fund` i spróbuj ponownie
+selftest-demand = zapotrzebowanie
+selftest-demand-detail = opublikowane w podsieci { $subnet }
+selftest-demand-hint = sprawdź połączenie z rynkiem przez `yagna-usd net check`
+selftest-agreement = negocjacja
+selftest-agreement-detail = umowa { $agreement }
+selftest-agreement-hint = upewnij się, że dostawca działa i ma ofertę w sieci testowej, zobacz `yagna-usd logs provider`
+selftest-no-offer = brak oferty od lokalnego dostawcy
+selftest-no-counter-proposal = lokalny dostawca nie odpowiedział na kontrpropozycję
+selftest-rejected = lokalny dostawca odrzucił zapotrzebowanie: { $reason }
+selftest-activity = wykonanie zadania
+selftest-activity-executed = polecenie wykonane w VM
+selftest-activity-no-package = aktywność uruchomiona i zatrzymana, podaj --package, żeby wykonać polecenie
+selftest-activity-hint = przetestuj środowisko przez `yagna-usd runtime test`
+selftest-exec-timeout = polecenie nie skończyło się na czas
+selftest-invoice = faktura
+selftest-invoice-detail = zafakturowano i zaakceptowano { $amount }
+selftest-invoice-hint = dostawca nie wysłał faktury, zobacz `yagna-usd logs provider`
+selftest-no-invoice = brak faktury za umowę
+
+# earnings
+earnings-name = Środowisko / preset
+earnings-total = Razem GLM
+earnings-last-days = Ostatnie dni: { $days }
+earnings-runtimes = Środowiska
+earnings-presets = Presety
+
+# agreements
+since-invalid = niepoprawny czas { $value }, użyj np. "12h", "7d" lub "2021-06-01"
+since-too-early = { $value } sięga zbyt daleko wstecz
+minutes-invalid = niepoprawna liczba minut { $value }, oczekiwano od 1 do { $max }
+decimals-invalid = niepoprawna liczba miejsc po przecinku { $value }, oczekiwano od 0 do { $max }
+agreements-id = Umowa
+agreements-requestor = Zleceniodawca
+agreements-state = Stan
+agreements-created = Utworzona
+agreements-duration = Czas trwania
+agreements-usage = Zużycie
+agreements-invoiced = Zafakturowano GLM
+agreements-none = nie znaleziono umów
+agreements-not-found = nie znaleziono umowy { $id }: { $error }
+agreements-title = Umowa { $id }
+agreements-offer = Oferta
+agreements-demand = Zapotrzebowanie
+agreements-debit-notes = Noty debetowe
+agreements-invoices = Faktury
+agreements-payments = Płatności
+
+# payments
+payments-median = mediana
+payments-confirmation = Potwierdzenie płatności po akceptacji
+payments-platform = platforma
+payments-confirmed = potwierdzone
+payments-p50 = p50
+payments-p95 = p95
+payments-none-confirmed = żadna płatność nie została jeszcze potwierdzona
+payments-gas = Gaz wydany na wysłane transakcje
+payments-network = sieć
+payments-transactions = transakcje
+payments-gas-native = gaz
+payments-gas-fiat = { $currency }
+payments-gas-failed = eksplorator niedostępny: { $error }
+
+# allocations
+allocations-id = Alokacja
+allocations-platform = Platforma
+allocations-total = Razem GLM
+allocations-remaining = Pozostało GLM
+allocations-timeout = Wygasa
+allocations-expires-in = za { $left }
+allocations-expired = wygasła
+allocations-none = brak alokacji
+
+# top
+top-header = { $time }  działające: { $count }, naliczono { $total } GLM
+top-idle = żadna aktywność nie działa
+top-error = odświeżenie nie powiodło się, pokazane są ostatnie aktywności: { $error }
+top-activity = Aktywność
+top-agreement = Umowa
+top-runtime = Środowisko
+top-state = Stan
+top-elapsed = Czas
+top-cost = Koszt GLM
+
+# profiles
+profile-not-found = nie znaleziono profilu { $name }, utwórz { $path }
+
+# bench
+bench-download = pobieranie
+bench-upload = wysyłanie
+bench-net-previous = poprzedni pomiar { $date }: pobieranie { $download } Mbit/s, wysyłanie { $upload } Mbit/s
+bench-cpu-single = CPU, 1 wątek
+bench-cpu-multi = CPU, wątki: { $threads }
+bench-memory = pamięć
+bench-disk-write = zapis na dysk
+bench-was = (wcześniej { $value })
+
+# service command
+service-already-running = yagna już działa
+service-not-installed = nie znaleziono yagna, najpierw ją zainstaluj
+service-waiting = oczekiwanie na gotowość demonów...
+service-ready = yagna i ya-provider działają, API pod { $url }
+service-not-ready = demony nie były gotowe w ciągu { $seconds } s, zobacz `yagna-usd logs`
+service-stopping = zatrzymywanie { $name }
+service-stopped = yagna i ya-provider są zatrzymane
+service-still-running = nadal działają: { $names }
+
+# wallet command
+wallet-backup-unlocked = Klucz tego węzła nie ma hasła, kopia zapasowa zawierałaby go niezaszyfrowanego.
+wallet-backup-lock = Ustawić teraz hasło? yagna będzie o nie pytać po każdym restarcie (yagna id unlock)
+wallet-backup-written = Klucz zapisano w { $path }, trzymaj go offline i przywróć przez `yagna id import`
+wallet-backup-replay = Odtworzony klucz nie jest kopią zapasową, uruchom `wallet backup` bez --replay
+wallet-backup-exists = { $path } już istnieje, usuń go lub wybierz inny plik przez --out
+
+# provider command
+provider-set-nothing = nic do zmiany, podaj --node-name, --subnet lub --account
+provider-set-done = ya-provider działa teraz jako { $node_name } w podsieci { $subnet }, płatności na { $account }
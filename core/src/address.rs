@@ -0,0 +1,116 @@
+//! Ethereum addresses of the node: format validation and EIP-55 checksum casing
+
+use anyhow::{bail, Result};
+use sha3::{Digest, Keccak256};
+
+/// Keccak-256 as used by Ethereum (the original padding, not SHA3-256).
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&Keccak256::digest(data));
+    hash
+}
+
+/// `address` is "0x" followed by 40 hex digits, whatever their case.
+pub fn is_well_formed(address: &str) -> bool {
+    match address.strip_prefix("0x") {
+        Some(digits) => digits.len() == 40 && digits.chars().all(|c| c.is_ascii_hexdigit()),
+        None => false,
+    }
+}
+
+/// Checks `address` is well formed, with a valid checksum when mixed case.
+pub fn validate(address: &str) -> Result<()> {
+    if !address.starts_with("0x") {
+        bail!(tr!("address-no-prefix", address = address));
+    }
+    if !is_well_formed(address) {
+        bail!(tr!("address-malformed", address = address));
+    }
+    if !has_valid_checksum(address) {
+        bail!(tr!("address-bad-checksum", address = address));
+    }
+    Ok(())
+}
+
+/// All lower or upper case addresses carry no checksum, mixed case ones have to match it.
+pub fn has_valid_checksum(address: &str) -> bool {
+    let digits = address.trim_start_matches("0x");
+    let mut letters = digits.chars().filter(|c| c.is_ascii_alphabetic());
+    let lower = letters.clone().all(|c| c.is_ascii_lowercase());
+    let upper = letters.all(|c| c.is_ascii_uppercase());
+    lower || upper || checksummed(address) == address
+}
+
+/// `address` in EIP-55 casing, eg. "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".
+pub fn checksummed(address: &str) -> String {
+    let digits = address.trim_start_matches("0x").to_ascii_lowercase();
+    let hash = keccak256(digits.as_bytes());
+    let cased: String = digits
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0x0f;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+    format!("0x{}", cased)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Examples of EIP-55, the first two happen to be all caps, the next two all lowercase.
+    const EIP55_EXAMPLES: &[&str] = &[
+        "0x52908400098527886E0F7030069857D2E4169EE7",
+        "0x8617E340B3D01FA5F11F306F4090FD50E238070D",
+        "0xde709f2102306220921060314715629080e2fb77",
+        "0x27b1fdb04752bbc536007a920d24acb045561c26",
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+
+    #[test]
+    fn keccak256_of_known_inputs() {
+        assert_eq!(
+            hex::encode(keccak256(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(
+            hex::encode(keccak256(b"hello world")),
+            "47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad"
+        );
+        assert_eq!(
+            hex::encode(keccak256(b"The quick brown fox jumps over the lazy dog")),
+            "4d741b6f1eb29cb2a9b9911c82f56fa8d73b04959d3d9d222895df6c0b28aa15"
+        );
+    }
+
+    #[test]
+    fn checksummed_matches_eip55_examples() {
+        for example in EIP55_EXAMPLES {
+            assert_eq!(checksummed(&example.to_lowercase()), *example);
+            assert!(has_valid_checksum(example), "{}", example);
+        }
+    }
+
+    #[test]
+    fn mixed_case_with_wrong_checksum_is_rejected() {
+        // first letter of 0x5aAeb... in the wrong case
+        assert!(!has_valid_checksum(
+            "0x5AAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+        ));
+        assert!(has_valid_checksum(
+            "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+        ));
+        assert!(is_well_formed("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"));
+        assert!(!is_well_formed("5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"));
+        assert!(!is_well_formed("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA"));
+    }
+}
@@ -0,0 +1,560 @@
+//! Agreements the provider made with requestors
+
+use anyhow::{anyhow, Result};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use prettytable::{format, row, Table};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::str::FromStr;
+use structopt::StructOpt;
+use strum::VariantNames;
+
+use ya_client::market::MarketProviderApi;
+use ya_client::model::market::Agreement;
+use ya_client::model::payment::{DebitNote, DocumentStatus, Invoice};
+use ya_client::payment::PaymentApi;
+use ya_client::web::WebClient;
+
+use crate::appkey;
+use crate::command::{AgreementEntry, AgreementState, CommandOptions, YaCommand};
+use crate::display::{self, format_duration, format_relative};
+use crate::status::section_table;
+use crate::workload::{self, property, AgreementPayments};
+
+/// Usage counter holding the wall-clock duration of an activity.
+const DURATION_COUNTER: &str = "golem.usage.duration_sec";
+
+#[derive(StructOpt)]
+pub enum AgreementsCommand {
+    /// List agreements with their requestor, usage and invoiced GLM
+    List {
+        /// Only agreements in this state
+        #[structopt(long, possible_values = AgreementState::VARIANTS)]
+        state: Option<AgreementState>,
+        /// Only agreements created since, eg. "12h", "7d" or "2021-06-01"
+        #[structopt(long)]
+        since: Option<Since>,
+        /// Print the agreements as JSON instead of a table
+        #[structopt(long)]
+        json: bool,
+    },
+    /// Negotiated properties, usage, debit notes, invoice and payments of an agreement
+    Show {
+        agreement_id: String,
+        /// Print the agreement as JSON instead of tables
+        #[structopt(long)]
+        json: bool,
+    },
+}
+
+/// Start of a time window, given relative to now ("30m", "12h", "7d") or as a date.
+#[derive(Clone, Copy, Debug)]
+pub struct Since(pub DateTime<Utc>);
+
+impl FromStr for Since {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if let Ok(ts) = DateTime::parse_from_rfc3339(s) {
+            return Ok(Since(ts.with_timezone(&Utc)));
+        }
+        if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+            return Ok(Since(DateTime::from_utc(date.and_hms(0, 0, 0), Utc)));
+        }
+        let unit = s
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(|| anyhow!(tr!("since-invalid", value = s)))?;
+        let count: i64 = s[..unit]
+            .parse()
+            .map_err(|_| anyhow!(tr!("since-invalid", value = s)))?;
+        let unit_secs: i64 = match &s[unit..] {
+            "m" | "min" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            "w" => 7 * 24 * 60 * 60,
+            _ => return Err(anyhow!(tr!("since-invalid", value = s))),
+        };
+        // counts reaching before the calendar's start are refused instead of overflowing
+        count
+            .checked_mul(unit_secs * 1000)
+            .map(Duration::milliseconds)
+            .and_then(|ago| Utc::now().checked_sub_signed(ago))
+            .map(Since)
+            .ok_or_else(|| anyhow!(tr!("since-too-early", value = s)))
+    }
+}
+
+/// Agreement with the requestor, what was used and invoiced under it.
+#[derive(Serialize)]
+pub struct AgreementSummary {
+    pub agreement_id: String,
+    pub requestor_id: String,
+    pub state: Option<AgreementState>,
+    pub created: DateTime<Utc>,
+    /// Seconds the activities ran, or the agreement lasted so far
+    pub duration: Option<i64>,
+    /// Usage counters of the latest debit notes, summed over the activities
+    pub usage: BTreeMap<String, f64>,
+    pub invoiced: BigDecimal,
+}
+
+/// Everything known about an agreement, to investigate disputes with the requestor.
+#[derive(Serialize)]
+pub struct AgreementDetail {
+    #[serde(flatten)]
+    pub summary: AgreementSummary,
+    /// Demand properties, flattened to dotted names
+    pub demand: BTreeMap<String, Value>,
+    /// Offer properties, flattened to dotted names
+    pub offer: BTreeMap<String, Value>,
+    pub debit_notes: Vec<DebitNote>,
+    pub invoices: Vec<Invoice>,
+    pub payments: Vec<AgreementPayment>,
+}
+
+/// Part of a payment which settled the agreement or its activities.
+#[derive(Serialize)]
+pub struct AgreementPayment {
+    pub payment_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub amount: BigDecimal,
+    pub payment_platform: String,
+}
+
+/// Usage counters of the latest debit note of every activity, summed per agreement.
+fn usage_vectors(debit_notes: Vec<DebitNote>) -> HashMap<String, Vec<f64>> {
+    let mut latest = HashMap::<String, DebitNote>::new();
+    for note in debit_notes {
+        match latest.get(&note.activity_id) {
+            Some(known) if known.timestamp >= note.timestamp => (),
+            _ => {
+                latest.insert(note.activity_id.clone(), note);
+            }
+        }
+    }
+    let mut usage = HashMap::<String, Vec<f64>>::new();
+    for note in latest.into_values() {
+        let counters = note
+            .usage_counter_vector
+            .as_ref()
+            .and_then(Value::as_array)
+            .map(|counters| counters.iter().map(|c| c.as_f64().unwrap_or_default()))
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        let sum = usage.entry(note.agreement_id).or_default();
+        if sum.len() < counters.len() {
+            sum.resize(counters.len(), 0.0);
+        }
+        for (total, counter) in sum.iter_mut().zip(counters) {
+            *total += counter;
+        }
+    }
+    usage
+}
+
+pub async fn list(
+    cmd: &YaCommand,
+    state: Option<AgreementState>,
+    since: Option<Since>,
+) -> Result<Vec<AgreementSummary>> {
+    let app_key = appkey::get_app_key(cmd).await?;
+    let client = WebClient::with_token(&app_key);
+    let market: MarketProviderApi = client.interface()?;
+    let payment: PaymentApi = client.interface()?;
+
+    let entries = cmd
+        .yagna()?
+        .agreements()
+        .await?
+        .into_iter()
+        // agreements the node made as a requestor, eg. in `selftest`, are not provider work
+        .filter(
+            |entry| !matches!(&entry.role, Some(role) if role.eq_ignore_ascii_case("requestor")),
+        )
+        .filter(|entry| state.is_none() || entry.state == state)
+        .filter(|entry| match (since, entry.created) {
+            (Some(Since(since)), Some(created)) => created >= since,
+            _ => true,
+        })
+        .collect::<Vec<AgreementEntry>>();
+
+    let (agreements, debit_notes, payments) = futures::future::try_join3(
+        futures::future::try_join_all(entries.iter().map(|entry| market.get_agreement(&entry.id))),
+        payment.get_debit_notes::<Utc>(None, None),
+        workload::payments(cmd),
+    )
+    .await?;
+    let usage = usage_vectors(debit_notes);
+
+    Ok(entries
+        .into_iter()
+        .zip(agreements)
+        .map(|(entry, agreement)| summarize(entry, &agreement, &usage, &payments))
+        .collect())
+}
+
+fn summarize(
+    entry: AgreementEntry,
+    agreement: &Agreement,
+    usage: &HashMap<String, Vec<f64>>,
+    payments: &HashMap<String, AgreementPayments>,
+) -> AgreementSummary {
+    let names = property(&agreement.offer.properties, "golem.com.usage.vector")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let usage = names
+        .into_iter()
+        .zip(usage.get(&entry.id).cloned().unwrap_or_default())
+        .map(|(name, value)| (name.to_string(), value))
+        .collect::<BTreeMap<_, _>>();
+    let duration = usage
+        .get(DURATION_COUNTER)
+        .map(|secs| *secs as i64)
+        .or_else(|| {
+            agreement
+                .approved_date
+                .filter(|_| entry.state == Some(AgreementState::Approved))
+                .map(|approved| (Utc::now() - approved).num_seconds())
+        });
+    AgreementSummary {
+        requestor_id: agreement.demand.requestor_id.to_string(),
+        state: entry.state,
+        created: entry.created.unwrap_or(agreement.timestamp),
+        duration,
+        usage,
+        invoiced: payments
+            .get(&entry.id)
+            .map(|payments| payments.invoiced.clone())
+            .unwrap_or_default(),
+        agreement_id: entry.id,
+    }
+}
+
+fn flatten(prefix: &str, value: &Value, properties: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            for (name, value) in map {
+                let name = match prefix {
+                    "" => name.clone(),
+                    prefix => format!("{}.{}", prefix, name),
+                };
+                flatten(&name, value, properties);
+            }
+        }
+        value => {
+            properties.insert(prefix.to_string(), value.clone());
+        }
+    }
+}
+
+fn flat_properties(value: &Value) -> BTreeMap<String, Value> {
+    let mut properties = BTreeMap::new();
+    flatten("", value, &mut properties);
+    properties
+}
+
+pub async fn show(cmd: &YaCommand, agreement_id: &str) -> Result<AgreementDetail> {
+    let app_key = appkey::get_app_key(cmd).await?;
+    let client = WebClient::with_token(&app_key);
+    let market: MarketProviderApi = client.interface()?;
+    let payment_api: PaymentApi = client.interface()?;
+
+    let agreement = market
+        .get_agreement(agreement_id)
+        .await
+        .map_err(|e| anyhow!(tr!("agreements-not-found", id = agreement_id, error = e)))?;
+    let (debit_notes, invoices, payments) = futures::future::try_join3(
+        payment_api.get_debit_notes::<Utc>(None, None),
+        payment_api.get_invoices::<Utc>(None, None),
+        payment_api.get_payments::<Utc>(None, None),
+    )
+    .await?;
+    let mut debit_notes = debit_notes
+        .into_iter()
+        .filter(|note| note.agreement_id == agreement_id)
+        .collect::<Vec<_>>();
+    debit_notes.sort_by_key(|note| note.timestamp);
+    let invoices = invoices
+        .into_iter()
+        .filter(|invoice| invoice.agreement_id == agreement_id)
+        .collect::<Vec<_>>();
+
+    let activities = debit_notes
+        .iter()
+        .map(|note| note.activity_id.as_str())
+        .chain(
+            invoices
+                .iter()
+                .flat_map(|invoice| invoice.activity_ids.iter().map(String::as_str)),
+        )
+        .collect::<HashSet<_>>();
+    let payments = payments
+        .into_iter()
+        .filter_map(|payment| {
+            let agreement_paid = payment
+                .agreement_payments
+                .iter()
+                .filter(|paid| paid.agreement_id == agreement_id)
+                .map(|paid| &paid.amount);
+            let activities_paid = payment
+                .activity_payments
+                .iter()
+                .filter(|paid| activities.contains(paid.activity_id.as_str()))
+                .map(|paid| &paid.amount);
+            let mut amounts = agreement_paid.chain(activities_paid).peekable();
+            amounts.peek()?;
+            let amount = amounts.fold(BigDecimal::default(), |sum, amount| sum + amount);
+            Some(AgreementPayment {
+                payment_id: payment.payment_id,
+                timestamp: payment.timestamp,
+                amount,
+                payment_platform: payment.payment_platform,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut invoiced = AgreementPayments::default();
+    for invoice in &invoices {
+        invoiced.invoiced += invoice.amount.clone();
+        if matches!(invoice.status, DocumentStatus::Settled) {
+            invoiced.confirmed += invoice.amount.clone();
+        }
+    }
+    let entry = AgreementEntry {
+        id: agreement.agreement_id.clone(),
+        role: None,
+        state: format!("{:?}", agreement.state).to_lowercase().parse().ok(),
+        created: Some(agreement.timestamp),
+    };
+    let summary = summarize(
+        entry,
+        &agreement,
+        &usage_vectors(debit_notes.clone()),
+        &HashMap::from([(agreement.agreement_id.clone(), invoiced)]),
+    );
+    Ok(AgreementDetail {
+        summary,
+        demand: flat_properties(&agreement.demand.properties),
+        offer: flat_properties(&agreement.offer.properties),
+        debit_notes,
+        invoices,
+        payments,
+    })
+}
+
+/// Counters without their "golem.usage." prefix, eg. "cpu_sec=12.5 duration_sec=30".
+fn usage_line(usage: &BTreeMap<String, f64>) -> String {
+    usage
+        .iter()
+        .map(|(name, value)| {
+            let name = name.strip_prefix("golem.usage.").unwrap_or(name);
+            format!("{}={:.1}", name, value)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn agreements_table(agreements: &[AgreementSummary]) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    table.set_titles(row![
+        tr!("agreements-id"),
+        tr!("agreements-requestor"),
+        tr!("agreements-state"),
+        tr!("agreements-created"),
+        tr!("agreements-duration"),
+        tr!("agreements-usage"),
+        tr!("agreements-invoiced"),
+    ]);
+    let now = Utc::now();
+    for agreement in agreements {
+        let optional = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+        table.add_row(row![
+            agreement.agreement_id,
+            agreement.requestor_id,
+            optional(agreement.state.map(|state| state.to_string())),
+            format_relative(agreement.created, now),
+            r->optional(agreement.duration.map(|secs| format_duration(Duration::seconds(secs)))),
+            usage_line(&agreement.usage),
+            r->display::glm(&agreement.invoiced),
+        ]);
+    }
+    table
+}
+
+fn properties_table(title: String, properties: &BTreeMap<String, Value>) -> Table {
+    let mut table = section_table(title);
+    for (name, value) in properties {
+        let value = match value {
+            Value::String(s) => s.clone(),
+            value => value.to_string(),
+        };
+        table.add_row(row![name, value]);
+    }
+    table
+}
+
+fn detail_tables(detail: &AgreementDetail) -> Vec<Table> {
+    let summary = &detail.summary;
+    let now = Utc::now();
+    let dash = || "-".to_string();
+
+    let mut overview = section_table(tr!("agreements-title", id = summary.agreement_id));
+    overview.add_row(row![tr!("agreements-requestor"), summary.requestor_id]);
+    overview.add_row(row![
+        tr!("agreements-state"),
+        summary
+            .state
+            .map(|state| state.to_string())
+            .unwrap_or_else(dash)
+    ]);
+    overview.add_row(row![
+        tr!("agreements-created"),
+        format_relative(summary.created, now)
+    ]);
+    overview.add_row(row![
+        tr!("agreements-duration"),
+        summary
+            .duration
+            .map(|secs| format_duration(Duration::seconds(secs)))
+            .unwrap_or_else(dash)
+    ]);
+    for (name, value) in &summary.usage {
+        overview.add_row(row![name, format!("{:.3}", value)]);
+    }
+    overview.add_row(row![
+        tr!("agreements-invoiced"),
+        display::glm(&summary.invoiced)
+    ]);
+
+    let mut debit_notes = section_table(tr!("agreements-debit-notes"));
+    if detail.debit_notes.is_empty() {
+        debit_notes.add_row(row![tr!("agreements-none")]);
+    }
+    for note in &detail.debit_notes {
+        debit_notes.add_row(row![
+            format_relative(note.timestamp, now),
+            note.activity_id,
+            r->display::glm(&note.total_amount_due),
+            format!("{:?}", note.status)
+        ]);
+    }
+
+    let mut invoices = section_table(tr!("agreements-invoices"));
+    if detail.invoices.is_empty() {
+        invoices.add_row(row![tr!("agreements-none")]);
+    }
+    for invoice in &detail.invoices {
+        invoices.add_row(row![
+            format_relative(invoice.timestamp, now),
+            invoice.invoice_id,
+            r->display::glm(&invoice.amount),
+            format!("{:?}", invoice.status)
+        ]);
+    }
+
+    let mut payments = section_table(tr!("agreements-payments"));
+    if detail.payments.is_empty() {
+        payments.add_row(row![tr!("agreements-none")]);
+    }
+    for payment in &detail.payments {
+        payments.add_row(row![
+            format_relative(payment.timestamp, now),
+            payment.payment_id,
+            r->display::glm(&payment.amount),
+            payment.payment_platform
+        ]);
+    }
+
+    vec![
+        overview,
+        properties_table(tr!("agreements-offer"), &detail.offer),
+        properties_table(tr!("agreements-demand"), &detail.demand),
+        debit_notes,
+        invoices,
+        payments,
+    ]
+}
+
+pub async fn run(
+    options: &CommandOptions,
+    command: AgreementsCommand,
+) -> Result</*exit code*/ i32> {
+    let cmd = YaCommand::new(options)?;
+    match command {
+        AgreementsCommand::List { state, since, json } => {
+            let mut agreements = list(&cmd, state, since).await?;
+            agreements.sort_by(|a, b| b.created.cmp(&a.created));
+            if json {
+                println!("{}", serde_json::to_string_pretty(&agreements)?);
+            } else if agreements.is_empty() {
+                println!("{}", tr!("agreements-none"));
+            } else {
+                agreements_table(&agreements).printstd();
+            }
+            Ok(0)
+        }
+        AgreementsCommand::Show { agreement_id, json } => {
+            let detail = show(&cmd, &agreement_id).await?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&detail)?);
+            } else {
+                for table in detail_tables(&detail) {
+                    table.printstd();
+                }
+            }
+            Ok(0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn ago(since: &str) -> Duration {
+        Utc::now() - since.parse::<Since>().unwrap().0
+    }
+
+    #[test]
+    fn since_relative() {
+        let minutes = ago("30m");
+        assert!(minutes >= Duration::minutes(30) && minutes < Duration::minutes(31));
+        let minutes = ago(" 45min ");
+        assert!(minutes >= Duration::minutes(45) && minutes < Duration::minutes(46));
+        let days = ago("7d");
+        assert!(days >= Duration::days(7) && days < Duration::days(7) + Duration::minutes(1));
+        let weeks = ago("2w");
+        assert!(weeks >= Duration::weeks(2) && weeks < Duration::weeks(2) + Duration::minutes(1));
+    }
+
+    #[test]
+    fn since_dates() {
+        let day = "2021-06-01".parse::<Since>().unwrap().0;
+        assert_eq!(day, Utc.ymd(2021, 6, 1).and_hms(0, 0, 0));
+        let ts = "2021-06-01T12:30:00+02:00".parse::<Since>().unwrap().0;
+        assert_eq!(ts, Utc.ymd(2021, 6, 1).and_hms(10, 30, 0));
+    }
+
+    #[test]
+    fn since_invalid() {
+        for bad in &["", "30", "m", "30s", "7 days", "-7d", "2021-13-01"] {
+            assert_eq!(
+                bad.parse::<Since>().unwrap_err().to_string(),
+                tr!("since-invalid", value = bad.trim()),
+                "{:?}",
+                bad
+            );
+        }
+        assert_eq!(
+            "99999999999999w".parse::<Since>().unwrap_err().to_string(),
+            tr!("since-too-early", value = "99999999999999w")
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! Allocations reserving GLM for the tasks the node orders as a requestor
+
+use anyhow::Result;
+use chrono::Utc;
+use prettytable::{format, row, Table};
+use structopt::StructOpt;
+
+use ya_client::payment::PaymentApi;
+use ya_client::web::WebClient;
+
+use crate::appkey;
+use crate::command::{CommandOptions, YaCommand};
+use crate::display::{self, format_duration};
+use crate::requestor::{self, AllocationSummary};
+
+#[derive(StructOpt)]
+pub struct AllocationsCommand {
+    /// Print the allocations as JSON instead of a table
+    #[structopt(long)]
+    json: bool,
+}
+
+fn allocations_table(allocations: &[AllocationSummary]) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    table.set_titles(row![
+        tr!("allocations-id"),
+        tr!("allocations-platform"),
+        tr!("allocations-total"),
+        tr!("allocations-remaining"),
+        tr!("allocations-timeout"),
+    ]);
+    let now = Utc::now();
+    for allocation in allocations {
+        let timeout = match allocation.timeout {
+            Some(timeout) if timeout > now => {
+                tr!(
+                    "allocations-expires-in",
+                    left = format_duration(timeout - now)
+                )
+            }
+            Some(_) => tr!("allocations-expired"),
+            None => "-".to_string(),
+        };
+        table.add_row(row![
+            allocation.allocation_id,
+            allocation.payment_platform,
+            r->display::glm(&allocation.total),
+            r->display::glm(&allocation.remaining),
+            timeout,
+        ]);
+    }
+    table
+}
+
+pub async fn run(options: &CommandOptions, args: AllocationsCommand) -> Result</*exit code*/ i32> {
+    let cmd = YaCommand::new(options)?;
+    let app_key = appkey::get_app_key(&cmd).await?;
+    let payment_api: PaymentApi = WebClient::with_token(&app_key).interface()?;
+    let mut allocations = requestor::allocations(&payment_api).await?;
+    allocations.sort_by(|a, b| a.timeout.cmp(&b.timeout));
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&allocations)?);
+        return Ok(0);
+    }
+    if allocations.is_empty() {
+        println!("{}", tr!("allocations-none"));
+        return Ok(0);
+    }
+    allocations_table(&allocations).printstd();
+    let now = Utc::now();
+    for warning in allocations
+        .iter()
+        .filter_map(|allocation| allocation.warning(now))
+    {
+        eprintln!("{}", warning);
+    }
+    Ok(0)
+}
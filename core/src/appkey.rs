@@ -0,0 +1,78 @@
+use crate::command::{remember_secret, YaCommand};
+use crate::utils::move_string_out_of_json;
+use anyhow::Result;
+
+/// Name of the app key yagna-usd authenticates with, shared with golemsp.
+pub const APP_KEY_NAME: &str = "golem-cli";
+
+pub struct AppKey {
+    pub name: String,
+    key: String,
+}
+
+impl AppKey {
+    /// The key with all but its first and last 4 characters hidden, safe to show.
+    pub fn masked(&self) -> String {
+        let chars = self.key.chars().collect::<Vec<_>>();
+        if chars.len() < 12 {
+            return "*".repeat(chars.len());
+        }
+        let head: String = chars[..4].iter().collect();
+        let tail: String = chars[chars.len() - 4..].iter().collect();
+        format!("{}…{}", head, tail)
+    }
+
+    /// The full key, only for showing on explicit request.
+    pub fn reveal(&self) -> &str {
+        &self.key
+    }
+}
+
+fn appkey_from_json_as_in_list(mut value: serde_json::Value) -> Option<AppKey> {
+    Some(AppKey {
+        name: move_string_out_of_json(value.get_mut(0)?.take())?,
+        key: move_string_out_of_json(value.get_mut(1)?.take())?,
+    })
+}
+
+fn get_existing_key_from_output(mut command_output: serde_json::Value) -> Option<AppKey> {
+    let mut keys = command_output.get_mut("values")?.take();
+    let keys = keys.as_array_mut()?;
+    let mut keys = keys
+        .drain(..)
+        .filter_map(appkey_from_json_as_in_list)
+        .collect::<Vec<_>>();
+    for appkey in &keys {
+        remember_secret(&appkey.key);
+    }
+
+    keys.drain(..).find(|appkey| appkey.name == APP_KEY_NAME)
+}
+
+/// App key used for the REST API when it exists, never creating one.
+pub async fn get_existing_key(cmd: &YaCommand) -> Result<Option<AppKey>> {
+    let keys = cmd.yagna()?.app_keys().await?;
+    Ok(get_existing_key_from_output(keys))
+}
+
+/// App key used for the REST API, created when there is none yet.
+pub async fn get_active(cmd: &YaCommand) -> Result<AppKey> {
+    let app_key = match get_existing_key(cmd).await? {
+        Some(app_key) => app_key,
+        None => AppKey {
+            name: APP_KEY_NAME.to_string(),
+            key: cmd.yagna()?.create_app_key(APP_KEY_NAME).await?,
+        },
+    };
+    remember_secret(&app_key.key);
+    tracing::debug!(
+        "authenticating with app key {} ({})",
+        app_key.name,
+        app_key.masked()
+    );
+    Ok(app_key)
+}
+
+pub async fn get_app_key(cmd: &YaCommand) -> Result<String> {
+    Ok(get_active(cmd).await?.key)
+}
@@ -0,0 +1,266 @@
+//! Benchmarks of the resources the node offers
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+
+use crate::display::TimeDisplay;
+use crate::history::{self, Entry};
+use crate::http;
+use crate::utils::yagna_datadir;
+
+const NET_HISTORY: &str = "bench-net";
+const MACHINE_HISTORY: &str = "bench-machine";
+
+/// Bytes hashed by each thread in the CPU benchmark.
+const CPU_BYTES: usize = 256 * MB;
+/// Size of the buffer copied in the memory benchmark and how many times it is copied.
+const MEMORY_BYTES: usize = 256 * MB;
+const MEMORY_ROUNDS: usize = 8;
+/// Size of the file written in the disk benchmark.
+const DISK_BYTES: usize = 512 * MB;
+const CHUNK: usize = MB;
+const MB: usize = 1_000_000;
+
+#[derive(StructOpt)]
+pub enum BenchCommand {
+    /// Measure download and upload throughput
+    Net {
+        /// URL returning a large response body
+        #[structopt(
+            long,
+            env = "YAGNA_USD_BENCH_DOWNLOAD_URL",
+            default_value = "https://speed.cloudflare.com/__down?bytes=25000000"
+        )]
+        download_url: String,
+        /// URL accepting POST requests with arbitrary body
+        #[structopt(
+            long,
+            env = "YAGNA_USD_BENCH_UPLOAD_URL",
+            default_value = "https://speed.cloudflare.com/__up"
+        )]
+        upload_url: String,
+        /// Size of the uploaded body in MB
+        #[structopt(long, default_value = "10")]
+        upload_size: usize,
+    },
+    /// Measure CPU, memory and disk throughput
+    Machine {
+        /// Directory the disk benchmark writes to (the yagna data directory by default)
+        #[structopt(long, parse(from_os_str))]
+        dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct NetBench {
+    pub download_mbps: f64,
+    pub upload_mbps: f64,
+}
+
+/// Scores of the machine benchmark, all in MB/s.
+#[derive(Serialize, Deserialize)]
+pub struct MachineBench {
+    /// SHA-256 throughput of a single thread
+    pub cpu_single: f64,
+    /// SHA-256 throughput of all threads together
+    pub cpu_multi: f64,
+    pub threads: usize,
+    /// Memory copy bandwidth, counting both reads and writes
+    pub memory: f64,
+    /// Sequential write to the yagna data directory, synced to disk
+    pub disk_write: f64,
+}
+
+/// Result of the last `bench machine` run.
+pub fn latest_machine() -> Option<Entry<MachineBench>> {
+    history::load(MACHINE_HISTORY)
+        .map_err(|e| tracing::debug!("failed to load machine benchmarks: {:?}", e))
+        .ok()?
+        .pop()
+}
+
+pub async fn run(command: BenchCommand) -> Result</*exit code*/ i32> {
+    match command {
+        BenchCommand::Net {
+            download_url,
+            upload_url,
+            upload_size,
+        } => {
+            let previous = history::load::<NetBench>(NET_HISTORY)?.pop();
+            let result = tokio::task::spawn_blocking(move || {
+                bench_net(&download_url, &upload_url, upload_size * 1_000_000)
+            })
+            .await??;
+
+            println!(
+                "{:>10}  {:.1} Mbit/s",
+                tr!("bench-download"),
+                result.download_mbps
+            );
+            println!(
+                "{:>10}  {:.1} Mbit/s",
+                tr!("bench-upload"),
+                result.upload_mbps
+            );
+            if let Some(previous) = previous {
+                println!(
+                    "\n{}",
+                    tr!(
+                        "bench-net-previous",
+                        date = TimeDisplay::local(Utc::now()).render_absolute(previous.ts),
+                        download = format!("{:.1}", previous.record.download_mbps),
+                        upload = format!("{:.1}", previous.record.upload_mbps)
+                    )
+                );
+            }
+            history::append(NET_HISTORY, result)?;
+            Ok(0)
+        }
+        BenchCommand::Machine { dir } => {
+            let dir = dir
+                .or_else(yagna_datadir)
+                .ok_or_else(|| anyhow!("unable to determine yagna data directory"))?;
+            let previous = latest_machine();
+            let result = tokio::task::spawn_blocking(move || bench_machine(&dir)).await??;
+
+            let was = |value: Option<f64>| match value {
+                Some(value) => tr!("bench-was", value = format!("{:.0}", value)),
+                None => String::new(),
+            };
+            let scores = [
+                (
+                    tr!("bench-cpu-single"),
+                    result.cpu_single,
+                    previous.as_ref().map(|p| p.record.cpu_single),
+                ),
+                (
+                    tr!("bench-cpu-multi", threads = result.threads),
+                    result.cpu_multi,
+                    previous.as_ref().map(|p| p.record.cpu_multi),
+                ),
+                (
+                    tr!("bench-memory"),
+                    result.memory,
+                    previous.as_ref().map(|p| p.record.memory),
+                ),
+                (
+                    tr!("bench-disk-write"),
+                    result.disk_write,
+                    previous.as_ref().map(|p| p.record.disk_write),
+                ),
+            ];
+            for (label, value, previous) in scores {
+                println!("{:>16}  {:>8.0} MB/s  {}", label, value, was(previous));
+            }
+            history::append(MACHINE_HISTORY, result)?;
+            Ok(0)
+        }
+    }
+}
+
+fn bench_net(download_url: &str, upload_url: &str, upload_bytes: usize) -> Result<NetBench> {
+    let started = Instant::now();
+    let mut body = http::get(download_url)?
+        .call()
+        .map_err(http::explain)?
+        .into_reader();
+    let downloaded = io::copy(&mut body, &mut io::sink())?;
+    let download_mbps = megabits_per_sec(downloaded as usize, started.elapsed().as_secs_f64());
+
+    let payload = vec![0u8; upload_bytes];
+    let started = Instant::now();
+    let mut response = http::post(upload_url)?
+        .send_bytes(&payload)
+        .map_err(http::explain)?
+        .into_reader();
+    io::copy(&mut response, &mut io::sink())?;
+    let upload_mbps = megabits_per_sec(upload_bytes, started.elapsed().as_secs_f64());
+
+    Ok(NetBench {
+        download_mbps,
+        upload_mbps,
+    })
+}
+
+fn megabits_per_sec(bytes: usize, secs: f64) -> f64 {
+    (bytes as f64 * 8.0) / 1_000_000.0 / secs.max(f64::EPSILON)
+}
+
+fn bench_machine(dir: &Path) -> Result<MachineBench> {
+    let threads = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+
+    let started = Instant::now();
+    hash(CPU_BYTES);
+    let cpu_single = megabytes_per_sec(CPU_BYTES, started.elapsed());
+
+    let started = Instant::now();
+    let workers = (0..threads)
+        .map(|_| thread::spawn(|| hash(CPU_BYTES)))
+        .collect::<Vec<_>>();
+    for worker in workers {
+        worker
+            .join()
+            .map_err(|_| anyhow!("benchmark thread panicked"))?;
+    }
+    let cpu_multi = megabytes_per_sec(CPU_BYTES * threads, started.elapsed());
+
+    Ok(MachineBench {
+        cpu_single,
+        cpu_multi,
+        threads,
+        memory: bench_memory(),
+        disk_write: bench_disk(dir)?,
+    })
+}
+
+fn hash(bytes: usize) -> u8 {
+    let chunk = vec![0x5a; CHUNK];
+    let mut hasher = Sha256::new();
+    for _ in 0..bytes / CHUNK {
+        hasher.update(&chunk);
+    }
+    hasher.finalize()[0]
+}
+
+fn bench_memory() -> f64 {
+    let src = vec![1u8; MEMORY_BYTES];
+    let mut dst = vec![0u8; MEMORY_BYTES];
+    let started = Instant::now();
+    for round in 0..MEMORY_ROUNDS {
+        dst.copy_from_slice(&src);
+        dst[round] = 0;
+    }
+    megabytes_per_sec(2 * MEMORY_BYTES * MEMORY_ROUNDS, started.elapsed())
+}
+
+fn bench_disk(dir: &Path) -> Result<f64> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(".yagna-usd-bench.tmp");
+    let chunk = vec![0xa5; CHUNK];
+    let started = Instant::now();
+    let written = (|| -> io::Result<()> {
+        let mut file = File::create(&path)?;
+        for _ in 0..DISK_BYTES / CHUNK {
+            file.write_all(&chunk)?;
+        }
+        file.sync_all()
+    })();
+    let elapsed = started.elapsed();
+    let _ = fs::remove_file(&path);
+    written.map_err(|e| anyhow!("writing {}: {}", path.display(), e))?;
+    Ok(megabytes_per_sec(DISK_BYTES, elapsed))
+}
+
+fn megabytes_per_sec(bytes: usize, elapsed: Duration) -> f64 {
+    bytes as f64 / MB as f64 / elapsed.as_secs_f64().max(f64::EPSILON)
+}
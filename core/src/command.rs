@@ -0,0 +1,370 @@
+//! Subcommand execution handling
+
+use anyhow::anyhow;
+use directories::BaseDirs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::rc::Rc;
+use std::time::Duration;
+use std::{env, fs, io};
+use structopt::StructOpt;
+use tokio::process::Command;
+use tokio::time;
+
+mod install;
+mod provider;
+mod runner;
+mod yagna;
+
+pub use install::*;
+pub use provider::*;
+pub use runner::*;
+pub use yagna::*;
+
+/// Delay before the first retry, doubled on every subsequent attempt.
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+/// Longest delay between two attempts, however many retries are allowed.
+const MAX_BACKOFF: Duration = Duration::from_secs(8);
+
+/// Options controlling how yagna and ya-provider subcommands are executed
+#[derive(StructOpt, Clone, Debug)]
+pub struct CommandOptions {
+    /// Timeout of a single yagna invocation in seconds
+    #[structopt(
+        long,
+        env = "YAGNA_USD_CMD_TIMEOUT",
+        default_value = "10",
+        global = true
+    )]
+    pub cmd_timeout: u64,
+    /// How many times a failed or timed out read-only yagna invocation is retried,
+    /// invocations changing state are run once
+    #[structopt(
+        long,
+        env = "YAGNA_USD_CMD_RETRIES",
+        default_value = "2",
+        global = true
+    )]
+    pub cmd_retries: u32,
+    /// Path of the yagna binary (looked up next to yagna-usd or on PATH by default)
+    #[structopt(long, env = "YAGNA_USD_YAGNA_BIN", parse(from_os_str), global = true)]
+    pub yagna_bin: Option<PathBuf>,
+    /// Path of the ya-provider binary (looked up next to yagna-usd or on PATH by default)
+    #[structopt(
+        long,
+        env = "YAGNA_USD_PROVIDER_BIN",
+        parse(from_os_str),
+        global = true
+    )]
+    pub provider_bin: Option<PathBuf>,
+    /// Data directory of yagna, passed to every yagna invocation
+    #[structopt(long, env = "YAGNA_DATADIR", parse(from_os_str), global = true)]
+    pub datadir: Option<PathBuf>,
+    /// Data directory of ya-provider, passed to every ya-provider invocation
+    #[structopt(long, env = "DATA_DIR", parse(from_os_str), global = true)]
+    pub provider_datadir: Option<PathBuf>,
+    /// Directory with exe-unit descriptors (detected per platform by default)
+    #[structopt(long, env = "YAGNA_USD_PLUGINS_DIR", parse(from_os_str), global = true)]
+    pub plugins_dir: Option<PathBuf>,
+    /// Glob of exe-unit descriptors, relative to the plugins directory unless absolute
+    #[structopt(
+        long,
+        env = "YAGNA_USD_EXE_UNIT_GLOB",
+        default_value = "ya-*.json",
+        global = true
+    )]
+    pub exe_unit_glob: String,
+    /// Replay yagna and ya-provider outputs recorded with --record instead of running them
+    #[structopt(
+        long,
+        env = "YAGNA_USD_REPLAY",
+        parse(from_os_str),
+        conflicts_with = "record",
+        global = true
+    )]
+    pub replay: Option<PathBuf>,
+    /// Record outputs of yagna and ya-provider invocations to a file
+    #[structopt(long, env = "YAGNA_USD_RECORD", parse(from_os_str), global = true)]
+    pub record: Option<PathBuf>,
+    /// Seconds outputs of read-only yagna and ya-provider invocations are reused for,
+    /// across yagna-usd runs (0 disables the cache)
+    #[structopt(long, env = "YAGNA_USD_CACHE_TTL", default_value = "3", global = true)]
+    pub cache_ttl: u64,
+    /// Debug logs with -v, plus the commands run and their raw output with -vv
+    #[structopt(short, long, parse(from_occurrences), global = true)]
+    pub verbose: u8,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ExecPolicy {
+    pub timeout: Duration,
+    pub retries: u32,
+    /// Print invocations and their raw output to stderr
+    pub echo: bool,
+}
+
+impl ExecPolicy {
+    /// The same policy without retries, for invocations which change state: a timed out
+    /// one may still have taken effect and running it again would apply it twice.
+    pub fn once(self) -> Self {
+        ExecPolicy { retries: 0, ..self }
+    }
+}
+
+impl From<&CommandOptions> for ExecPolicy {
+    fn from(options: &CommandOptions) -> Self {
+        ExecPolicy {
+            timeout: Duration::from_secs(options.cmd_timeout),
+            retries: options.cmd_retries,
+            echo: options.verbose >= 2,
+        }
+    }
+}
+
+/// Runs `cmd` to completion, killing it after `policy.timeout` and retrying failures
+/// with exponential backoff, capped at [`MAX_BACKOFF`].
+pub(crate) async fn output_with_retry(
+    cmd: &mut Command,
+    policy: ExecPolicy,
+) -> anyhow::Result<Output> {
+    cmd.kill_on_drop(true);
+    let mut attempt = 0;
+    loop {
+        tracing::debug!("Running: {:?}", cmd);
+        let err = match time::timeout(policy.timeout, cmd.output()).await {
+            Ok(Ok(output)) if output.status.success() => return Ok(output),
+            Ok(Ok(output)) => anyhow!(
+                "{:?} failed.: Stdout:\n{}\nStderr:\n{}",
+                cmd,
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            ),
+            Ok(Err(e)) if e.kind() == io::ErrorKind::NotFound => {
+                return Err(anyhow!("{:?} failed to start: {}", cmd, e))
+            }
+            Ok(Err(e)) => anyhow!("{:?} failed to start: {}", cmd, e),
+            Err(_) => anyhow!("{:?} timed out after {:?}", cmd, policy.timeout),
+        };
+        if attempt >= policy.retries {
+            return Err(err);
+        }
+        let backoff = 2u32
+            .checked_pow(attempt)
+            .and_then(|factor| BASE_BACKOFF.checked_mul(factor))
+            .map_or(MAX_BACKOFF, |backoff| backoff.min(MAX_BACKOFF));
+        tracing::debug!("{}; retrying in {:?}", err, backoff);
+        time::delay_for(backoff).await;
+        attempt += 1;
+    }
+}
+
+/// Directories the installers put exe-unit plugins into on this platform, most specific first.
+fn plugins_dir_candidates() -> Vec<PathBuf> {
+    let base_dirs = BaseDirs::new();
+    let mut candidates = vec![];
+    if cfg!(windows) {
+        if let Some(program_data) = env::var_os("ProgramData") {
+            candidates.push(PathBuf::from(program_data).join("yagna").join("plugins"));
+        }
+        if let Some(dirs) = &base_dirs {
+            candidates.push(dirs.data_dir().join("yagna").join("plugins"));
+            candidates.push(dirs.data_local_dir().join("yagna").join("plugins"));
+        }
+    } else if cfg!(target_os = "macos") {
+        if let Some(dirs) = &base_dirs {
+            candidates.push(dirs.data_dir().join("yagna").join("plugins"));
+            candidates.push(dirs.home_dir().join(".local/lib/yagna/plugins"));
+        }
+    } else {
+        if let Some(dirs) = &base_dirs {
+            candidates.push(dirs.home_dir().join(".local/lib/yagna/plugins"));
+        }
+        // system packages
+        candidates.push(PathBuf::from("/usr/lib/yagna/plugins"));
+    }
+    candidates
+}
+
+/// Where [`CachingRunner`] keeps the outputs of invocations.
+fn command_cache_dir() -> Option<PathBuf> {
+    crate::utils::project_dirs().map(|dirs| dirs.cache_dir().join("commands"))
+}
+
+/// First existing plugins directory of this platform.
+fn default_plugins_dir() -> Option<PathBuf> {
+    plugins_dir_candidates()
+        .into_iter()
+        .find(|dir| dir.is_dir())
+}
+
+pub struct YaCommand {
+    base_path: Option<Box<Path>>,
+    yagna_bin: Option<PathBuf>,
+    provider_bin: Option<PathBuf>,
+    datadir: Option<PathBuf>,
+    provider_datadir: Option<PathBuf>,
+    plugins_dir: Option<PathBuf>,
+    exe_unit_glob: String,
+    policy: ExecPolicy,
+    runner: Rc<dyn CommandRunner>,
+    replay: bool,
+}
+
+impl YaCommand {
+    pub fn new(options: &CommandOptions) -> anyhow::Result<Self> {
+        let policy = ExecPolicy::from(options);
+        let mut me = env::current_exe()?;
+
+        // find original binary path.
+        for _ in 0..5 {
+            if let Ok(base) = fs::read_link(&me) {
+                me = base;
+            } else {
+                break;
+            }
+        }
+
+        let base_path = me
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("Unable to resolve yagna binaries location"))?;
+
+        let base_path =
+            if !base_path.join("yagna").exists() || !base_path.join("ya-provider").exists() {
+                None
+            } else {
+                Some(base_path.into())
+            };
+
+        let runner: Rc<dyn CommandRunner> = match (&options.replay, &options.record) {
+            (Some(path), _) => Rc::new(ReplayRunner::load(path)?),
+            (None, Some(path)) => Rc::new(RecordingRunner::new(path.clone())?),
+            (None, None) => match command_cache_dir().filter(|_| options.cache_ttl > 0) {
+                Some(dir) => Rc::new(CachingRunner::new(
+                    Rc::new(ProcessRunner),
+                    dir,
+                    Duration::from_secs(options.cache_ttl),
+                )),
+                None => Rc::new(ProcessRunner),
+            },
+        };
+
+        Ok(Self {
+            base_path,
+            yagna_bin: options.yagna_bin.clone(),
+            provider_bin: options.provider_bin.clone(),
+            datadir: options.datadir.clone(),
+            provider_datadir: options.provider_datadir.clone(),
+            plugins_dir: options.plugins_dir.clone().or_else(default_plugins_dir),
+            exe_unit_glob: options.exe_unit_glob.clone(),
+            policy,
+            runner,
+            replay: options.replay.is_some(),
+        })
+    }
+
+    /// Outputs come from a recording, what they show didn't happen on this machine now.
+    pub fn is_replay(&self) -> bool {
+        self.replay
+    }
+
+    /// Binary set explicitly by the user for `program`.
+    fn override_path(&self, program: &str) -> Option<&Path> {
+        match program {
+            "yagna" => self.yagna_bin.as_deref(),
+            "ya-provider" => self.provider_bin.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Location of `program` as it will be spawned, if it can be found.
+    pub fn resolve(&self, program: &str) -> Option<PathBuf> {
+        if let Some(path) = self.override_path(program) {
+            return Some(path.to_path_buf());
+        }
+        let file_name = format!("{}{}", program, env::consts::EXE_SUFFIX);
+        match &self.base_path {
+            Some(path) => Some(path.join(&file_name)).filter(|path| path.exists()),
+            None => {
+                let path_dirs = env::var_os("PATH")
+                    .map(|paths| env::split_paths(&paths).collect::<Vec<_>>())
+                    .unwrap_or_default();
+                path_dirs
+                    .into_iter()
+                    .chain(install_dirs())
+                    .map(|dir| dir.join(&file_name))
+                    .find(|path| path.is_file())
+            }
+        }
+    }
+
+    /// How `program` was installed, if it can be found.
+    pub fn install_method(&self, program: &str) -> Option<InstallMethod> {
+        if self.override_path(program).is_some() {
+            return Some(InstallMethod::Custom);
+        }
+        if self.base_path.is_some() {
+            return Some(InstallMethod::Bundled);
+        }
+        self.resolve(program)
+            .map(|path| InstallMethod::detect(&path))
+    }
+
+    pub fn invocation(&self, program: &str) -> Invocation {
+        if let Some(path) = self.override_path(program) {
+            return Invocation::new(path);
+        }
+        match &self.base_path {
+            Some(path) => Invocation::new(path.join(program)),
+            None => match self.resolve(program) {
+                Some(path) => Invocation::new(path),
+                None => Invocation::new(program),
+            },
+        }
+    }
+
+    /// Value of EXE_UNIT_PATH passed to ya-provider.
+    fn exe_unit_path(&self) -> Option<PathBuf> {
+        let glob = Path::new(&self.exe_unit_glob);
+        if glob.is_absolute() {
+            return Some(glob.to_path_buf());
+        }
+        self.plugins_dir.as_ref().map(|dir| dir.join(glob))
+    }
+
+    pub fn ya_provider(&self) -> anyhow::Result<YaProviderCommand> {
+        let mut cmd = self.invocation("ya-provider");
+        if let Some(datadir) = &self.provider_datadir {
+            // ya-provider names the flag differently across versions, the env var is stable
+            cmd.env("DATA_DIR", datadir);
+        }
+
+        if let Some(exe_unit_path) = self.exe_unit_path() {
+            cmd.env("EXE_UNIT_PATH", exe_unit_path);
+        }
+
+        Ok(YaProviderCommand {
+            cmd,
+            policy: self.policy,
+            runner: self.runner.clone(),
+        })
+    }
+
+    /// Whether the yagna API accepts connections, replayed along with command outputs.
+    pub async fn is_yagna_running(&self) -> anyhow::Result<bool> {
+        self.runner.is_yagna_running().await
+    }
+
+    pub fn yagna(&self) -> anyhow::Result<YagnaCommand> {
+        let mut cmd = self.invocation("yagna");
+        if let Some(datadir) = &self.datadir {
+            cmd.env("YAGNA_DATADIR", datadir)
+                .arg("--datadir")
+                .arg(datadir);
+        }
+        Ok(YagnaCommand {
+            cmd,
+            policy: self.policy,
+            runner: self.runner.clone(),
+        })
+    }
+}
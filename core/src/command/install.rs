@@ -0,0 +1,74 @@
+//! Recognizing how yagna was installed
+
+use directories::BaseDirs;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InstallMethod {
+    /// Binary set with --yagna-bin / --provider-bin
+    Custom,
+    /// Next to the yagna-usd binary
+    Bundled,
+    /// golem installer (`~/.local/bin`)
+    Installer,
+    /// Snap package
+    Snap,
+    /// System package (deb, rpm, ...)
+    Package,
+    /// `cargo install`
+    Cargo,
+    Unknown,
+}
+
+impl InstallMethod {
+    pub fn describe(self) -> String {
+        match self {
+            InstallMethod::Custom => tr!("install-custom"),
+            InstallMethod::Bundled => tr!("install-bundled"),
+            InstallMethod::Installer => tr!("install-installer"),
+            InstallMethod::Snap => tr!("install-snap"),
+            InstallMethod::Package => tr!("install-package"),
+            InstallMethod::Cargo => tr!("install-cargo"),
+            InstallMethod::Unknown => tr!("install-unknown"),
+        }
+    }
+
+    /// Recognizes the install method from the location of a binary found on PATH
+    /// or in one of the [`install_dirs`].
+    pub fn detect(path: &Path) -> Self {
+        let base_dirs = BaseDirs::new();
+        let home = base_dirs.as_ref().map(|dirs| dirs.home_dir());
+        let in_home = |dir: &str| home.map(|home| path.starts_with(home.join(dir)));
+
+        if path.starts_with("/snap") || path.starts_with("/var/lib/snapd") {
+            InstallMethod::Snap
+        } else if in_home(".cargo/bin") == Some(true) {
+            InstallMethod::Cargo
+        } else if in_home(".local/bin") == Some(true) {
+            InstallMethod::Installer
+        } else if ["/usr/bin", "/usr/lib", "/opt"]
+            .iter()
+            .any(|dir| path.starts_with(dir))
+        {
+            InstallMethod::Package
+        } else {
+            InstallMethod::Unknown
+        }
+    }
+}
+
+/// Directories yagna is installed into when they are not on PATH, eg. for services
+/// started with a minimal environment.
+pub fn install_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![];
+    if let Some(base_dirs) = BaseDirs::new() {
+        let home = base_dirs.home_dir();
+        dirs.push(home.join(".local/bin"));
+        dirs.push(home.join(".cargo/bin"));
+    }
+    if cfg!(unix) {
+        dirs.push(PathBuf::from("/snap/bin"));
+        dirs.push(PathBuf::from("/usr/bin"));
+    }
+    dirs
+}
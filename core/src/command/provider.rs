@@ -0,0 +1,415 @@
+use anyhow::Context;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::{path::PathBuf, rc::Rc};
+use ya_core_model::NodeId;
+
+use super::{CommandRunner, ExecPolicy, Invocation, ResponseTable};
+use crate::address;
+
+pub struct YaProviderCommand {
+    pub(super) cmd: Invocation,
+    pub(super) policy: ExecPolicy,
+    pub(super) runner: Rc<dyn CommandRunner>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Preset {
+    pub name: String,
+    pub exeunit_name: String,
+    pub initial_price: f64,
+    pub usage_coeffs: UsageDef,
+}
+
+/// Subnet requestors send their demands to unless told otherwise
+pub const PUBLIC_SUBNET: &str = "public";
+
+#[derive(Deserialize, Serialize)]
+pub struct ProviderConfig {
+    pub node_name: Option<String>,
+    pub subnet: Option<String>,
+    pub account: Option<NodeId>,
+}
+
+impl ProviderConfig {
+    /// Nodes outside the public subnet only get tasks from requestors sending there.
+    pub fn is_public_subnet(&self) -> bool {
+        self.subnet
+            .as_deref()
+            .map_or(true, |subnet| subnet == PUBLIC_SUBNET)
+    }
+}
+
+pub type UsageDef = BTreeMap<String, f64>;
+
+/// Share of the machine offered to requestors, see `ya-provider profile`
+#[derive(Clone, Deserialize, Serialize)]
+pub struct ResourceProfile {
+    pub cpu_threads: usize,
+    pub mem_gib: f64,
+    pub storage_gib: f64,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RuntimeInfo {
+    pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub supervisor_path: Option<PathBuf>,
+    pub runtime_path: Option<PathBuf>,
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+}
+
+/// Domain payloads may connect to without a signed manifest
+#[derive(Deserialize, Serialize)]
+pub struct WhitelistEntry {
+    pub id: String,
+    pub pattern: String,
+    /// "strict", "wildcard" or "regex"
+    pub kind: String,
+}
+
+impl WhitelistEntry {
+    fn from_record(record: &HashMap<String, &serde_json::Value>) -> Option<Self> {
+        let text = |name: &str| record.get(name).and_then(|value| value.as_str());
+        Some(WhitelistEntry {
+            id: text("id").unwrap_or_default().to_string(),
+            pattern: text("pattern")?.to_string(),
+            kind: text("type").unwrap_or("strict").to_lowercase(),
+        })
+    }
+
+    pub fn matches(&self, domain: &str) -> bool {
+        match self.kind.as_str() {
+            "regex" => regex::Regex::new(&self.pattern)
+                .map(|re| re.is_match(domain))
+                .unwrap_or(false),
+            "wildcard" => {
+                let pattern = self.pattern.trim_start_matches("*.");
+                domain == pattern || domain.ends_with(&format!(".{}", pattern))
+            }
+            _ => domain == self.pattern,
+        }
+    }
+}
+
+/// Certificate trusted to sign manifests of audited payloads
+#[derive(Deserialize, Serialize)]
+pub struct KeystoreCert {
+    pub id: String,
+    pub subject: Option<String>,
+    pub not_after: Option<String>,
+}
+
+impl KeystoreCert {
+    fn from_record(record: &HashMap<String, &serde_json::Value>) -> Option<Self> {
+        let text = |name: &str| {
+            record
+                .get(name)
+                .and_then(|value| value.as_str())
+                .map(str::to_string)
+        };
+        Some(KeystoreCert {
+            id: text("id")?,
+            subject: text("subject"),
+            not_after: text("not after"),
+        })
+    }
+}
+
+/// Who payloads may reach the internet for, from `ya-provider rule list`
+#[derive(Deserialize, Serialize)]
+pub struct OutboundRules {
+    pub enabled: bool,
+    /// Mode for payloads of any requestor: "all", "none" or "whitelist"
+    pub everyone: String,
+    /// Modes of audited payloads by certificate
+    pub audited_payload: BTreeMap<String, String>,
+}
+
+impl OutboundRules {
+    fn from_value(value: &serde_json::Value) -> Self {
+        let outbound = &value["outbound"];
+        let audited_payload = outbound["audited-payload"]
+            .as_object()
+            .map(|certs| {
+                certs
+                    .iter()
+                    .filter_map(|(cert, rule)| {
+                        Some((cert.clone(), rule["mode"].as_str()?.to_string()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        OutboundRules {
+            enabled: outbound["enabled"].as_bool().unwrap_or(false),
+            everyone: outbound["everyone"]
+                .as_str()
+                .unwrap_or("none")
+                .to_lowercase(),
+            audited_payload,
+        }
+    }
+}
+
+impl YaProviderCommand {
+    async fn run(self) -> anyhow::Result<Vec<u8>> {
+        let policy = if self.cmd.is_read_only() {
+            self.policy
+        } else {
+            self.policy.once()
+        };
+        self.runner.run(&self.cmd, policy).await
+    }
+
+    pub async fn get_config(self) -> anyhow::Result<ProviderConfig> {
+        Ok(self.get_config_with_account().await?.0)
+    }
+
+    /// The config with its `account` as written, in its original casing.
+    pub async fn get_config_with_account(
+        mut self,
+    ) -> anyhow::Result<(ProviderConfig, Option<String>)> {
+        self.cmd.read_only().args(&["--json", "config", "get"]);
+        let output = self
+            .run()
+            .await
+            .context("failed to get ya-provider configuration")?;
+
+        let value: serde_json::Value =
+            serde_json::from_slice(output.as_slice()).context("parsing ya-provider config get")?;
+        let account_as_configured = value
+            .get("account")
+            .and_then(|account| account.as_str())
+            .map(str::to_string);
+        if let Some(account) = &account_as_configured {
+            if !address::is_well_formed(account) {
+                address::validate(account).context("ya-provider payment account")?;
+            }
+        }
+        let config: ProviderConfig =
+            serde_json::from_value(value).context("parsing ya-provider config get")?;
+        Ok((config, account_as_configured))
+    }
+
+    async fn run_json<T: DeserializeOwned>(mut self, args: &[&str]) -> anyhow::Result<T> {
+        self.cmd.arg("--json").args(args);
+        let output = self.run().await?;
+        serde_json::from_slice(&output)
+            .with_context(|| format!("parsing ya-provider {}", args.join(" ")))
+    }
+
+    /// Version of ya-provider, eg. "0.9.3" from `ya-provider 0.9.3 (a1b2c3d 2021-12-01 build #170)`.
+    pub async fn version(mut self) -> anyhow::Result<String> {
+        self.cmd.read_only().arg("--version");
+        let output = self.run().await?;
+        let output = String::from_utf8_lossy(&output);
+        output
+            .split_whitespace()
+            .nth(1)
+            .map(String::from)
+            .ok_or_else(|| anyhow::anyhow!("cannot parse ya-provider version {:?}", output))
+    }
+
+    /// Runtimes (exe-units) installed for ya-provider.
+    pub async fn exe_units(mut self) -> anyhow::Result<Vec<RuntimeInfo>> {
+        self.cmd.read_only();
+        self.run_json(&["exe-unit", "list"]).await
+    }
+
+    /// All presets defined in ya-provider, active or not.
+    pub async fn presets(mut self) -> anyhow::Result<Vec<Preset>> {
+        self.cmd.read_only();
+        self.run_json(&["preset", "list"]).await
+    }
+
+    /// Sets linear pricing coefficients (GLM per second) of an existing preset.
+    pub async fn update_preset_prices(
+        mut self,
+        name: &str,
+        cpu_sec: f64,
+        duration_sec: f64,
+        initial_price: f64,
+    ) -> anyhow::Result<()> {
+        self.cmd
+            .args(&["preset", "update", "--no-interactive", name, "--price"])
+            .arg(format!("CPU={}", cpu_sec))
+            .arg(format!("Duration={}", duration_sec))
+            .arg(format!("Init price={}", initial_price));
+        self.run().await?;
+        Ok(())
+    }
+
+    /// Changes the settings given, the others are kept.
+    pub async fn set_config(
+        mut self,
+        node_name: Option<&str>,
+        subnet: Option<&str>,
+        account: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.cmd.args(&["config", "set"]);
+        for (flag, value) in [
+            ("--node-name", node_name),
+            ("--subnet", subnet),
+            ("--account", account),
+        ] {
+            if let Some(value) = value {
+                self.cmd.arg(flag).arg(value);
+            }
+        }
+        self.run()
+            .await
+            .context("failed to set ya-provider configuration")?;
+        Ok(())
+    }
+
+    /// Publishes offers for the preset, from the next offer subscription on.
+    pub async fn activate_preset(mut self, name: &str) -> anyhow::Result<()> {
+        self.cmd.args(&["preset", "activate", name]);
+        self.run()
+            .await
+            .with_context(|| format!("failed to activate preset {}", name))?;
+        Ok(())
+    }
+
+    /// Stops publishing offers for the preset, which stays defined.
+    pub async fn deactivate_preset(mut self, name: &str) -> anyhow::Result<()> {
+        self.cmd.args(&["preset", "deactivate", name]);
+        self.run()
+            .await
+            .with_context(|| format!("failed to deactivate preset {}", name))?;
+        Ok(())
+    }
+
+    /// Resource profiles by name.
+    pub async fn profiles(mut self) -> anyhow::Result<BTreeMap<String, ResourceProfile>> {
+        self.cmd.read_only();
+        self.run_json(&["profile", "list"]).await
+    }
+
+    /// Name of the profile offers are made from.
+    pub async fn active_profile(mut self) -> anyhow::Result<String> {
+        self.cmd.read_only().args(&["profile", "active"]);
+        let output = self.run().await?;
+        Ok(String::from_utf8_lossy(&output)
+            .trim()
+            .trim_matches('"')
+            .to_string())
+    }
+
+    /// Changes the resources of profile `name`, the ones not given are kept.
+    pub async fn update_profile(
+        mut self,
+        name: &str,
+        cpu_threads: Option<usize>,
+        mem_gib: Option<f64>,
+        storage_gib: Option<f64>,
+    ) -> anyhow::Result<()> {
+        self.cmd.args(&["profile", "update", "--names", name]);
+        if let Some(threads) = cpu_threads {
+            self.cmd.arg("--cpu-threads").arg(threads.to_string());
+        }
+        if let Some(mem) = mem_gib {
+            self.cmd.arg("--mem-gib").arg(mem.to_string());
+        }
+        if let Some(storage) = storage_gib {
+            self.cmd.arg("--storage-gib").arg(storage.to_string());
+        }
+        self.run()
+            .await
+            .with_context(|| format!("failed to update profile {}", name))?;
+        Ok(())
+    }
+
+    /// Domains payloads of any requestor may reach when outbound runs in whitelist mode.
+    pub async fn whitelist(mut self) -> anyhow::Result<Vec<WhitelistEntry>> {
+        self.cmd.read_only();
+        let table: ResponseTable = self.run_json(&["whitelist", "list"]).await?;
+        Ok(table
+            .records()
+            .iter()
+            .filter_map(WhitelistEntry::from_record)
+            .collect())
+    }
+
+    /// Certificates of the keystore for audited payloads.
+    pub async fn keystore(mut self) -> anyhow::Result<Vec<KeystoreCert>> {
+        self.cmd.read_only();
+        let table: ResponseTable = self.run_json(&["keystore", "list"]).await?;
+        Ok(table
+            .records()
+            .iter()
+            .filter_map(KeystoreCert::from_record)
+            .collect())
+    }
+
+    /// Adds `patterns` of `kind` ("strict", "wildcard" or "regex") to the whitelist.
+    pub async fn whitelist_add(mut self, patterns: &[String], kind: &str) -> anyhow::Result<()> {
+        self.cmd.args(&["whitelist", "add", "--patterns"]);
+        self.cmd.args(patterns);
+        self.cmd.args(&["--type", kind]);
+        self.run()
+            .await
+            .context("failed to add whitelist entries")?;
+        Ok(())
+    }
+
+    pub async fn whitelist_remove(mut self, ids: &[String]) -> anyhow::Result<()> {
+        self.cmd.args(&["whitelist", "remove"]).args(ids);
+        self.run()
+            .await
+            .context("failed to remove whitelist entries")?;
+        Ok(())
+    }
+
+    /// Trusts the certificates of `path` (PEM or DER) to sign manifests.
+    pub async fn keystore_add(mut self, path: &std::path::Path) -> anyhow::Result<()> {
+        self.cmd.args(&["keystore", "add"]).arg(path);
+        self.run()
+            .await
+            .with_context(|| format!("failed to import {}", path.display()))?;
+        Ok(())
+    }
+
+    pub async fn keystore_remove(mut self, ids: &[String]) -> anyhow::Result<()> {
+        self.cmd.args(&["keystore", "remove"]).args(ids);
+        self.run().await.context("failed to remove certificates")?;
+        Ok(())
+    }
+
+    /// Sets the outbound mode for payloads of any requestor: "all", "none" or "whitelist".
+    pub async fn set_outbound_everyone(mut self, mode: &str) -> anyhow::Result<()> {
+        self.cmd
+            .args(&["rule", "set", "outbound", "everyone", "--mode", mode]);
+        self.run()
+            .await
+            .context("failed to set the outbound rule")?;
+        Ok(())
+    }
+
+    /// Turns outbound access of payloads on or off as a whole.
+    pub async fn set_outbound_enabled(mut self, enabled: bool) -> anyhow::Result<()> {
+        let state = if enabled { "enable" } else { "disable" };
+        self.cmd.args(&["rule", "set", "outbound", state]);
+        self.run()
+            .await
+            .context("failed to set the outbound rule")?;
+        Ok(())
+    }
+
+    pub async fn outbound_rules(mut self) -> anyhow::Result<OutboundRules> {
+        self.cmd.read_only();
+        let value: serde_json::Value = self.run_json(&["rule", "list"]).await?;
+        Ok(OutboundRules::from_value(&value))
+    }
+
+    /// Names of presets used by ya-provider for publishing offers.
+    pub async fn active_presets(mut self) -> anyhow::Result<Vec<String>> {
+        self.cmd.read_only();
+        self.run_json(&["preset", "active"]).await
+    }
+}
@@ -0,0 +1,551 @@
+//! Execution of prepared yagna and ya-provider invocations
+//!
+//! Subcommand wrappers only describe what to run ([`Invocation`]), a [`CommandRunner`]
+//! runs it. Besides spawning real processes ([`ProcessRunner`]), outputs can be recorded
+//! to a file ([`RecordingRunner`]) and replayed later without a live yagna ([`ReplayRunner`]).
+//! The check whether the yagna API answers is recorded and replayed along with them.
+//! Outputs of read-only invocations can also be reused for a few seconds ([`CachingRunner`]).
+//! Outputs of secret invocations are never echoed, recorded nor cached.
+
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, Utc};
+use futures::future::{FutureExt, LocalBoxFuture};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::rc::Rc;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::process::Command;
+use tracing::Instrument;
+
+use super::{output_with_retry, ExecPolicy};
+use crate::utils::{is_yagna_running, write_atomic_private};
+
+/// Key of the yagna API liveness check in recordings, with "true" or "false" as its output
+pub const YAGNA_RUNNING_KEY: &str = "yagna <api reachable>";
+
+/// Environment variables holding credentials, masked in echoed output.
+const SECRET_VARS: &[&str] = &["YAGNA_APPKEY"];
+
+lazy_static! {
+    /// `"key": "..."` fields of yagna's JSON output, eg. of `yagna app-key create --json`
+    static ref KEY_FIELD: Regex = Regex::new(r#"("key"\s*:\s*")[^"]+(")"#).unwrap();
+    /// `YAGNA_APPKEY=...` assignments, eg. of the environment in echoed command lines
+    static ref SECRET_ASSIGNMENT: Regex =
+        Regex::new(&format!(r"\b((?:{})=)[^\s]+", SECRET_VARS.join("|"))).unwrap();
+    /// App keys read from yagna so far
+    static ref KNOWN_SECRETS: Mutex<Vec<String>> = Mutex::new(vec![]);
+}
+
+/// Masks `secret` wherever it shows up in echoed output from now on.
+pub(crate) fn remember_secret(secret: &str) {
+    if secret.is_empty() {
+        return;
+    }
+    if let Ok(mut secrets) = KNOWN_SECRETS.lock() {
+        if !secrets.iter().any(|known| known == secret) {
+            secrets.push(secret.to_string());
+        }
+    }
+}
+
+/// Masks credentials in `text` before it is shown to the user: the app keys read from yagna,
+/// the `key` fields of JSON and `YAGNA_APPKEY`, both its value in our environment and any
+/// `YAGNA_APPKEY=...` assignment. Ids looking like keys, eg. of activities or invoices, are
+/// left alone.
+pub fn redact(text: &str) -> String {
+    let text = KEY_FIELD.replace_all(text, "${1}<redacted>${2}");
+    let mut text = SECRET_ASSIGNMENT
+        .replace_all(&text, "${1}<redacted>")
+        .into_owned();
+    let known = KNOWN_SECRETS
+        .lock()
+        .map(|secrets| secrets.clone())
+        .unwrap_or_default();
+    let from_env = SECRET_VARS
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .filter(|secret| !secret.is_empty());
+    for secret in known.into_iter().chain(from_env) {
+        text = text.replace(&secret, "<redacted>");
+    }
+    text
+}
+
+/// Program with its arguments and environment, ready to be run
+#[derive(Clone, Debug)]
+pub struct Invocation {
+    program: PathBuf,
+    args: Vec<OsString>,
+    envs: Vec<(OsString, OsString)>,
+    /// Only reads state, set by the wrapper building the invocation
+    read_only: bool,
+    /// Prints credentials, eg. app keys or the keystore
+    secret: bool,
+}
+
+impl Invocation {
+    pub fn new(program: impl Into<PathBuf>) -> Self {
+        Invocation {
+            program: program.into(),
+            args: vec![],
+            envs: vec![],
+            read_only: false,
+            secret: false,
+        }
+    }
+
+    /// Marks the invocation as one which doesn't change any state, so its output may be
+    /// reused. Invocations are taken to write unless marked.
+    pub fn read_only(&mut self) -> &mut Self {
+        self.read_only = true;
+        self
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Marks the invocation as one printing credentials: its output is never echoed, written
+    /// to recordings nor cached. Recordings made by hand may still hold it for replays.
+    pub fn secret(&mut self) -> &mut Self {
+        self.secret = true;
+        self
+    }
+
+    pub fn is_secret(&self) -> bool {
+        self.secret
+    }
+
+    pub fn arg(&mut self, arg: impl AsRef<OsStr>) -> &mut Self {
+        self.args.push(arg.as_ref().to_owned());
+        self
+    }
+
+    pub fn args<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        for arg in args {
+            self.arg(arg);
+        }
+        self
+    }
+
+    pub fn env(&mut self, key: impl AsRef<OsStr>, value: impl AsRef<OsStr>) -> &mut Self {
+        self.envs
+            .push((key.as_ref().to_owned(), value.as_ref().to_owned()));
+        self
+    }
+
+    /// Identifies the invocation in recordings, eg. "yagna payment status --json".
+    /// The environment, the location of the program and the `--datadir` are left out, so
+    /// recordings replay on machines keeping yagna's data elsewhere.
+    pub fn key(&self) -> String {
+        let program = self
+            .program
+            .file_stem()
+            .unwrap_or_else(|| self.program.as_os_str());
+        let mut parts = vec![program];
+        let mut args = self.args.iter().map(OsString::as_os_str);
+        while let Some(arg) = args.next() {
+            if arg == "--datadir" {
+                args.next();
+            } else {
+                parts.push(arg);
+            }
+        }
+        parts
+            .into_iter()
+            .map(|part| part.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Shell-like rendering of the invocation, eg. "DATA_DIR=/data /usr/bin/ya-provider preset list".
+    pub fn command_line(&self) -> String {
+        self.envs
+            .iter()
+            .map(|(key, value)| format!("{}={}", key.to_string_lossy(), value.to_string_lossy()))
+            .chain(std::iter::once(self.program.display().to_string()))
+            .chain(
+                self.args
+                    .iter()
+                    .map(|arg| arg.to_string_lossy().into_owned()),
+            )
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn command(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        command
+            .args(&self.args)
+            .envs(self.envs.iter().map(|(key, value)| (key, value)))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        command
+    }
+}
+
+/// Runs invocations and returns their stdout, failing on non zero exit codes
+pub trait CommandRunner {
+    fn run<'a>(
+        &'a self,
+        invocation: &'a Invocation,
+        policy: ExecPolicy,
+    ) -> LocalBoxFuture<'a, anyhow::Result<Vec<u8>>>;
+
+    /// Whether the yagna API accepts connections, by default checked on the live API.
+    fn is_yagna_running(&self) -> LocalBoxFuture<'_, anyhow::Result<bool>> {
+        is_yagna_running().boxed_local()
+    }
+}
+
+/// Spawns real processes
+pub struct ProcessRunner;
+
+impl CommandRunner for ProcessRunner {
+    fn run<'a>(
+        &'a self,
+        invocation: &'a Invocation,
+        policy: ExecPolicy,
+    ) -> LocalBoxFuture<'a, anyhow::Result<Vec<u8>>> {
+        let span = tracing::debug_span!("exec", cmd = %invocation.key());
+        async move {
+            if policy.echo {
+                eprintln!("$ {}", redact(&invocation.command_line()));
+            }
+            let result = output_with_retry(&mut invocation.command(), policy).await;
+            if policy.echo && invocation.is_secret() {
+                eprintln!("<output holding secrets not shown>");
+            } else if policy.echo {
+                match &result {
+                    Ok(output) => {
+                        eprint!("{}", redact(&String::from_utf8_lossy(&output.stdout)));
+                        eprint!("{}", redact(&String::from_utf8_lossy(&output.stderr)));
+                    }
+                    Err(e) => eprintln!("{}", redact(&format!("{:#}", e))),
+                }
+            }
+            Ok(result?.stdout)
+        }
+        .instrument(span)
+        .boxed_local()
+    }
+}
+
+/// Recorded outcome of an invocation
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Recording {
+    #[serde(default)]
+    pub stdout: String,
+    /// Failure message, when the invocation failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+type Recordings = BTreeMap<String, Recording>;
+
+fn load_recordings(path: &Path) -> anyhow::Result<Recordings> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("reading recordings {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("parsing recordings {}", path.display()))
+}
+
+/// Replays outputs recorded by [`RecordingRunner`], keyed by [`Invocation::key`]
+pub struct ReplayRunner {
+    recordings: Recordings,
+}
+
+impl ReplayRunner {
+    pub fn new(recordings: Recordings) -> Self {
+        ReplayRunner { recordings }
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        Ok(Self::new(load_recordings(path)?))
+    }
+}
+
+impl CommandRunner for ReplayRunner {
+    fn run<'a>(
+        &'a self,
+        invocation: &'a Invocation,
+        _policy: ExecPolicy,
+    ) -> LocalBoxFuture<'a, anyhow::Result<Vec<u8>>> {
+        let key = invocation.key();
+        let result = match self.recordings.get(&key) {
+            Some(Recording {
+                error: Some(error), ..
+            }) => Err(anyhow!("{}", error)),
+            Some(recording) => Ok(recording.stdout.clone().into_bytes()),
+            None => Err(anyhow!("no recorded output of `{}`", key)),
+        };
+        futures::future::ready(result).boxed_local()
+    }
+
+    fn is_yagna_running(&self) -> LocalBoxFuture<'_, anyhow::Result<bool>> {
+        let result = match self.recordings.get(YAGNA_RUNNING_KEY) {
+            Some(Recording {
+                error: Some(error), ..
+            }) => Err(anyhow!("{}", error)),
+            Some(recording) => Ok(recording.stdout.trim() == "true"),
+            // recorded before the check was: the API answered if yagna commands using it did
+            None => Ok(self.recordings.iter().any(|(key, recording)| {
+                key.starts_with("yagna ") && key != "yagna --version" && recording.error.is_none()
+            })),
+        };
+        futures::future::ready(result).boxed_local()
+    }
+}
+
+/// Runs real processes, saving their outputs to a file which [`ReplayRunner`] can load
+pub struct RecordingRunner {
+    path: PathBuf,
+    recordings: RefCell<Recordings>,
+}
+
+impl RecordingRunner {
+    /// Records into `path`, keeping outputs already recorded there.
+    pub fn new(path: PathBuf) -> anyhow::Result<Self> {
+        let recordings = if path.exists() {
+            load_recordings(&path)?
+        } else {
+            Recordings::new()
+        };
+        Ok(RecordingRunner {
+            path,
+            recordings: RefCell::new(recordings),
+        })
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        let content = serde_json::to_string_pretty(&*self.recordings.borrow())?;
+        fs::write(&self.path, content)
+            .with_context(|| format!("writing recordings {}", self.path.display()))
+    }
+}
+
+impl CommandRunner for RecordingRunner {
+    fn run<'a>(
+        &'a self,
+        invocation: &'a Invocation,
+        policy: ExecPolicy,
+    ) -> LocalBoxFuture<'a, anyhow::Result<Vec<u8>>> {
+        async move {
+            let result = ProcessRunner.run(invocation, policy).await;
+            if invocation.is_secret() {
+                return result;
+            }
+            let recording = match &result {
+                Ok(stdout) => Recording {
+                    stdout: String::from_utf8_lossy(stdout).into_owned(),
+                    error: None,
+                },
+                Err(e) => Recording {
+                    stdout: String::new(),
+                    error: Some(format!("{:#}", e)),
+                },
+            };
+            self.recordings
+                .borrow_mut()
+                .insert(invocation.key(), recording);
+            if let Err(e) = self.save() {
+                tracing::warn!("{:#}", e);
+            }
+            result
+        }
+        .boxed_local()
+    }
+
+    fn is_yagna_running(&self) -> LocalBoxFuture<'_, anyhow::Result<bool>> {
+        async move {
+            let result = is_yagna_running().await;
+            let recording = match &result {
+                Ok(running) => Recording {
+                    stdout: running.to_string(),
+                    error: None,
+                },
+                Err(e) => Recording {
+                    stdout: String::new(),
+                    error: Some(format!("{:#}", e)),
+                },
+            };
+            self.recordings
+                .borrow_mut()
+                .insert(YAGNA_RUNNING_KEY.to_string(), recording);
+            if let Err(e) = self.save() {
+                tracing::warn!("{:#}", e);
+            }
+            result
+        }
+        .boxed_local()
+    }
+}
+
+/// Output of an invocation kept by [`CachingRunner`]
+#[derive(Serialize, Deserialize)]
+struct CachedOutput {
+    stored: DateTime<Utc>,
+    /// [`Invocation::key`], for whoever looks into the cache directory
+    key: String,
+    stdout: String,
+}
+
+/// Reuses outputs of read-only invocations younger than `ttl`, so a status bar widget
+/// polling every couple of seconds doesn't spawn a yagna process per section each time.
+///
+/// Outputs are kept in files shared by all yagna-usd processes, failures and outputs of
+/// secret invocations are not cached.
+/// Any other invocation drops them all, as it may change what they show.
+pub struct CachingRunner {
+    inner: Rc<dyn CommandRunner>,
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl CachingRunner {
+    pub fn new(inner: Rc<dyn CommandRunner>, dir: PathBuf, ttl: Duration) -> Self {
+        CachingRunner { inner, dir, ttl }
+    }
+
+    /// File of the invocation, the environment and program path are part of the hash so
+    /// the outputs of different datadirs or binaries are kept apart.
+    fn path(&self, invocation: &Invocation) -> PathBuf {
+        // a hash stable across builds, the cache outlives the binary that wrote it
+        let digest = Sha256::digest(invocation.command_line().as_bytes());
+        self.dir.join(format!("{}.json", hex::encode(&digest[..8])))
+    }
+
+    fn read(&self, path: &Path) -> Option<Vec<u8>> {
+        let cached: CachedOutput = serde_json::from_slice(&fs::read(path).ok()?).ok()?;
+        let age = (Utc::now() - cached.stored).to_std().ok()?;
+        Some(cached.stdout.into_bytes()).filter(|_| age < self.ttl)
+    }
+
+    fn clear(&self) {
+        if let Err(e) = fs::remove_dir_all(&self.dir) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::debug!("failed to clear {}: {:?}", self.dir.display(), e);
+            }
+        }
+    }
+
+    fn write(&self, path: &Path, invocation: &Invocation, stdout: &[u8]) -> anyhow::Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("creating {}", self.dir.display()))?;
+        let cached = CachedOutput {
+            stored: Utc::now(),
+            key: invocation.key(),
+            stdout: String::from_utf8_lossy(stdout).into_owned(),
+        };
+        // outputs show balances, addresses and agreements, not for other users to read
+        write_atomic_private(path, &serde_json::to_vec(&cached)?)
+    }
+}
+
+impl CommandRunner for CachingRunner {
+    fn run<'a>(
+        &'a self,
+        invocation: &'a Invocation,
+        policy: ExecPolicy,
+    ) -> LocalBoxFuture<'a, anyhow::Result<Vec<u8>>> {
+        async move {
+            if invocation.is_secret() && invocation.is_read_only() {
+                return self.inner.run(invocation, policy).await;
+            }
+            if !invocation.is_read_only() {
+                let result = self.inner.run(invocation, policy).await;
+                // the invocation may have changed what the cached ones show, even if it failed
+                self.clear();
+                return result;
+            }
+            let path = self.path(invocation);
+            if let Some(stdout) = self.read(&path) {
+                tracing::debug!("reusing cached output of `{}`", invocation.key());
+                return Ok(stdout);
+            }
+            let stdout = self.inner.run(invocation, policy).await?;
+            if let Err(e) = self.write(&path, invocation, &stdout) {
+                tracing::debug!("failed to cache output of `{}`: {:?}", invocation.key(), e);
+            }
+            Ok(stdout)
+        }
+        .boxed_local()
+    }
+
+    fn is_yagna_running(&self) -> LocalBoxFuture<'_, anyhow::Result<bool>> {
+        self.inner.is_yagna_running()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redact_masks_key_fields() {
+        let json = r#"{"name": "yagna-usd", "key": "0f3a9c1b2d4e", "keyId": "18f2"}"#;
+        assert_eq!(
+            redact(json),
+            r#"{"name": "yagna-usd", "key": "<redacted>", "keyId": "18f2"}"#
+        );
+        assert_eq!(
+            redact(r#"{"key":"0f3a9c1b2d4e"}"#),
+            r#"{"key":"<redacted>"}"#
+        );
+    }
+
+    #[test]
+    fn redact_masks_app_key_assignments() {
+        assert_eq!(
+            redact("YAGNA_APPKEY=5d0c7e21aa94 /usr/bin/yagna payment status --json"),
+            "YAGNA_APPKEY=<redacted> /usr/bin/yagna payment status --json"
+        );
+        assert_eq!(redact("MY_YAGNA_APPKEY=1"), "MY_YAGNA_APPKEY=1");
+    }
+
+    #[test]
+    fn redact_masks_known_keys_in_app_key_table() {
+        remember_secret("9be41f0c6d2a4e7b8c13f5a0d9e27b64");
+        let table = "\
+│  name       │  key                               │  id                                          │  role     │
+│  yagna-usd  │  9be41f0c6d2a4e7b8c13f5a0d9e27b64  │  0x1a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d  │  manager  │";
+
+        let redacted = redact(table);
+        assert!(!redacted.contains("9be41f0c6d2a4e7b8c13f5a0d9e27b64"));
+        assert!(redacted.contains("│  yagna-usd  │  <redacted>  │"));
+        assert!(redacted.contains("0x1a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d"));
+    }
+
+    #[test]
+    fn key_leaves_out_datadir() {
+        let mut invocation = Invocation::new("/opt/golem/bin/yagna");
+        invocation.env("YAGNA_DATADIR", "/srv/yagna").args(&[
+            "--datadir",
+            "/srv/yagna",
+            "payment",
+            "status",
+            "--json",
+        ]);
+        assert_eq!(invocation.key(), "yagna payment status --json");
+    }
+
+    #[test]
+    fn redact_leaves_ids_alone() {
+        let line =
+            "activity 2f9b4c1e8a7d4b0c9e3f5a6d7c8b9a01 invoice 7c1d2e3f4a5b6c7d8e9f0a1b2c3d4e5f";
+        assert_eq!(redact(line), line);
+    }
+}
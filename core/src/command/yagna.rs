@@ -0,0 +1,498 @@
+use anyhow::{anyhow, bail};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::rc::Rc;
+use strum_macros::{Display, EnumString, EnumVariantNames, IntoStaticStr};
+
+use ya_core_model::payment::local::{
+    Account, InvoiceStats, InvoiceStatusNotes, NetworkName, StatusNotes, StatusResult,
+};
+use ya_core_model::version::VersionInfo;
+
+use super::{CommandRunner, ExecPolicy, Invocation};
+
+#[derive(Deserialize, Serialize)]
+pub struct VersionRaw {
+    pub version: String,
+    pub sha: String,
+    pub date: String,
+    pub build: String,
+}
+
+pub struct PaymentPlatform {
+    pub platform: &'static str,
+    pub driver: &'static str,
+    pub token: &'static str,
+}
+
+pub struct PaymentDriver(pub HashMap<&'static str, PaymentPlatform>);
+
+lazy_static! {
+    pub static ref ZKSYNC_DRIVER: PaymentDriver = {
+        let mut zksync = HashMap::new();
+        zksync.insert(
+            NetworkName::Mainnet.into(),
+            PaymentPlatform {
+                platform: "zksync-mainnet-glm",
+                driver: "zksync",
+                token: "GLM",
+            },
+        );
+        zksync.insert(
+            NetworkName::Rinkeby.into(),
+            PaymentPlatform {
+                platform: "zksync-rinkeby-tglm",
+                driver: "zksync",
+                token: "tGLM",
+            },
+        );
+        PaymentDriver(zksync)
+    };
+    pub static ref ERC20_DRIVER: PaymentDriver = {
+        let mut erc20 = HashMap::new();
+        erc20.insert(
+            NetworkName::Mainnet.into(),
+            PaymentPlatform {
+                platform: "erc20-mainnet-glm",
+                driver: "erc20",
+                token: "GLM",
+            },
+        );
+        erc20.insert(
+            NetworkName::Rinkeby.into(),
+            PaymentPlatform {
+                platform: "erc20-rinkeby-tglm",
+                driver: "erc20",
+                token: "tGLM",
+            },
+        );
+        erc20.insert(
+            NetworkName::Goerli.into(),
+            PaymentPlatform {
+                platform: "erc20-goerli-tglm",
+                driver: "erc20",
+                token: "tGLM",
+            },
+        );
+        erc20.insert(
+            NetworkName::Mumbai.into(),
+            PaymentPlatform {
+                platform: "erc20-mumbai-tglm",
+                driver: "erc20",
+                token: "tGLM",
+            },
+        );
+        erc20.insert(
+            NetworkName::Polygon.into(),
+            PaymentPlatform {
+                platform: "erc20-polygon-glm",
+                driver: "erc20",
+                token: "GLM",
+            },
+        );
+        PaymentDriver(erc20)
+    };
+}
+
+impl PaymentDriver {
+    pub fn platform(&self, network: &NetworkName) -> anyhow::Result<&PaymentPlatform> {
+        let net: &str = network.into();
+        Ok(self.0.get(net).ok_or(anyhow!(
+            "Payment driver config for network '{}' not found.",
+            network
+        ))?)
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Deserialize,
+    Display,
+    EnumVariantNames,
+    EnumString,
+    Eq,
+    Hash,
+    IntoStaticStr,
+    PartialEq,
+    Serialize,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkGroup {
+    Mainnet,
+    Testnet,
+}
+
+lazy_static! {
+    pub static ref NETWORK_GROUP_MAP: HashMap<NetworkGroup, Vec<NetworkName>> = {
+        let mut ngm = HashMap::new();
+        ngm.insert(
+            NetworkGroup::Mainnet,
+            vec![NetworkName::Mainnet, NetworkName::Polygon],
+        );
+        ngm.insert(
+            NetworkGroup::Testnet,
+            vec![
+                NetworkName::Rinkeby,
+                NetworkName::Mumbai,
+                NetworkName::Goerli,
+            ],
+        );
+        ngm
+    };
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Id {
+    pub node_id: String,
+    /// The key is encrypted with a password and has to be unlocked after a restart
+    #[serde(default)]
+    pub is_locked: bool,
+}
+
+/// Output of `yagna net status`
+#[derive(Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NetStatus {
+    pub node_id: Option<String>,
+    pub listen_address: Option<String>,
+    pub public_address: Option<String>,
+    pub sessions: Option<u64>,
+}
+
+/// Tabular output of yagna commands in `--json` mode
+#[derive(Deserialize, Default)]
+pub struct ResponseTable {
+    pub headers: Vec<String>,
+    pub values: Vec<Vec<serde_json::Value>>,
+}
+
+impl ResponseTable {
+    /// Rows as maps of lowercase header to value.
+    pub fn records(&self) -> Vec<HashMap<String, &serde_json::Value>> {
+        self.values
+            .iter()
+            .map(|row| {
+                self.headers
+                    .iter()
+                    .map(|header| header.to_lowercase())
+                    .zip(row)
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// States of a market agreement, as yagna reports them.
+#[derive(Clone, Copy, Debug, Display, EnumString, EnumVariantNames, Eq, PartialEq, Serialize)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum AgreementState {
+    Proposal,
+    Pending,
+    Cancelled,
+    Rejected,
+    Approved,
+    Expired,
+    Terminated,
+}
+
+/// Row of `yagna market agreements list`
+#[derive(Clone, Debug, Serialize)]
+pub struct AgreementEntry {
+    pub id: String,
+    /// Provider or Requestor, when yagna tells
+    pub role: Option<String>,
+    pub state: Option<AgreementState>,
+    pub created: Option<DateTime<Utc>>,
+}
+
+impl AgreementEntry {
+    fn from_record(record: &HashMap<String, &serde_json::Value>) -> Option<Self> {
+        let text = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| record.get(*name).and_then(|value| value.as_str()))
+        };
+        Some(AgreementEntry {
+            id: text(&["id", "agreement_id", "agreementid"])?.to_string(),
+            role: text(&["role"]).map(str::to_string),
+            state: text(&["state"]).and_then(|state| state.to_lowercase().parse().ok()),
+            created: text(&["created", "timestamp", "created_ts"])
+                .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+                .map(|ts| ts.with_timezone(&Utc)),
+        })
+    }
+}
+
+/// Problem reported by `yagna payment driver status`, eg. `InsufficientGas`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DriverProblem {
+    pub kind: String,
+    pub driver: Option<String>,
+    pub network: Option<String>,
+    /// Remaining fields of the report, eg. the address short of gas
+    pub details: serde_json::Value,
+}
+
+impl DriverProblem {
+    /// Reads the externally tagged `{"InsufficientGas": {"driver": ..}}` form.
+    fn from_value(value: serde_json::Value) -> Option<Self> {
+        let (kind, mut details) = match value {
+            serde_json::Value::Object(report) => report.into_iter().next()?,
+            _ => return None,
+        };
+        let mut field = |name: &str| {
+            details
+                .as_object_mut()
+                .and_then(|fields| fields.remove(name))
+                .and_then(|value| value.as_str().map(str::to_string))
+        };
+        Some(DriverProblem {
+            kind,
+            driver: field("driver"),
+            network: field("network"),
+            details,
+        })
+    }
+}
+
+pub trait PaymentSummary {
+    fn total_pending(&self) -> (BigDecimal, u64);
+    fn unconfirmed(&self) -> (BigDecimal, u64);
+}
+
+#[derive(Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityStatus {
+    pub last1h: HashMap<String, u64>,
+    pub total: HashMap<String, u64>,
+    pub last_activity_ts: Option<DateTime<Utc>>,
+}
+
+impl ActivityStatus {
+    pub fn last1h_processed(&self) -> u64 {
+        self.last1h.get("Terminated").copied().unwrap_or_default()
+    }
+
+    pub fn in_progress(&self) -> u64 {
+        let mut in_progress = 0;
+        for (k, v) in &self.last1h {
+            if k != "Terminated" && k != "New" {
+                in_progress += *v;
+            }
+        }
+        in_progress
+    }
+
+    pub fn total_processed(&self) -> u64 {
+        self.total.get("Terminated").copied().unwrap_or_default()
+    }
+}
+impl PaymentSummary for StatusNotes {
+    fn total_pending(&self) -> (BigDecimal, u64) {
+        (
+            &self.accepted.total_amount - &self.confirmed.total_amount,
+            self.accepted.agreements_count - self.confirmed.agreements_count,
+        )
+    }
+
+    fn unconfirmed(&self) -> (BigDecimal, u64) {
+        (
+            &self.requested.total_amount - &self.accepted.total_amount,
+            self.requested.agreements_count - self.accepted.agreements_count,
+        )
+    }
+}
+
+impl PaymentSummary for InvoiceStatusNotes {
+    fn total_pending(&self) -> (BigDecimal, u64) {
+        let value = self.accepted.clone();
+        (value.total_amount, value.agreements_count)
+    }
+
+    fn unconfirmed(&self) -> (BigDecimal, u64) {
+        let value = self.issued.clone() + self.received.clone();
+        (value.total_amount.clone(), value.agreements_count)
+    }
+}
+
+pub struct YagnaCommand {
+    pub(super) cmd: Invocation,
+    pub(super) policy: ExecPolicy,
+    pub(super) runner: Rc<dyn CommandRunner>,
+}
+
+impl YagnaCommand {
+    async fn run(self) -> anyhow::Result<Vec<u8>> {
+        let policy = if self.cmd.is_read_only() {
+            self.policy
+        } else {
+            self.policy.once()
+        };
+        self.runner.run(&self.cmd, policy).await
+    }
+
+    async fn run_json<T: DeserializeOwned>(mut self) -> anyhow::Result<T> {
+        self.cmd.args(&["--json"]);
+        let stdout = self.run().await?;
+        Ok(serde_json::from_slice(&stdout)?)
+    }
+
+    pub async fn default_id(mut self) -> anyhow::Result<Id> {
+        self.cmd.read_only().args(&["id", "show"]);
+        let output: Result<Id, String> = self.run_json().await?;
+        output.map_err(anyhow::Error::msg)
+    }
+
+    pub async fn version(mut self) -> anyhow::Result<VersionInfo> {
+        self.cmd.read_only().args(&["version", "show"]);
+        self.run_json().await
+    }
+
+    pub async fn version_raw(mut self) -> anyhow::Result<VersionRaw> {
+        self.cmd.read_only().args(&["--version"]);
+        let output = self.run().await?;
+        let re = Regex::new(r"yagna ([0-9.]+) \(([a-z0-9]+) ([-0-9]+)( build #(\d+))?")?;
+        if let Some(cap) = re.captures(&String::from_utf8_lossy(&output)) {
+            Ok(VersionRaw {
+                version: cap[1].to_string(),
+                sha: cap[2].to_string(),
+                date: cap[3].to_string(),
+                build: cap
+                    .get(5)
+                    .map(|m| m.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+            })
+        } else {
+            bail!("cannot parse yagna version {:?}", output)
+        }
+    }
+
+    pub async fn payment_status(
+        mut self,
+        address: &str,
+        network: &NetworkName,
+        payment_driver: &PaymentDriver,
+    ) -> anyhow::Result<StatusResult> {
+        self.cmd.read_only().args(&["payment", "status"]);
+        self.cmd.args(&["--account", address]);
+
+        let payment_platform = payment_driver.platform(network)?;
+        self.cmd.args(&["--network", &network.to_string()]);
+        self.cmd.args(&["--driver", payment_platform.driver]);
+
+        self.run_json().await
+    }
+
+    /// Prepares `address` to send payments on `network`, as a requestor.
+    pub async fn payment_init_sender(
+        mut self,
+        address: &str,
+        network: &NetworkName,
+        payment_driver: &PaymentDriver,
+    ) -> anyhow::Result<()> {
+        self.cmd.args(&["payment", "init", "--sender"]);
+        self.cmd.args(&["--account", address]);
+
+        let payment_platform = payment_driver.platform(network)?;
+        self.cmd.args(&["--network", &network.to_string()]);
+        self.cmd.args(&["--driver", payment_platform.driver]);
+
+        self.run().await?;
+        Ok(())
+    }
+
+    /// Gets test tokens from the faucet, only works on testnets.
+    pub async fn payment_fund(
+        mut self,
+        address: &str,
+        network: &NetworkName,
+        payment_driver: &PaymentDriver,
+    ) -> anyhow::Result<()> {
+        self.cmd.args(&["payment", "fund"]);
+        self.cmd.args(&["--account", address]);
+
+        let payment_platform = payment_driver.platform(network)?;
+        self.cmd.args(&["--network", &network.to_string()]);
+        self.cmd.args(&["--driver", payment_platform.driver]);
+
+        self.run().await?;
+        Ok(())
+    }
+
+    /// Problems of the payment drivers, empty when all is well.
+    pub async fn payment_driver_status(mut self) -> anyhow::Result<Vec<DriverProblem>> {
+        self.cmd.read_only().args(&["payment", "driver", "status"]);
+        let problems: Vec<serde_json::Value> = self.run_json().await?;
+        Ok(problems
+            .into_iter()
+            .filter_map(DriverProblem::from_value)
+            .collect())
+    }
+
+    pub async fn invoice_status(mut self) -> anyhow::Result<InvoiceStats> {
+        self.cmd.read_only().args(&["payment", "invoice", "status"]);
+        self.run_json().await
+    }
+
+    /// Keystore of the default identity, as written by `yagna id export`.
+    ///
+    /// The output holds the private key, it is never echoed, recorded nor cached.
+    pub async fn export_id(mut self) -> anyhow::Result<serde_json::Value> {
+        self.cmd.read_only().secret().args(&["id", "export"]);
+        let output: Result<serde_json::Value, String> = self.run_json().await?;
+        output.map_err(anyhow::Error::msg)
+    }
+
+    /// `yagna app-key list`, the output holds the keys.
+    pub async fn app_keys(mut self) -> anyhow::Result<serde_json::Value> {
+        self.cmd.read_only().secret().args(&["app-key", "list"]);
+        self.run_json().await
+    }
+
+    /// Creates the app key `name` and returns it.
+    pub async fn create_app_key(mut self, name: &str) -> anyhow::Result<String> {
+        self.cmd.secret().args(&["app-key", "create", name]);
+        let output = self.run().await?;
+        Ok(String::from_utf8(output)?.trim_end().to_string())
+    }
+
+    pub async fn payment_accounts(mut self) -> anyhow::Result<Vec<Account>> {
+        self.cmd.read_only().args(&["payment", "accounts"]);
+        self.run_json().await
+    }
+
+    pub async fn net_status(mut self) -> anyhow::Result<NetStatus> {
+        self.cmd.read_only().args(&["net", "status"]);
+        self.run_json().await
+    }
+
+    pub async fn net_sessions(mut self) -> anyhow::Result<ResponseTable> {
+        self.cmd.read_only().args(&["net", "sessions"]);
+        self.run_json().await
+    }
+
+    pub async fn agreements(mut self) -> anyhow::Result<Vec<AgreementEntry>> {
+        self.cmd.read_only().args(&["market", "agreements", "list"]);
+        let table: ResponseTable = self.run_json().await?;
+        Ok(table
+            .records()
+            .iter()
+            .filter_map(AgreementEntry::from_record)
+            .collect())
+    }
+
+    pub async fn activity_status(mut self) -> anyhow::Result<ActivityStatus> {
+        self.cmd.read_only().args(&["activity", "status"]);
+        self.run_json().await
+    }
+}
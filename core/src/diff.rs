@@ -0,0 +1,246 @@
+//! Changes between two status snapshots
+//!
+//! Snapshots are compared in their JSON form, so the previous one doesn't have to
+//! match the structs of the current yagna-usd version.
+
+use anyhow::{anyhow, bail, Result};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::utils::{project_dirs, write_atomic};
+
+/// Values compared between snapshots: JSON pointer, label id and whether the
+/// value is a number whose difference is shown.
+const TRACKED: &[(&str, &str, bool)] = &[
+    ("/is_running", "changes-running", false),
+    ("/version/version", "changes-version", false),
+    ("/update", "changes-update", false),
+    ("/config/node_name", "changes-node-name", false),
+    ("/config/subnet", "changes-subnet", false),
+    ("/observed/online", "changes-online", false),
+    ("/activity/total/Terminated", "changes-tasks", true),
+    ("/market/active_offers", "changes-offers", true),
+    ("/ranking/node/earnings_total", "changes-earnings", true),
+];
+
+/// Tracked value which differs between two snapshots
+#[derive(Clone, Serialize)]
+pub struct Change {
+    /// JSON pointer of the value in the snapshot
+    pub key: String,
+    pub label: String,
+    pub before: Value,
+    pub after: Value,
+    /// `after - before` of numeric values
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delta: Option<String>,
+}
+
+fn data_path(file_name: &str) -> Result<PathBuf> {
+    let dirs = project_dirs().ok_or_else(|| anyhow!("unable to determine data directory"))?;
+    Ok(dirs.data_dir().join(file_name))
+}
+
+fn load(file_name: &str) -> Result<Option<Value>> {
+    let path = data_path(file_name)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_slice(&std::fs::read(path)?)?))
+}
+
+fn store(file_name: &str, snapshot: &Value) -> Result<()> {
+    let path = data_path(file_name)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    write_atomic(&path, &serde_json::to_vec(snapshot)?)
+}
+
+/// Snapshot stored by the previous `status` run.
+pub fn load_last() -> Result<Option<Value>> {
+    load("last-status.json")
+}
+
+/// Latest snapshot taken while yagna was running, shown when it's down.
+pub fn load_last_online() -> Result<Option<Value>> {
+    load("last-online-status.json")
+}
+
+pub fn store_last(snapshot: &Value) -> Result<()> {
+    store("last-status.json", snapshot)?;
+    if snapshot.get("is_running") == Some(&Value::Bool(true)) {
+        store("last-online-status.json", snapshot)?;
+    }
+    Ok(())
+}
+
+/// When `snapshot` was collected.
+pub fn collected_at(snapshot: &Value) -> Option<DateTime<Utc>> {
+    serde_json::from_value(snapshot.get("collected_at")?.clone()).ok()
+}
+
+fn as_decimal(value: &Value) -> Option<BigDecimal> {
+    match value {
+        Value::Number(number) => BigDecimal::from_str(&number.to_string()).ok(),
+        Value::String(s) => BigDecimal::from_str(s).ok(),
+        _ => None,
+    }
+}
+
+fn compare(
+    key: &str,
+    label: String,
+    before: &Value,
+    after: &Value,
+    numeric: bool,
+) -> Option<Change> {
+    // sections missing from either snapshot were not collected, not changed
+    if before.is_null() || after.is_null() || before == after {
+        return None;
+    }
+    let delta = match (numeric, as_decimal(before), as_decimal(after)) {
+        (true, Some(before), Some(after)) if before == after => return None,
+        (true, Some(before), Some(after)) => {
+            let delta = after - before;
+            let sign = if delta > BigDecimal::default() {
+                "+"
+            } else {
+                ""
+            };
+            Some(format!("{}{}", sign, delta))
+        }
+        _ => None,
+    };
+    Some(Change {
+        key: key.to_string(),
+        label,
+        before: before.clone(),
+        after: after.clone(),
+        delta,
+    })
+}
+
+/// Tracked values which changed from `previous` to `current`.
+pub fn changes(previous: &Value, current: &Value) -> Vec<Change> {
+    let missing = Value::Null;
+    let at = |snapshot: &'_ Value, pointer: &str| -> Value {
+        snapshot.pointer(pointer).unwrap_or(&missing).clone()
+    };
+    let mut changes = TRACKED
+        .iter()
+        .filter_map(|(pointer, label, numeric)| {
+            compare(
+                pointer,
+                tr!(*label),
+                &at(previous, pointer),
+                &at(current, pointer),
+                *numeric,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // balances are listed per payment network
+    if let Some(Value::Object(statuses)) = current.pointer("/payments/statuses") {
+        for network in statuses.keys() {
+            let pointer = format!("/payments/statuses/{}/amount", network);
+            changes.extend(compare(
+                &pointer,
+                tr!("changes-balance", network = network),
+                &at(previous, &pointer),
+                &at(current, &pointer),
+                true,
+            ));
+        }
+    }
+    changes
+}
+
+/// Time the `--on-change` command gets before it is killed, so a hanging script doesn't
+/// stop the watch.
+const HOOK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Runs the user's `--on-change` command through the shell, with the changes on stdin:
+/// `{"collected_at": "...", "changes": [...]}`.
+pub async fn run_hook(
+    command: &str,
+    collected_at: DateTime<Utc>,
+    changes: &[Change],
+) -> Result<()> {
+    let payload = serde_json::to_vec(&serde_json::json!({
+        "collected_at": collected_at,
+        "changes": changes,
+    }))?;
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let mut child = shell
+        .arg(command)
+        .stdin(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| anyhow!("{}: {}", command, e))?;
+    let run = async move {
+        if let Some(mut stdin) = child.stdin.take() {
+            // commands not interested in the changes may exit without reading them
+            match stdin.write_all(&payload).await {
+                Err(e) if e.kind() != ErrorKind::BrokenPipe => return Err(e),
+                _ => (),
+            }
+        }
+        child.await
+    };
+    let status = tokio::time::timeout(HOOK_TIMEOUT, run)
+        .await
+        .map_err(|_| anyhow!("{}: timed out after {} s", command, HOOK_TIMEOUT.as_secs()))??;
+    if !status.success() {
+        bail!("{}: {}", command, status);
+    }
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn run(command: &str) -> Result<()> {
+        tokio::runtime::Builder::new()
+            .basic_scheduler()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(run_hook(command, Utc::now(), &[]))
+    }
+
+    #[test]
+    fn hook_gets_the_changes_on_stdin() {
+        let path = std::env::temp_dir().join(format!("yagna-usd-hook-{}.json", std::process::id()));
+        run(&format!("cat > '{}'", path.display())).unwrap();
+
+        let payload: Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert!(payload["collected_at"].is_string());
+        assert_eq!(payload["changes"], Value::Array(vec![]));
+    }
+
+    #[test]
+    fn hook_not_reading_stdin_succeeds() {
+        run("exit 0").unwrap();
+        assert!(run("exit 3").is_err());
+    }
+}
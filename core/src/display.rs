@@ -0,0 +1,387 @@
+//! Helpers for rendering values in the status output
+
+use bigdecimal::{BigDecimal, FromPrimitive, Signed, ToPrimitive};
+use chrono::{DateTime, Local, Utc};
+use chrono_tz::Tz;
+use lazy_static::lazy_static;
+use std::str::FromStr;
+use std::sync::RwLock;
+use structopt::StructOpt;
+use strum::VariantNames;
+use strum_macros::{Display, EnumString, EnumVariantNames};
+
+const ABSOLUTE_FORMAT: &str = "%Y-%m-%d %H:%M:%S %Z";
+
+/// Time zone used when rendering absolute timestamps.
+#[derive(Clone, Copy, Debug)]
+pub enum DisplayZone {
+    /// System local time zone, used when its IANA name could not be resolved.
+    Local,
+    Named(Tz),
+}
+
+impl DisplayZone {
+    /// Resolves the system time zone, preferring a named zone so that the
+    /// abbreviation (eg. CEST) can be shown instead of a bare offset.
+    pub fn system() -> Self {
+        system_zone_name()
+            .and_then(|name| Tz::from_str(&name).ok())
+            .map(DisplayZone::Named)
+            .unwrap_or(DisplayZone::Local)
+    }
+}
+
+impl FromStr for DisplayZone {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("local") {
+            return Ok(DisplayZone::system());
+        }
+        Tz::from_str(s)
+            .map(DisplayZone::Named)
+            .map_err(|_| format!("unknown time zone: {}", s))
+    }
+}
+
+fn system_zone_name() -> Option<String> {
+    if let Ok(tz) = std::env::var("TZ") {
+        let tz = tz.trim_start_matches(':');
+        if !tz.is_empty() {
+            return Some(tz.to_string());
+        }
+    }
+    let target = std::fs::read_link("/etc/localtime").ok()?;
+    let target = target.to_string_lossy();
+    target
+        .split("zoneinfo/")
+        .nth(1)
+        .map(|name| name.to_string())
+}
+
+/// Controls how timestamps are presented to the user.
+#[derive(Clone, Copy, Debug)]
+pub struct TimeDisplay {
+    pub absolute: bool,
+    pub zone: DisplayZone,
+    /// Relative timestamps are measured from here, the collection time of the snapshot shown.
+    pub now: DateTime<Utc>,
+}
+
+impl TimeDisplay {
+    /// Absolute timestamps in the system zone, for output without a `--tz` option.
+    pub fn local(now: DateTime<Utc>) -> Self {
+        TimeDisplay {
+            absolute: true,
+            zone: DisplayZone::system(),
+            now,
+        }
+    }
+
+    pub fn render(&self, ts: DateTime<Utc>) -> String {
+        if self.absolute {
+            self.render_absolute(ts)
+        } else {
+            format_relative(ts, self.now)
+        }
+    }
+
+    pub fn render_absolute(&self, ts: DateTime<Utc>) -> String {
+        match self.zone {
+            DisplayZone::Local => ts.with_timezone(&Local).format(ABSOLUTE_FORMAT).to_string(),
+            DisplayZone::Named(tz) => ts.with_timezone(&tz).format(ABSOLUTE_FORMAT).to_string(),
+        }
+    }
+}
+
+/// How the digits of amounts are laid out
+#[derive(Clone, Copy, Debug, Display, EnumString, EnumVariantNames, Eq, PartialEq)]
+#[strum(serialize_all = "lowercase")]
+pub enum Notation {
+    /// 1234567.89
+    Plain,
+    /// 1,234,567.89
+    Grouped,
+    /// 1.2M, amounts below a thousand as usual
+    Compact,
+}
+
+/// Suffixes of the compact notation with the power of a thousand they stand for.
+const COMPACT_SUFFIXES: &[(&str, i32)] = &[("B", 3), ("M", 2), ("k", 1)];
+
+/// Decimal places and notation GLM and fiat amounts are shown with
+#[derive(StructOpt, Clone, Copy, Debug)]
+pub struct AmountFormat {
+    /// Decimal places of GLM amounts
+    #[structopt(
+        long,
+        env = "YAGNA_USD_GLM_DECIMALS",
+        default_value = "4",
+        parse(try_from_str = parse_decimals),
+        global = true
+    )]
+    pub glm_decimals: u32,
+    /// Decimal places of fiat amounts
+    #[structopt(
+        long,
+        env = "YAGNA_USD_FIAT_DECIMALS",
+        default_value = "2",
+        parse(try_from_str = parse_decimals),
+        global = true
+    )]
+    pub fiat_decimals: u32,
+    /// Show amounts with all the digits yagna reports, eg. to audit invoices
+    #[structopt(long, global = true)]
+    pub full_precision: bool,
+    /// Thousands separators ("grouped") or "1.2k" style ("compact") for wide tables
+    #[structopt(
+        long,
+        env = "YAGNA_USD_NOTATION",
+        default_value = "plain",
+        possible_values = Notation::VARIANTS,
+        global = true
+    )]
+    pub notation: Notation,
+}
+
+/// Most decimal places shown, the precision of GLM's 18 decimals.
+const MAX_DECIMALS: u32 = 18;
+
+/// Decimal places from 0 to [`MAX_DECIMALS`].
+fn parse_decimals(decimals: &str) -> Result<u32, String> {
+    match decimals.trim().parse::<u32>() {
+        Ok(decimals) if decimals <= MAX_DECIMALS => Ok(decimals),
+        _ => Err(tr!(
+            "decimals-invalid",
+            value = decimals,
+            max = MAX_DECIMALS
+        )),
+    }
+}
+
+impl Default for AmountFormat {
+    fn default() -> Self {
+        AmountFormat {
+            glm_decimals: 4,
+            fiat_decimals: 2,
+            full_precision: false,
+            notation: Notation::Plain,
+        }
+    }
+}
+
+lazy_static! {
+    static ref AMOUNT_FORMAT: RwLock<AmountFormat> = RwLock::new(AmountFormat::default());
+}
+
+/// Sets how amounts are rendered for the rest of the run.
+pub fn set_amount_format(format: AmountFormat) {
+    *AMOUNT_FORMAT.write().unwrap() = format;
+}
+
+fn amount_format() -> AmountFormat {
+    *AMOUNT_FORMAT.read().unwrap()
+}
+
+/// `amount` rounded half away from zero to `decimals` places, trailing zeros kept.
+///
+/// `BigDecimal::with_scale` alone truncates, so 0.00995 would be shown as 0.00.
+pub fn round(amount: &BigDecimal, decimals: u32) -> BigDecimal {
+    let half = BigDecimal::from_str(&format!("0.{}5", "0".repeat(decimals as usize)))
+        .expect("valid decimal literal");
+    let away_from_zero = if amount.is_negative() {
+        amount - half
+    } else {
+        amount + half
+    };
+    away_from_zero.with_scale(decimals as i64)
+}
+
+/// "1,234,567.89" from "1234567.89".
+fn group_thousands(number: &str) -> String {
+    let (sign, unsigned) = match number.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", number),
+    };
+    let (integer, fraction) = match unsigned.find('.') {
+        Some(point) => unsigned.split_at(point),
+        None => (unsigned, ""),
+    };
+    let mut grouped = String::with_capacity(integer.len() + integer.len() / 3);
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    format!("{}{}{}", sign, grouped, fraction)
+}
+
+/// "1.2k" or "3.4M", `None` below a thousand.
+fn compact(amount: &BigDecimal) -> Option<String> {
+    let value = amount.to_f64()?;
+    let index = COMPACT_SUFFIXES
+        .iter()
+        .position(|(_, power)| value.abs() >= 1000f64.powi(*power))?;
+    // 999,960 is "1.0M" rather than "1000.0k"
+    let rounds_up = |power: i32| (value.abs() / 1000f64.powi(power) * 10.0).round() >= 10_000.0;
+    let index = if index > 0 && rounds_up(COMPACT_SUFFIXES[index].1) {
+        index - 1
+    } else {
+        index
+    };
+    let (suffix, power) = COMPACT_SUFFIXES[index];
+    Some(format!("{:.1}{}", value / 1000f64.powi(power), suffix))
+}
+
+fn format_amount(amount: &BigDecimal, decimals: u32) -> String {
+    let format = amount_format();
+    if format.full_precision {
+        return amount.to_string();
+    }
+    match format.notation {
+        Notation::Plain => round(amount, decimals).to_string(),
+        Notation::Grouped => group_thousands(&round(amount, decimals).to_string()),
+        Notation::Compact => compact(amount).unwrap_or_else(|| round(amount, decimals).to_string()),
+    }
+}
+
+/// GLM amount with the configured decimal places, without the token symbol.
+pub fn glm(amount: &BigDecimal) -> String {
+    format_amount(amount, amount_format().glm_decimals)
+}
+
+/// Fiat amount with the configured decimal places, without the currency.
+pub fn fiat(amount: &BigDecimal) -> String {
+    format_amount(amount, amount_format().fiat_decimals)
+}
+
+/// [`glm`] for amounts computed as floating point, eg. estimates.
+pub fn glm_f64(amount: f64) -> String {
+    BigDecimal::from_f64(amount)
+        .map(|amount| glm(&amount))
+        .unwrap_or_else(|| amount.to_string())
+}
+
+/// [`fiat`] for amounts computed as floating point.
+pub fn fiat_f64(amount: f64) -> String {
+    BigDecimal::from_f64(amount)
+        .map(|amount| fiat(&amount))
+        .unwrap_or_else(|| amount.to_string())
+}
+
+/// Removes ANSI styling, for output going to dumb terminals, serial consoles or files.
+pub fn strip_styles(text: &str) -> String {
+    strip_ansi_escapes::strip(text)
+        .map(|plain| String::from_utf8_lossy(&plain).into_owned())
+        .unwrap_or_else(|_| text.to_string())
+}
+
+/// Formats `ts` as a human readable distance from `now`, eg. "12 min ago".
+pub fn format_relative(ts: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = now.signed_duration_since(ts);
+    let secs = elapsed.num_seconds();
+    if secs < 0 {
+        return tr!("time-in-future");
+    }
+    if secs < 60 {
+        return tr!("time-just-now");
+    }
+    let mins = elapsed.num_minutes();
+    if mins < 60 {
+        return tr!("time-minutes-ago", count = mins);
+    }
+    let hours = elapsed.num_hours();
+    if hours < 48 {
+        return tr!("time-hours-ago", count = hours);
+    }
+    tr!("time-days-ago", count = elapsed.num_days())
+}
+
+/// Formats a length of time compactly, eg. "3h 05m".
+pub fn format_duration(duration: chrono::Duration) -> String {
+    let secs = duration.num_seconds().max(0);
+    let (days, hours, mins) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60);
+    if days > 0 {
+        format!("{}d {:02}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {:02}m", hours, mins)
+    } else {
+        format!("{}m {:02}s", mins, secs % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rounded(amount: &str, decimals: u32) -> String {
+        round(&BigDecimal::from_str(amount).unwrap(), decimals).to_string()
+    }
+
+    #[test]
+    fn round_goes_half_away_from_zero() {
+        assert_eq!(rounded("1.005", 2), "1.01");
+        assert_eq!(rounded("1.004", 2), "1.00");
+        assert_eq!(rounded("-1.005", 2), "-1.01");
+        assert_eq!(rounded("-1.004", 2), "-1.00");
+        assert_eq!(rounded("0.00995", 4), "0.0100");
+    }
+
+    #[test]
+    fn round_to_whole_numbers() {
+        assert_eq!(rounded("2.5", 0), "3");
+        assert_eq!(rounded("2.4", 0), "2");
+        assert_eq!(rounded("-2.5", 0), "-3");
+        assert_eq!(rounded("-0.4", 0), "0");
+        assert_eq!(rounded("7", 0), "7");
+    }
+
+    #[test]
+    fn decimals_are_bounded() {
+        assert_eq!(parse_decimals("0"), Ok(0));
+        assert_eq!(parse_decimals("18"), Ok(18));
+        assert!(parse_decimals("19").is_err());
+        assert!(parse_decimals("4000000000").is_err());
+        assert!(parse_decimals("-1").is_err());
+    }
+
+    fn compacted(amount: &str) -> Option<String> {
+        compact(&BigDecimal::from_str(amount).unwrap())
+    }
+
+    #[test]
+    fn compact_leaves_amounts_below_a_thousand() {
+        assert_eq!(compacted("999"), None);
+        assert_eq!(compacted("999.99"), None);
+        assert_eq!(compacted("-999"), None);
+        assert_eq!(compacted("0.5"), None);
+    }
+
+    #[test]
+    fn compact_scales_to_the_suffix() {
+        assert_eq!(compacted("1000").as_deref(), Some("1.0k"));
+        assert_eq!(compacted("1234.5").as_deref(), Some("1.2k"));
+        assert_eq!(compacted("-1500").as_deref(), Some("-1.5k"));
+        assert_eq!(compacted("2500000").as_deref(), Some("2.5M"));
+        assert_eq!(compacted("7000000000").as_deref(), Some("7.0B"));
+    }
+
+    #[test]
+    fn compact_rounds_up_to_the_next_suffix() {
+        assert_eq!(compacted("999960").as_deref(), Some("1.0M"));
+        assert_eq!(compacted("-999960").as_deref(), Some("-1.0M"));
+        assert_eq!(compacted("999940").as_deref(), Some("999.9k"));
+        assert_eq!(compacted("999960000000").as_deref(), Some("1000.0B"));
+    }
+
+    #[test]
+    fn group_thousands_of_integer_part() {
+        assert_eq!(group_thousands("999"), "999");
+        assert_eq!(group_thousands("1000"), "1,000");
+        assert_eq!(group_thousands("1234567.891"), "1,234,567.891");
+        assert_eq!(group_thousands("-1234.5"), "-1,234.5");
+        assert_eq!(group_thousands("-999.5"), "-999.5");
+        assert_eq!(group_thousands("0.12345"), "0.12345");
+    }
+}
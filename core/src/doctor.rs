@@ -0,0 +1,517 @@
+//! Diagnostics of the local provider setup
+
+use ansi_term::{Colour, Style};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use futures::future;
+use std::path::Path;
+use structopt::StructOpt;
+
+use crate::appkey;
+use crate::command::{CommandOptions, YaCommand, ERC20_DRIVER};
+use crate::display::format_relative;
+use crate::drivers;
+use crate::http;
+use crate::presets;
+use crate::stats;
+use crate::status::account_mismatch;
+
+const CLOCK_REFERENCE_URL: &str = "https://api.coingecko.com/api/v3/ping";
+const CLOCK_SKEW_WARN_SECS: i64 = 5;
+const CLOCK_SKEW_FAIL_SECS: i64 = 30;
+const DISK_FREE_WARN: u64 = 10 * 1024 * 1024 * 1024;
+const DISK_FREE_FAIL: u64 = 1024 * 1024 * 1024;
+/// Market scans run every few minutes, so an hour without one means the offers are not seen.
+const VISIBILITY_STALE_SECS: i64 = 60 * 60;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// Result of a single diagnostic, shared by `doctor` and `net check`
+pub struct Check {
+    pub name: String,
+    pub verdict: Verdict,
+    pub detail: String,
+    pub hint: Option<String>,
+}
+
+impl Check {
+    pub fn pass(name: String, detail: String) -> Self {
+        Check {
+            name,
+            verdict: Verdict::Pass,
+            detail,
+            hint: None,
+        }
+    }
+
+    pub fn warn(name: String, detail: String, hint: String) -> Self {
+        Check {
+            name,
+            verdict: Verdict::Warn,
+            detail,
+            hint: Some(hint),
+        }
+    }
+
+    pub fn fail(name: String, detail: String, hint: String) -> Self {
+        Check {
+            name,
+            verdict: Verdict::Fail,
+            detail,
+            hint: Some(hint),
+        }
+    }
+}
+
+#[derive(StructOpt)]
+pub struct DoctorCommand {
+    /// Print the full app key instead of a masked one, eg. to try the REST API by hand
+    #[structopt(long)]
+    reveal: bool,
+}
+
+pub async fn run(options: &CommandOptions, args: DoctorCommand) -> Result</*exit code*/ i32> {
+    let cmd = YaCommand::new(options)?;
+    let mut checks = vec![];
+
+    for program in &["yagna", "ya-provider"] {
+        checks.push(check_binary(&cmd, program));
+    }
+    if let Some(check) = check_install_method(&cmd) {
+        checks.push(check);
+    }
+
+    let running = cmd.is_yagna_running().await?;
+    if running {
+        checks.push(Check::pass(
+            tr!("doctor-daemon"),
+            tr!("doctor-daemon-running"),
+        ));
+        checks.push(check_appkey(&cmd, args.reveal).await);
+        checks.push(check_payment_accounts(&cmd).await);
+        checks.push(check_payment_networks(&cmd).await);
+        checks.push(check_visibility(&cmd).await);
+    } else {
+        checks.push(Check::fail(
+            tr!("doctor-daemon"),
+            tr!("doctor-daemon-not-running"),
+            tr!("doctor-daemon-hint"),
+        ));
+    }
+    checks.push(check_presets(&cmd).await);
+    checks.push(
+        tokio::task::spawn_blocking(check_clock)
+            .await
+            .unwrap_or_else(|e| {
+                Check::warn(tr!("doctor-clock"), e.to_string(), tr!("doctor-clock-hint"))
+            }),
+    );
+    if let Some(check) = check_disk_space() {
+        checks.push(check);
+    }
+
+    print_checks(&checks);
+    Ok(exit_code(&checks))
+}
+
+pub fn exit_code(checks: &[Check]) -> i32 {
+    if checks.iter().any(|check| check.verdict == Verdict::Fail) {
+        1
+    } else {
+        0
+    }
+}
+
+pub fn print_checks(checks: &[Check]) {
+    let width = checks
+        .iter()
+        .map(|check| check.name.chars().count())
+        .max()
+        .unwrap_or_default();
+    for check in checks {
+        let label = match check.verdict {
+            Verdict::Pass => Style::new().fg(Colour::Green).paint(" PASS "),
+            Verdict::Warn => Style::new().fg(Colour::Yellow).paint(" WARN "),
+            Verdict::Fail => Style::new().fg(Colour::Red).paint(" FAIL "),
+        };
+        println!(
+            "{} {:width$}  {}",
+            label,
+            check.name,
+            check.detail,
+            width = width
+        );
+        if let Some(hint) = &check.hint {
+            println!("{:indent$}{}", "", hint, indent = width + 9);
+        }
+    }
+}
+
+fn check_binary(cmd: &YaCommand, program: &str) -> Check {
+    let name = tr!("doctor-binary", program = program);
+    match cmd.resolve(program) {
+        Some(path) if is_executable(&path) => Check::pass(name, path.display().to_string()),
+        Some(path) => Check::fail(
+            name,
+            tr!("doctor-binary-not-executable", path = path.display()),
+            tr!("doctor-binary-not-executable-hint"),
+        ),
+        None => Check::fail(
+            name,
+            tr!("doctor-binary-not-found"),
+            tr!("doctor-binary-not-found-hint"),
+        ),
+    }
+}
+
+/// Mixing binaries of different installations usually means one of them is outdated.
+fn check_install_method(cmd: &YaCommand) -> Option<Check> {
+    let name = tr!("doctor-install");
+    match (
+        cmd.install_method("yagna"),
+        cmd.install_method("ya-provider"),
+    ) {
+        (Some(yagna), Some(provider)) if yagna == provider => {
+            Some(Check::pass(name, yagna.describe()))
+        }
+        (Some(yagna), Some(provider)) => Some(Check::warn(
+            name,
+            tr!(
+                "doctor-install-mixed",
+                yagna = yagna.describe(),
+                provider = provider.describe()
+            ),
+            tr!("doctor-install-mixed-hint"),
+        )),
+        // missing binaries are already reported
+        _ => None,
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+async fn check_appkey(cmd: &YaCommand, reveal: bool) -> Check {
+    let name = tr!("doctor-appkey");
+    // a diagnosis mustn't change the node, a missing key is reported instead of created
+    let app_key = match appkey::get_existing_key(cmd).await {
+        Ok(Some(app_key)) => app_key,
+        Ok(None) => {
+            return Check::fail(
+                name,
+                tr!("doctor-appkey-missing", name = appkey::APP_KEY_NAME),
+                tr!("doctor-appkey-create-hint", name = appkey::APP_KEY_NAME),
+            )
+        }
+        Err(e) => return Check::fail(name, e.to_string(), tr!("doctor-appkey-hint")),
+    };
+    let shown = if reveal {
+        app_key.reveal().to_string()
+    } else {
+        app_key.masked()
+    };
+    let result: Result<usize> = async {
+        let mkt_api: ya_client::market::MarketProviderApi =
+            ya_client::web::WebClient::with_token(app_key.reveal()).interface()?;
+        Ok(mkt_api.get_offers().await?.len())
+    }
+    .await;
+    match result {
+        Ok(_) => Check::pass(
+            name,
+            tr!(
+                "doctor-appkey-valid",
+                name = app_key.name.clone(),
+                key = shown
+            ),
+        ),
+        Err(e) => Check::fail(
+            name,
+            tr!(
+                "doctor-appkey-rejected",
+                name = app_key.name.clone(),
+                key = shown,
+                error = e
+            ),
+            tr!("doctor-appkey-hint"),
+        ),
+    }
+}
+
+async fn check_payment_accounts(cmd: &YaCommand) -> Check {
+    let name = tr!("doctor-payment-accounts");
+    let accounts = match cmd.yagna() {
+        Ok(yagna) => yagna.payment_accounts().await,
+        Err(e) => Err(e),
+    };
+    match accounts {
+        Ok(accounts) if accounts.is_empty() => Check::fail(
+            name,
+            tr!("doctor-payment-accounts-none"),
+            tr!("doctor-payment-accounts-hint"),
+        ),
+        Ok(accounts) => {
+            let platforms = accounts
+                .iter()
+                .filter(|account| account.receive)
+                .map(|account| account.platform.as_str())
+                .collect::<Vec<_>>();
+            if platforms.is_empty() {
+                return Check::fail(
+                    name,
+                    tr!("doctor-payment-accounts-no-receive"),
+                    tr!("doctor-payment-accounts-hint"),
+                );
+            }
+            let (config, id) = match (cmd.ya_provider(), cmd.yagna()) {
+                (Ok(provider), Ok(yagna)) => {
+                    future::join(provider.get_config_with_account(), yagna.default_id()).await
+                }
+                _ => return Check::pass(name, platforms.join(", ")),
+            };
+            let configured = config
+                .as_ref()
+                .ok()
+                .and_then(|(_, account)| account.as_deref());
+            let identity = id.as_ref().ok().map(|id| id.node_id.as_str());
+            match account_mismatch(configured, identity, Some(accounts.as_slice())) {
+                Some(mismatch) => Check::fail(name, mismatch, tr!("doctor-account-hint")),
+                None => Check::pass(name, platforms.join(", ")),
+            }
+        }
+        Err(e) => Check::warn(name, e.to_string(), tr!("doctor-payment-accounts-hint")),
+    }
+}
+
+/// Every network the provider accepts payments on needs an erc20 account initialized for
+/// receiving, or requestors paying there are never seen.
+async fn check_payment_networks(cmd: &YaCommand) -> Check {
+    let name = tr!("doctor-payment-networks");
+    let accounts = match cmd.yagna() {
+        Ok(yagna) => yagna.payment_accounts().await,
+        Err(e) => Err(e),
+    };
+    let accounts = match accounts {
+        Ok(accounts) => accounts,
+        Err(e) => return Check::warn(name, e.to_string(), tr!("doctor-payment-accounts-hint")),
+    };
+    let networks = drivers::networks();
+    let mut missing = vec![];
+    for network in &networks {
+        let platform = match ERC20_DRIVER.platform(network) {
+            Ok(platform) => platform,
+            Err(_) => continue,
+        };
+        let initialized = accounts
+            .iter()
+            .any(|account| account.receive && account.platform == platform.platform);
+        if !initialized {
+            missing.push((network.to_string().to_lowercase(), platform));
+        }
+    }
+    if missing.is_empty() {
+        return Check::pass(
+            name,
+            networks
+                .iter()
+                .map(|network| network.to_string().to_lowercase())
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+    }
+    Check::fail(
+        name,
+        tr!(
+            "doctor-payment-networks-missing",
+            platforms = missing
+                .iter()
+                .map(|(_, platform)| platform.platform)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        missing
+            .iter()
+            .map(|(network, platform)| {
+                format!(
+                    "yagna payment init --receiver --network {} --driver {}",
+                    network, platform.driver
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("; "),
+    )
+}
+
+async fn check_presets(cmd: &YaCommand) -> Check {
+    let name = tr!("doctor-presets");
+    let presets = async {
+        future::try_join3(
+            cmd.ya_provider()?.presets(),
+            cmd.ya_provider()?.active_presets(),
+            cmd.ya_provider()?.exe_units(),
+        )
+        .await
+    }
+    .await;
+    match presets {
+        Ok((_, active, _)) if active.is_empty() => {
+            Check::fail(name, tr!("doctor-presets-none"), tr!("doctor-presets-hint"))
+        }
+        Ok((presets, active, runtimes)) => {
+            let idle = presets::idle_runtimes(&runtimes, &presets, &active);
+            if !idle.is_empty() {
+                return Check::warn(
+                    name,
+                    tr!(
+                        "doctor-presets-runtime-inactive",
+                        runtimes = idle.join(", ")
+                    ),
+                    tr!("doctor-presets-hint"),
+                );
+            }
+            let network = stats::pricing()
+                .await
+                .map_err(|e| tracing::debug!("network prices unavailable: {:?}", e))
+                .unwrap_or_default();
+            let warnings = presets
+                .iter()
+                .filter(|preset| active.contains(&preset.name))
+                .flat_map(|preset| presets::pricing_warnings(preset, &network))
+                .collect::<Vec<_>>();
+            if warnings.is_empty() {
+                Check::pass(name, active.join(", "))
+            } else {
+                Check::warn(name, warnings.join("; "), tr!("doctor-presets-price-hint"))
+            }
+        }
+        Err(e) => Check::warn(name, e.to_string(), tr!("doctor-presets-hint")),
+    }
+}
+
+async fn check_visibility(cmd: &YaCommand) -> Check {
+    let name = tr!("doctor-visibility");
+    let node: Result<Option<stats::NodeInfo>> = async {
+        let id = cmd.yagna()?.default_id().await?;
+        stats::node(&id.node_id).await
+    }
+    .await;
+    match node {
+        Ok(Some(node)) => {
+            let stale = node
+                .updated_at
+                .map(|ts| {
+                    Utc::now().signed_duration_since(ts).num_seconds() > VISIBILITY_STALE_SECS
+                })
+                .unwrap_or(true);
+            let last_seen = match node.updated_at {
+                Some(ts) => tr!(
+                    "doctor-visibility-last-seen",
+                    ago = format_relative(ts, Utc::now())
+                ),
+                None => tr!("doctor-visibility-never"),
+            };
+            if node.online && !stale {
+                Check::pass(name, last_seen)
+            } else {
+                Check::warn(name, last_seen, tr!("doctor-visibility-hint"))
+            }
+        }
+        Ok(None) => Check::fail(
+            name,
+            tr!("doctor-visibility-never"),
+            tr!("doctor-visibility-hint"),
+        ),
+        Err(e) => Check::warn(
+            name,
+            tr!("doctor-visibility-unknown", reason = e),
+            tr!("doctor-visibility-hint"),
+        ),
+    }
+}
+
+fn check_clock() -> Check {
+    let name = tr!("doctor-clock");
+    match clock_skew() {
+        Ok(skew) => {
+            let secs = skew.num_seconds().abs();
+            let detail = tr!("doctor-clock-skew", seconds = secs);
+            if secs >= CLOCK_SKEW_FAIL_SECS {
+                Check::fail(name, detail, tr!("doctor-clock-hint"))
+            } else if secs >= CLOCK_SKEW_WARN_SECS {
+                Check::warn(name, detail, tr!("doctor-clock-hint"))
+            } else {
+                Check::pass(name, detail)
+            }
+        }
+        Err(e) => Check::warn(
+            name,
+            tr!("doctor-clock-unknown", reason = e),
+            tr!("doctor-clock-hint"),
+        ),
+    }
+}
+
+/// Difference between local time and the `Date` header of a well known server.
+fn clock_skew() -> Result<chrono::Duration> {
+    let response = http::get(CLOCK_REFERENCE_URL)?
+        .call()
+        .map_err(http::explain)?;
+    let date = response
+        .header("date")
+        .ok_or_else(|| anyhow!("missing Date header"))?;
+    let remote = DateTime::parse_from_rfc2822(date)?;
+    Ok(Utc::now().signed_duration_since(remote))
+}
+
+#[cfg(unix)]
+fn check_disk_space() -> Option<Check> {
+    use crate::utils::yagna_datadir;
+    use byte_unit::Byte;
+
+    let name = tr!("doctor-disk");
+    let datadir = yagna_datadir()?;
+    let check = match free_space(&datadir) {
+        Ok(free) => {
+            let detail = tr!(
+                "doctor-disk-free",
+                free = Byte::from_bytes(free as u128).get_appropriate_unit(true),
+                path = datadir.display()
+            );
+            if free < DISK_FREE_FAIL {
+                Check::fail(name, detail, tr!("doctor-disk-hint"))
+            } else if free < DISK_FREE_WARN {
+                Check::warn(name, detail, tr!("doctor-disk-hint"))
+            } else {
+                Check::pass(name, detail)
+            }
+        }
+        Err(e) => Check::warn(name, e.to_string(), tr!("doctor-disk-hint")),
+    };
+    Some(check)
+}
+
+#[cfg(not(unix))]
+fn check_disk_space() -> Option<Check> {
+    None
+}
+
+#[cfg(unix)]
+fn free_space(path: &Path) -> Result<u64> {
+    let stat = nix::sys::statvfs::statvfs(path)?;
+    Ok(stat.blocks_available() as u64 * stat.fragment_size() as u64)
+}
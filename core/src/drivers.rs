@@ -0,0 +1,240 @@
+//! Health of the payment drivers: the RPC endpoint each network is reached through, how
+//! fresh its chain view is and how many of the node's transactions are still pending on chain
+//!
+//! Payments get stuck when the RPC node lags behind or the sender runs out of gas, the
+//! latter is reported by `yagna payment driver status`, the former is checked here.
+//!
+//! Asking an endpoint tells its operator the node's address, so only endpoints the operator
+//! configured with `{NETWORK}_GETH_ADDR` are asked. The public ones yagna falls back to are
+//! asked only with `YAGNA_USD_PUBLIC_RPC=1`.
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::time::Duration;
+
+use ya_core_model::payment::local::NetworkName;
+use ya_core_model::NodeId;
+
+use crate::command::{DriverProblem, NetworkGroup, YaCommand, NETWORK_GROUP_MAP};
+use crate::http;
+use crate::processes;
+use crate::utils::payment_account;
+
+/// Latest block older than this means the endpoint stopped following the chain.
+pub const STALE_BLOCK_MINUTES: i64 = 5;
+const RPC_TIMEOUT: Duration = Duration::from_secs(10);
+/// Set to ask the public default endpoints as well.
+const PUBLIC_RPC: &str = "YAGNA_USD_PUBLIC_RPC";
+
+/// Endpoints the erc20 driver uses unless `{NETWORK}_GETH_ADDR` is set.
+fn default_endpoint(network: &str) -> Option<&'static str> {
+    match network {
+        "mainnet" => Some("https://geth.golem.network:55555"),
+        "polygon" => Some("https://bor.golem.network"),
+        "rinkeby" => Some("http://geth.testnet.golem.network:55555"),
+        "goerli" => Some("https://rpc.ankr.com/eth_goerli"),
+        "mumbai" => Some("https://matic-mumbai.chainstacklabs.com"),
+        _ => None,
+    }
+}
+
+/// First of the comma separated endpoints the driver of the running yagna was configured with.
+fn configured_endpoint(network: &str) -> Option<String> {
+    processes::daemon_env("yagna", &format!("{}_GETH_ADDR", network.to_uppercase())).and_then(
+        |addrs| {
+            addrs
+                .split(',')
+                .map(str::trim)
+                .find(|addr| !addr.is_empty())
+                .map(str::to_string)
+        },
+    )
+}
+
+fn public_rpc_allowed() -> bool {
+    std::env::var(PUBLIC_RPC).map_or(false, |value| {
+        matches!(value.trim().to_lowercase().as_str(), "1" | "true" | "yes")
+    })
+}
+
+/// Chain as seen through the RPC endpoint of one network.
+#[derive(Deserialize, Serialize)]
+pub struct NetworkRpc {
+    pub network: String,
+    /// Origin of the endpoint, its path and query may hold an API key
+    pub endpoint: String,
+    pub last_block: Option<u64>,
+    pub block_time: Option<DateTime<Utc>>,
+    /// Transactions of the node the endpoint knows of but which aren't mined yet (pending
+    /// minus latest nonce), not the payments queued in the driver
+    pub pending: Option<u64>,
+    /// Public default endpoint, not asked unless `YAGNA_USD_PUBLIC_RPC` is set
+    #[serde(default)]
+    pub skipped: bool,
+    /// Why the endpoint could not be asked
+    pub error: Option<String>,
+}
+
+impl NetworkRpc {
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        matches!(self.block_time, Some(time) if now - time > chrono::Duration::minutes(STALE_BLOCK_MINUTES))
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct DriverStatus {
+    pub networks: Vec<NetworkRpc>,
+    /// Problems reported by the drivers themselves, eg. insufficient gas
+    pub problems: Vec<DriverProblem>,
+}
+
+impl DriverStatus {
+    /// Why payments may be stuck, one line per cause.
+    pub fn warnings(&self, now: DateTime<Utc>) -> Vec<String> {
+        let mut warnings = vec![];
+        for network in &self.networks {
+            if let Some(error) = &network.error {
+                warnings.push(tr!(
+                    "drivers-rpc-failed",
+                    network = network.network.clone(),
+                    error = error.clone()
+                ));
+            } else if network.is_stale(now) {
+                warnings.push(tr!(
+                    "drivers-stale-block",
+                    network = network.network.clone(),
+                    block = network.last_block.unwrap_or_default()
+                ));
+            }
+        }
+        for problem in &self.problems {
+            warnings.push(tr!(
+                "drivers-problem",
+                kind = problem.kind.clone(),
+                network = problem.network.clone().unwrap_or_else(|| "-".to_string())
+            ));
+        }
+        warnings
+    }
+}
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    result: Option<serde_json::Value>,
+    error: Option<serde_json::Value>,
+}
+
+fn rpc_call(endpoint: &str, method: &str, params: serde_json::Value) -> Result<serde_json::Value> {
+    let response: RpcResponse = http::post(endpoint)?
+        .timeout(RPC_TIMEOUT)
+        .send_json(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        }))
+        .map_err(http::explain)?
+        .into_json()?;
+    match (response.result, response.error) {
+        (_, Some(error)) => Err(anyhow!("{}: {}", method, error)),
+        (Some(result), None) => Ok(result),
+        (None, None) => Err(anyhow!("{}: empty response", method)),
+    }
+}
+
+/// Parses the `0x` prefixed hex quantities of the Ethereum JSON-RPC.
+fn quantity(value: &serde_json::Value) -> Result<u64> {
+    let hex = value
+        .as_str()
+        .and_then(|s| s.strip_prefix("0x"))
+        .ok_or_else(|| anyhow!("not a quantity: {}", value))?;
+    Ok(u64::from_str_radix(hex, 16)?)
+}
+
+fn query_network(network: &NetworkName, address: &str) -> NetworkRpc {
+    let network = network.to_string().to_lowercase();
+    let configured = configured_endpoint(&network);
+    let skipped = configured.is_none() && !public_rpc_allowed();
+    let endpoint = configured
+        .or_else(|| default_endpoint(&network).map(str::to_string))
+        .unwrap_or_default();
+    let mut rpc = NetworkRpc {
+        network,
+        endpoint: http::origin(&endpoint),
+        last_block: None,
+        block_time: None,
+        pending: None,
+        error: None,
+        skipped,
+    };
+    if skipped {
+        return rpc;
+    }
+    let result = (|| -> Result<()> {
+        if endpoint.is_empty() {
+            bail!(
+                "no RPC endpoint, set {}_GETH_ADDR",
+                rpc.network.to_uppercase()
+            );
+        }
+        let block = rpc_call(
+            &endpoint,
+            "eth_getBlockByNumber",
+            serde_json::json!(["latest", false]),
+        )?;
+        rpc.last_block = Some(quantity(&block["number"])?);
+        let timestamp = quantity(&block["timestamp"])?;
+        rpc.block_time = Some(
+            i64::try_from(timestamp)
+                .ok()
+                .and_then(|timestamp| Utc.timestamp_opt(timestamp, 0).single())
+                .ok_or_else(|| anyhow!("invalid block timestamp {}", timestamp))?,
+        );
+        let count = |tag: &str| {
+            rpc_call(
+                &endpoint,
+                "eth_getTransactionCount",
+                serde_json::json!([address, tag]),
+            )
+            .and_then(|count| quantity(&count))
+        };
+        rpc.pending = Some(count("pending")?.saturating_sub(count("latest")?));
+        Ok(())
+    })();
+    if let Err(e) = result {
+        tracing::debug!(
+            "{} RPC at {} failed: {}",
+            rpc.network,
+            rpc.endpoint,
+            http::mask_url(&format!("{:?}", e), &endpoint)
+        );
+        rpc.error = Some(http::mask_url(&e.to_string(), &endpoint));
+    }
+    rpc
+}
+
+/// Networks of the group the running provider was started for, see `YA_PAYMENT_NETWORK_GROUP`.
+pub fn networks() -> Vec<NetworkName> {
+    let group = processes::daemon_env("ya-provider", "YA_PAYMENT_NETWORK_GROUP")
+        .and_then(|group| NetworkGroup::from_str(&group.to_lowercase()).ok())
+        .unwrap_or(NetworkGroup::Mainnet);
+    NETWORK_GROUP_MAP[&group].clone()
+}
+
+pub async fn collect(cmd: &YaCommand, account: &Option<NodeId>) -> Result<DriverStatus> {
+    let address = payment_account(cmd, account).await?;
+    let problems = cmd
+        .yagna()?
+        .payment_driver_status()
+        .await
+        .map_err(|e| tracing::debug!("yagna payment driver status failed: {:?}", e))
+        .unwrap_or_default();
+    let networks = futures::future::try_join_all(networks().into_iter().map(|network| {
+        let address = address.clone();
+        tokio::task::spawn_blocking(move || query_network(&network, &address))
+    }))
+    .await?;
+    Ok(DriverStatus { networks, problems })
+}
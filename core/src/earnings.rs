@@ -0,0 +1,132 @@
+//! Confirmed earnings attributed to the runtimes and presets which earned them
+//!
+//! Every run stores the totals in history, so that earnings of the recent days can be
+//! told apart from the lifetime ones.
+
+use anyhow::Result;
+use bigdecimal::ToPrimitive;
+use chrono::{Duration, Utc};
+use prettytable::{cell, format, row, Table};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use structopt::StructOpt;
+
+use crate::command::{CommandOptions, YaCommand};
+use crate::display;
+use crate::history::{self, Entry};
+use crate::workload;
+
+const HISTORY: &str = "earnings-attribution";
+
+/// Periods, in days, the recent earnings are shown for.
+const PERIODS: &[i64] = &[7, 30];
+
+#[derive(StructOpt)]
+pub struct EarningsCommand {
+    /// Print the attribution as JSON instead of a table
+    #[structopt(long)]
+    json: bool,
+}
+
+/// Confirmed GLM per runtime and per preset.
+#[derive(Default, Deserialize, Serialize)]
+pub struct Attribution {
+    pub runtimes: BTreeMap<String, f64>,
+    /// Agreements whose pricing matches no current preset are counted as "unknown"
+    pub presets: BTreeMap<String, f64>,
+}
+
+pub async fn collect(cmd: &YaCommand) -> Result<Attribution> {
+    let (agreements, payments, presets) = futures::future::try_join3(
+        async { tokio::task::spawn_blocking(workload::agreements).await? },
+        workload::payments(cmd),
+        cmd.ya_provider()?.presets(),
+    )
+    .await?;
+    let mut attribution = Attribution::default();
+    for work in agreements {
+        let confirmed = match payments.get(&work.agreement_id) {
+            Some(payments) => payments.confirmed.to_f64().unwrap_or_default(),
+            None => continue,
+        };
+        let preset = workload::preset_name(&work.offer, &work.runtime, &presets)
+            .unwrap_or_else(|| "unknown".to_string());
+        *attribution.runtimes.entry(work.runtime).or_default() += confirmed;
+        *attribution.presets.entry(preset).or_default() += confirmed;
+    }
+    Ok(attribution)
+}
+
+/// Growth of a total since the oldest sample in the last `days`.
+fn earned_in(
+    history: &[Entry<Attribution>],
+    days: i64,
+    total: f64,
+    value: impl Fn(&Attribution) -> Option<f64>,
+) -> Option<f64> {
+    let start = Utc::now() - Duration::days(days);
+    let first = history.iter().find(|entry| entry.ts >= start)?;
+    Some(total - value(&first.record).unwrap_or_default())
+}
+
+/// Rows of one group (runtimes or presets) with the totals and the recent earnings.
+fn add_group(
+    table: &mut Table,
+    title: String,
+    attribution: &Attribution,
+    history: &[Entry<Attribution>],
+    group: impl Fn(&Attribution) -> &BTreeMap<String, f64>,
+) {
+    table.add_row(row![b->title]);
+    for (name, total) in group(attribution) {
+        let mut row = row![format!("  {}", name), r->display::glm_f64(*total)];
+        for days in PERIODS {
+            let earned = earned_in(history, *days, *total, |a| group(a).get(name).copied());
+            row.add_cell(cell!(r->earned
+                .map(display::glm_f64)
+                .unwrap_or_else(|| "-".to_string())));
+        }
+        table.add_row(row);
+    }
+}
+
+pub async fn run(options: &CommandOptions, args: EarningsCommand) -> Result</*exit code*/ i32> {
+    let cmd = YaCommand::new(options)?;
+    let attribution = collect(&cmd).await?;
+    let history = history::load::<Attribution>(HISTORY)?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&attribution)?);
+    } else {
+        let mut table = Table::new();
+        table.set_format(*format::consts::FORMAT_CLEAN);
+        let mut titles = row![tr!("earnings-name"), tr!("earnings-total")];
+        for days in PERIODS {
+            titles.add_cell(cell!(tr!("earnings-last-days", days = days)));
+        }
+        table.set_titles(titles);
+        add_group(
+            &mut table,
+            tr!("earnings-runtimes"),
+            &attribution,
+            &history,
+            |a| &a.runtimes,
+        );
+        add_group(
+            &mut table,
+            tr!("earnings-presets"),
+            &attribution,
+            &history,
+            |a| &a.presets,
+        );
+        table.printstd();
+    }
+
+    // replayed earnings aren't this node's
+    if !cmd.is_replay() {
+        if let Err(e) = history::append(HISTORY, attribution) {
+            tracing::warn!("failed to record the earnings: {:?}", e);
+        }
+    }
+    Ok(0)
+}
@@ -0,0 +1,87 @@
+//! Exe-units registered in ya-provider, with a check of the binaries they point to
+
+use ansi_term::{Colour, Style};
+use anyhow::{bail, Result};
+use prettytable::{cell, format, row, Table};
+use std::path::Path;
+
+use crate::command::{CommandOptions, YaCommand};
+use crate::doctor::is_executable;
+
+/// Path with whether it can be run, red when it can't.
+fn describe(path: Option<&Path>) -> (String, ansi_term::ANSIString<'static>) {
+    match path {
+        Some(path) if is_executable(path) => (
+            path.display().to_string(),
+            Style::new().fg(Colour::Green).paint(tr!("exe-units-ok")),
+        ),
+        Some(path) if path.exists() => (
+            path.display().to_string(),
+            Style::new()
+                .fg(Colour::Red)
+                .paint(tr!("exe-units-not-executable")),
+        ),
+        Some(path) => (
+            path.display().to_string(),
+            Style::new().fg(Colour::Red).paint(tr!("exe-units-missing")),
+        ),
+        None => (
+            "-".to_string(),
+            Style::new().fg(Colour::Red).paint(tr!("exe-units-not-set")),
+        ),
+    }
+}
+
+pub async fn run(options: &CommandOptions) -> Result</*exit code*/ i32> {
+    let cmd = YaCommand::new(options)?;
+    let exe_units = cmd.ya_provider()?.exe_units().await?;
+    if exe_units.is_empty() {
+        bail!(tr!("runtime-none-installed"));
+    }
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    table.set_titles(row![
+        tr!("exe-units-name"),
+        tr!("which-version"),
+        tr!("exe-units-binary"),
+        tr!("which-path"),
+        tr!("exe-units-state")
+    ]);
+    let mut broken = false;
+    for exe_unit in &exe_units {
+        let binaries = [
+            (
+                tr!("exe-units-supervisor"),
+                exe_unit.supervisor_path.as_deref(),
+            ),
+            (tr!("exe-units-runtime"), exe_unit.runtime_path.as_deref()),
+        ];
+        for (i, (binary, path)) in binaries.into_iter().enumerate() {
+            broken |= !path.map_or(false, is_executable);
+            let (path, state) = describe(path);
+            let (name, version) = if i == 0 {
+                (
+                    exe_unit.name.clone(),
+                    exe_unit.version.clone().unwrap_or_else(|| "-".to_string()),
+                )
+            } else {
+                (String::new(), String::new())
+            };
+            table.add_row(row![name, version, binary, path, state]);
+        }
+        if !exe_unit.extra_args.is_empty() {
+            table.add_row(row![
+                "",
+                "",
+                tr!("exe-units-extra-args"),
+                exe_unit.extra_args.join(" ")
+            ]);
+        }
+    }
+    table.printstd();
+    if broken {
+        println!("\n{}", tr!("runtime-reinstall-hint"));
+    }
+    Ok(if broken { 1 } else { 0 })
+}
@@ -0,0 +1,264 @@
+//! Gas the node paid for its own transactions, eg. withdrawals and payments as a requestor
+//!
+//! yagna doesn't report the gas of sent transactions, the block explorers of the networks
+//! are asked instead. Their API keys are optional, set `YAGNA_USD_ETHERSCAN_API_KEY` and
+//! `YAGNA_USD_POLYGONSCAN_API_KEY` to get past the anonymous rate limits.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::http;
+use crate::rates::{self, FiatPrice, CURRENCY};
+
+/// Block explorer with an etherscan compatible API.
+struct Explorer {
+    network: &'static str,
+    api: &'static str,
+    key_var: &'static str,
+    symbol: &'static str,
+    /// Coingecko id of the native token, `None` on testnets
+    coin: Option<&'static str>,
+}
+
+const MAINNET_EXPLORERS: &[Explorer] = &[
+    Explorer {
+        network: "mainnet",
+        api: "https://api.etherscan.io/api",
+        key_var: "YAGNA_USD_ETHERSCAN_API_KEY",
+        symbol: "ETH",
+        coin: Some("ethereum"),
+    },
+    Explorer {
+        network: "polygon",
+        api: "https://api.polygonscan.com/api",
+        key_var: "YAGNA_USD_POLYGONSCAN_API_KEY",
+        symbol: "MATIC",
+        coin: Some("matic-network"),
+    },
+];
+
+const TESTNET_EXPLORERS: &[Explorer] = &[
+    Explorer {
+        network: "goerli",
+        api: "https://api-goerli.etherscan.io/api",
+        key_var: "YAGNA_USD_ETHERSCAN_API_KEY",
+        symbol: "ETH",
+        coin: None,
+    },
+    Explorer {
+        network: "mumbai",
+        api: "https://api-testnet.polygonscan.com/api",
+        key_var: "YAGNA_USD_POLYGONSCAN_API_KEY",
+        symbol: "MATIC",
+        coin: None,
+    },
+];
+
+/// Wei per native token.
+const WEI: f64 = 1e18;
+/// Explorers answer slowly when rate limited, one stuck request mustn't hold the others.
+const EXPLORER_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Gas of all transactions sent from the address on one network.
+#[derive(Deserialize, Serialize)]
+pub struct GasSpent {
+    pub network: String,
+    pub symbol: String,
+    pub transactions: usize,
+    /// In the native token, eg. MATIC
+    pub native: f64,
+    /// Fiat value at the current price of the native token
+    pub fiat: Option<f64>,
+    /// Price of the native token the fiat value was computed with
+    #[serde(default)]
+    pub rate: Option<FiatPrice>,
+    /// Why the block explorer could not be asked, the figures above are then zero
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Transaction {
+    from: String,
+    gas_used: String,
+    gas_price: String,
+}
+
+#[derive(Deserialize)]
+struct TxList {
+    status: String,
+    message: String,
+    result: serde_json::Value,
+}
+
+fn fetch_transactions(explorer: &Explorer, address: &str) -> Result<Vec<Transaction>> {
+    let mut request = http::get(explorer.api)?
+        .timeout(EXPLORER_TIMEOUT)
+        .query("module", "account")
+        .query("action", "txlist")
+        .query("address", address)
+        .query("sort", "asc");
+    if let Ok(key) = std::env::var(explorer.key_var) {
+        request = request.query("apikey", &key);
+    }
+    let list: TxList = request.call().map_err(http::explain)?.into_json()?;
+    match list.status.as_str() {
+        "1" => Ok(serde_json::from_value(list.result)?),
+        _ if list.message.starts_with("No transactions") => Ok(vec![]),
+        _ => Err(anyhow!(
+            "{}: {} {}",
+            explorer.network,
+            list.message,
+            list.result
+        )),
+    }
+}
+
+fn gas_spent(explorer: &Explorer, address: &str) -> Result<GasSpent> {
+    let sent = fetch_transactions(explorer, address)?
+        .into_iter()
+        .filter(|tx| tx.from.eq_ignore_ascii_case(address))
+        .collect::<Vec<_>>();
+    let native = sent
+        .iter()
+        .map(|tx| {
+            let used = tx.gas_used.parse::<f64>().unwrap_or_default();
+            let price = tx.gas_price.parse::<f64>().unwrap_or_default();
+            used * price / WEI
+        })
+        .sum();
+    Ok(GasSpent {
+        network: explorer.network.to_string(),
+        symbol: explorer.symbol.to_string(),
+        transactions: sent.len(),
+        native,
+        fiat: None,
+        rate: None,
+        error: None,
+    })
+}
+
+/// Gas spent by `address` on the mainnets, or the testnets.
+pub async fn collect(address: &str, testnet: bool) -> Result<Vec<GasSpent>> {
+    let explorers = if testnet {
+        TESTNET_EXPLORERS
+    } else {
+        MAINNET_EXPLORERS
+    };
+    let mut spent = vec![];
+    for explorer in explorers {
+        let address = address.to_string();
+        let result = tokio::task::spawn_blocking(move || gas_spent(explorer, &address))
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|result| result);
+        // a failing explorer leaves the other networks shown
+        let gas = result.unwrap_or_else(|e| {
+            tracing::debug!("{} explorer failed: {:?}", explorer.network, e);
+            GasSpent {
+                network: explorer.network.to_string(),
+                symbol: explorer.symbol.to_string(),
+                transactions: 0,
+                native: 0.0,
+                fiat: None,
+                rate: None,
+                error: Some(format!("{:#}", e)),
+            }
+        });
+        spent.push((explorer, gas));
+    }
+
+    // the native tokens of all networks are priced in one rate lookup
+    let coins = spent
+        .iter()
+        .filter(|(_, gas)| gas.transactions > 0)
+        .filter_map(|(explorer, _)| explorer.coin)
+        .collect::<Vec<_>>();
+    if !coins.is_empty() {
+        match rates::prices(coins.clone(), CURRENCY).await {
+            Ok(prices) => {
+                for (coin, rate) in coins.into_iter().zip(prices) {
+                    let priced = spent.iter_mut().filter(|(explorer, gas)| {
+                        explorer.coin == Some(coin) && gas.transactions > 0
+                    });
+                    for (_, gas) in priced {
+                        gas.fiat = rate.price.map(|price| price * gas.native);
+                        gas.rate = Some(rate.clone());
+                    }
+                }
+            }
+            Err(e) => tracing::debug!("{} prices unavailable: {:?}", coins.join(", "), e),
+        }
+    }
+    Ok(spent.into_iter().map(|(_, gas)| gas).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    const KEY_VAR: &str = "YAGNA_USD_TEST_EXPLORER_API_KEY";
+    const KEY: &str = "private-explorer-key";
+    const ADDRESS: &str = "0x1a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d";
+
+    fn explorer(api: String) -> Explorer {
+        Explorer {
+            network: "test",
+            api: Box::leak(api.into_boxed_str()),
+            key_var: KEY_VAR,
+            symbol: "ETH",
+            coin: None,
+        }
+    }
+
+    /// Explorer on localhost answering every request with 403.
+    fn rejecting_explorer() -> Explorer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                // the request ends with an empty line
+                let mut reader = BufReader::new(&stream);
+                let mut line = String::new();
+                while reader.read_line(&mut line).map_or(false, |read| read > 2) {
+                    line.clear();
+                }
+                stream
+                    .write_all(
+                        b"HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    )
+                    .ok();
+            }
+        });
+        explorer(format!("http://127.0.0.1:{}/api", port))
+    }
+
+    /// Explorer on a localhost port nothing listens on.
+    fn unreachable_explorer() -> Explorer {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        explorer(format!("http://127.0.0.1:{}/api", port))
+    }
+
+    #[test]
+    fn failed_explorer_call_hides_the_api_key() {
+        std::env::set_var("YAGNA_USD_PROXY", "off");
+        std::env::set_var(KEY_VAR, KEY);
+
+        for explorer in [rejecting_explorer(), unreachable_explorer()] {
+            let error = match fetch_transactions(&explorer, ADDRESS) {
+                Ok(_) => panic!("{} answered", explorer.api),
+                Err(e) => format!("{:#}", e),
+            };
+            assert!(error.contains("127.0.0.1"), "{}", error);
+            assert!(!error.contains(KEY), "{}", error);
+        }
+    }
+}
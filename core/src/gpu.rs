@@ -0,0 +1,88 @@
+//! GPU runtimes, drivers and presets of GPU providers
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::command::{RuntimeInfo, YaCommand};
+use crate::utils::get_command_output;
+
+/// Runtimes whose name contains any of these are considered GPU runtimes.
+const GPU_RUNTIME_KEYWORDS: &[&str] = &["gpu", "nvidia", "cuda"];
+
+#[derive(Deserialize, Serialize)]
+pub struct GpuStatus {
+    pub runtimes: Vec<RuntimeInfo>,
+    pub driver_version: Option<String>,
+    pub cuda_version: Option<String>,
+    /// Active presets which use one of the GPU runtimes.
+    pub active_presets: Vec<String>,
+}
+
+impl GpuStatus {
+    /// Nothing GPU related was found, so there is no point in showing the section.
+    pub fn is_empty(&self) -> bool {
+        self.runtimes.is_empty() && self.driver_version.is_none()
+    }
+}
+
+fn is_gpu_runtime(name: &str) -> bool {
+    let name = name.to_lowercase();
+    GPU_RUNTIME_KEYWORDS
+        .iter()
+        .any(|keyword| name.contains(keyword))
+}
+
+pub async fn collect(cmd: &YaCommand) -> Result<GpuStatus> {
+    let runtimes = cmd
+        .ya_provider()?
+        .exe_units()
+        .await?
+        .into_iter()
+        .filter(|runtime| is_gpu_runtime(&runtime.name))
+        .collect::<Vec<_>>();
+
+    let active_presets = if runtimes.is_empty() {
+        vec![]
+    } else {
+        let active = cmd.ya_provider()?.active_presets().await?;
+        cmd.ya_provider()?
+            .presets()
+            .await?
+            .into_iter()
+            .filter(|preset| active.contains(&preset.name))
+            .filter(|preset| {
+                runtimes
+                    .iter()
+                    .any(|runtime| runtime.name == preset.exeunit_name)
+            })
+            .map(|preset| preset.name)
+            .collect()
+    };
+
+    let (driver_version, cuda_version) = nvidia_versions().await;
+    Ok(GpuStatus {
+        runtimes,
+        driver_version,
+        cuda_version,
+        active_presets,
+    })
+}
+
+/// Driver and CUDA versions from the `nvidia-smi` banner, eg.
+/// `| NVIDIA-SMI 535.54.03    Driver Version: 535.54.03    CUDA Version: 12.2 |`
+async fn nvidia_versions() -> (Option<String>, Option<String>) {
+    let output = match get_command_output("nvidia-smi", &[]).await {
+        Ok(output) => output,
+        Err(e) => {
+            tracing::debug!("nvidia-smi unavailable: {}", e);
+            return (None, None);
+        }
+    };
+    let field = |label: &str| {
+        output.lines().find_map(|line| {
+            let rest = &line[line.find(label)? + label.len()..];
+            rest.split_whitespace().next().map(String::from)
+        })
+    };
+    (field("Driver Version:"), field("CUDA Version:"))
+}
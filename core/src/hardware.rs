@@ -0,0 +1,287 @@
+//! Inventory of hardware resources the node could offer
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use sysinfo::{DiskExt, ProcessorExt, RefreshKind, System, SystemExt};
+
+use crate::bench::{self, MachineBench};
+use crate::command::{CommandOptions, ResourceProfile, YaCommand};
+use crate::display::TimeDisplay;
+use crate::history::Entry;
+#[cfg(target_os = "linux")]
+use crate::utils::get_command_output;
+use crate::utils::yagna_datadir;
+
+#[derive(Deserialize, Serialize)]
+pub struct DiskSpace {
+    pub path: PathBuf,
+    pub free: u64,
+    pub total: u64,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct Hardware {
+    pub cpu_model: Option<String>,
+    pub physical_cores: Option<usize>,
+    pub logical_cores: usize,
+    pub total_memory: u64,
+    pub available_memory: u64,
+    pub disk: Option<DiskSpace>,
+    pub gpus: Vec<String>,
+    /// Last `bench machine` result
+    #[serde(default)]
+    pub bench: Option<Entry<MachineBench>>,
+    /// Resources of the active ya-provider profile, left out when ya-provider can't tell
+    #[serde(default)]
+    pub offered: Option<OfferedResources>,
+}
+
+const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+/// Machines with at least this many threads are worth offering more than a sliver of.
+const BIG_MACHINE_THREADS: usize = 8;
+/// Offering less than this share of a big machine's CPU and RAM is reported.
+const UNDERUSED_SHARE: f64 = 0.25;
+
+/// Resources offered through the active ya-provider profile
+#[derive(Deserialize, Serialize)]
+pub struct OfferedResources {
+    pub profile: String,
+    pub resources: ResourceProfile,
+}
+
+impl Hardware {
+    /// Resources the profile offers which the machine doesn't have, tasks using them fail.
+    pub fn overcommitted(&self) -> Vec<String> {
+        let offered = match &self.offered {
+            Some(offered) => &offered.resources,
+            None => return vec![],
+        };
+        let mut warnings = vec![];
+        if offered.cpu_threads > self.logical_cores {
+            warnings.push(tr!(
+                "hardware-offer-threads",
+                offered = offered.cpu_threads,
+                available = self.logical_cores
+            ));
+        }
+        if offered.mem_gib * GIB > self.total_memory as f64 {
+            warnings.push(tr!(
+                "hardware-offer-memory",
+                offered = format!("{:.1}", offered.mem_gib),
+                available = format!("{:.1}", self.total_memory as f64 / GIB)
+            ));
+        }
+        if let Some(disk) = &self.disk {
+            if offered.storage_gib * GIB > disk.free as f64 {
+                warnings.push(tr!(
+                    "hardware-offer-storage",
+                    offered = format!("{:.1}", offered.storage_gib),
+                    available = format!("{:.1}", disk.free as f64 / GIB)
+                ));
+            }
+        }
+        warnings
+    }
+
+    /// A big machine offering only a small share of its CPU and RAM.
+    pub fn underused(&self) -> Option<String> {
+        let offered = &self.offered.as_ref()?.resources;
+        if self.logical_cores < BIG_MACHINE_THREADS {
+            return None;
+        }
+        let cpu_share = offered.cpu_threads as f64 / self.logical_cores as f64;
+        let mem_share = offered.mem_gib * GIB / self.total_memory as f64;
+        if cpu_share < UNDERUSED_SHARE && mem_share < UNDERUSED_SHARE {
+            Some(tr!(
+                "hardware-offer-underused",
+                cpu = format!("{:.0}", cpu_share * 100.0),
+                memory = format!("{:.0}", mem_share * 100.0)
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+pub async fn run(options: &CommandOptions) -> Result</*exit code*/ i32> {
+    let cmd = YaCommand::new(options)?;
+    let hardware = collect_with_offer(&cmd).await;
+    crate::status::hardware_table(&hardware, &TimeDisplay::local(Utc::now())).printstd();
+    Ok(0)
+}
+
+pub async fn collect() -> Hardware {
+    let sys = System::new_with_specifics(
+        RefreshKind::new()
+            .with_cpu()
+            .with_memory()
+            .with_disks_list(),
+    );
+    let cpu_model = Some(sys.global_processor_info().brand().trim().to_string())
+        .filter(|brand| !brand.is_empty());
+
+    Hardware {
+        cpu_model,
+        physical_cores: sys.physical_core_count(),
+        logical_cores: sys.processors().len(),
+        // sysinfo reports memory in KiB
+        total_memory: sys.total_memory() * 1024,
+        available_memory: sys.available_memory() * 1024,
+        disk: yagna_datadir().and_then(|datadir| datadir_space(&sys, datadir)),
+        gpus: gpus().await,
+        bench: bench::latest_machine(),
+        offered: None,
+    }
+}
+
+async fn offered(cmd: &YaCommand) -> Result<OfferedResources> {
+    let (mut profiles, profile) = futures::future::try_join(
+        cmd.ya_provider()?.profiles(),
+        cmd.ya_provider()?.active_profile(),
+    )
+    .await?;
+    let resources = profiles
+        .remove(&profile)
+        .ok_or_else(|| anyhow::anyhow!("no resource profile named {:?}", profile))?;
+    Ok(OfferedResources { profile, resources })
+}
+
+/// The machine with what the active ya-provider profile offers of it.
+pub async fn collect_with_offer(cmd: &YaCommand) -> Hardware {
+    let (mut hardware, offered) = futures::future::join(collect(), offered(cmd)).await;
+    hardware.offered = offered
+        .map_err(|e| tracing::debug!("ya-provider resource profile unavailable: {:?}", e))
+        .ok();
+    hardware
+}
+
+/// Space left on the disk holding the yagna data directory.
+fn datadir_space(sys: &System, datadir: PathBuf) -> Option<DiskSpace> {
+    let resolved = datadir.canonicalize().unwrap_or_else(|_| datadir.clone());
+    sys.disks()
+        .iter()
+        .filter(|disk| resolved.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().components().count())
+        .map(|disk| DiskSpace {
+            path: datadir,
+            free: disk.available_space(),
+            total: disk.total_space(),
+        })
+}
+
+#[cfg(target_os = "linux")]
+async fn gpus() -> Vec<String> {
+    let mut gpus = match get_command_output(
+        "nvidia-smi",
+        &["--query-gpu=name", "--format=csv,noheader"],
+    )
+    .await
+    {
+        Ok(output) => output
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect::<Vec<_>>(),
+        Err(e) => {
+            tracing::debug!("nvidia-smi unavailable: {}", e);
+            vec![]
+        }
+    };
+    let has_nvidia_names = !gpus.is_empty();
+    for (vendor, device) in pci_display_devices() {
+        if vendor == "0x10de" && has_nvidia_names {
+            continue;
+        }
+        gpus.push(format!(
+            "{} [{}:{}]",
+            pci_vendor_name(&vendor),
+            vendor.trim_start_matches("0x"),
+            device.trim_start_matches("0x")
+        ));
+    }
+    gpus
+}
+
+/// Vendor and device ids of PCI display controllers.
+#[cfg(target_os = "linux")]
+fn pci_display_devices() -> Vec<(String, String)> {
+    use std::path::Path;
+
+    let read = |path: &Path| {
+        std::fs::read_to_string(path)
+            .map(|s| s.trim().to_string())
+            .ok()
+    };
+    let entries = match std::fs::read_dir("/sys/bus/pci/devices") {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            read(&path.join("class"))
+                .map(|class| class.starts_with("0x03"))
+                .unwrap_or(false)
+        })
+        .filter_map(|path| Some((read(&path.join("vendor"))?, read(&path.join("device"))?)))
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn pci_vendor_name(vendor: &str) -> &str {
+    match vendor {
+        "0x10de" => "NVIDIA",
+        "0x1002" => "AMD",
+        "0x8086" => "Intel",
+        "0x1af4" => "virtio",
+        "0x15ad" => "VMware",
+        "0x1234" => "QEMU",
+        _ => vendor,
+    }
+}
+
+#[cfg(target_os = "windows")]
+async fn gpus() -> Vec<String> {
+    match crate::utils::get_command_output(
+        "wmic",
+        &["path", "win32_VideoController", "get", "name"],
+    )
+    .await
+    {
+        Ok(output) => output
+            .lines()
+            .skip(1)
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect(),
+        Err(e) => {
+            tracing::debug!("wmic failed: {}", e);
+            vec![]
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+async fn gpus() -> Vec<String> {
+    match crate::utils::get_command_output("system_profiler", &["SPDisplaysDataType"]).await {
+        Ok(output) => output
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("Chipset Model:"))
+            .map(|model| model.trim().to_string())
+            .collect(),
+        Err(e) => {
+            tracing::debug!("system_profiler failed: {}", e);
+            vec![]
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+async fn gpus() -> Vec<String> {
+    vec![]
+}
@@ -0,0 +1,61 @@
+//! Local history of measurements, kept as JSON lines in the yagna-usd data directory
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+use crate::utils::project_dirs;
+
+/// Single timestamped record of a history file.
+#[derive(Serialize, Deserialize)]
+pub struct Entry<T> {
+    pub ts: DateTime<Utc>,
+    #[serde(flatten)]
+    pub record: T,
+}
+
+fn path(kind: &str) -> Result<PathBuf> {
+    let dirs = project_dirs().ok_or_else(|| anyhow!("unable to determine data directory"))?;
+    Ok(dirs
+        .data_dir()
+        .join("history")
+        .join(format!("{}.jsonl", kind)))
+}
+
+/// Appends `record` stamped with the current time to the `kind` history.
+pub fn append<T: Serialize>(kind: &str, record: T) -> Result<()> {
+    let path = path(kind)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let entry = Entry {
+        ts: Utc::now(),
+        record,
+    };
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("opening history file {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+    Ok(())
+}
+
+/// Reads the whole `kind` history, oldest first. Malformed lines are skipped.
+pub fn load<T: DeserializeOwned>(kind: &str) -> Result<Vec<Entry<T>>> {
+    let path = path(kind)?;
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let file = fs::File::open(&path)
+        .with_context(|| format!("opening history file {}", path.display()))?;
+    Ok(BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect())
+}
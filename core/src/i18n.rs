@@ -0,0 +1,154 @@
+//! Translation of user facing messages
+//!
+//! Messages live in Fluent-style catalogs (`locales/<lang>.ftl`): one `id = text`
+//! entry per line with `{ $name }` placeables. English is embedded and serves as the
+//! fallback for ids missing from the selected language. Community translations are
+//! either embedded below or dropped into `<config dir>/locales/<lang>.ftl`.
+//!
+//! The language is taken from `YAGNA_USD_LANG`, then `LC_ALL`, `LC_MESSAGES` and `LANG`.
+
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+
+use crate::utils::project_dirs;
+
+const FALLBACK: &str = include_str!("../locales/en.ftl");
+const BUILTIN: &[(&str, &str)] = &[("pl", include_str!("../locales/pl.ftl"))];
+
+type Catalog = HashMap<String, String>;
+
+struct Translations {
+    fallback: Catalog,
+    selected: Option<Catalog>,
+}
+
+lazy_static! {
+    static ref TRANSLATIONS: Translations = Translations::load(&language());
+}
+
+macro_rules! tr {
+    ($id:expr) => {
+        $crate::i18n::translate($id, &[])
+    };
+    ($id:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::translate($id, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
+
+impl Translations {
+    fn load(lang: &str) -> Self {
+        let fallback = parse(FALLBACK);
+        let mut selected = BUILTIN
+            .iter()
+            .find(|(code, _)| *code == lang)
+            .map(|(_, source)| parse(source));
+
+        if let Some(source) = project_dirs()
+            .map(|dirs| {
+                dirs.config_dir()
+                    .join("locales")
+                    .join(format!("{}.ftl", lang))
+            })
+            .and_then(|path| std::fs::read_to_string(path).ok())
+        {
+            selected
+                .get_or_insert_with(Catalog::new)
+                .extend(parse(&source));
+        }
+
+        Translations { fallback, selected }
+    }
+
+    fn lookup<'a>(&'a self, id: &'a str) -> &'a str {
+        self.selected
+            .as_ref()
+            .and_then(|catalog| catalog.get(id))
+            .or_else(|| self.fallback.get(id))
+            .map(String::as_str)
+            .unwrap_or(id)
+    }
+}
+
+fn parse(source: &str) -> Catalog {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, '=');
+            let id = parts.next()?.trim();
+            let text = parts.next()?.trim();
+            Some((id.to_string(), text.to_string()))
+        })
+        .collect()
+}
+
+/// Selected language code, eg. "pl" for `LANG=pl_PL.UTF-8`.
+pub fn language() -> String {
+    ["YAGNA_USD_LANG", "LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|locale| {
+            locale
+                .split(|c| c == '_' || c == '-' || c == '.' || c == '@')
+                .next()
+                .map(str::to_lowercase)
+        })
+        .unwrap_or_else(|| "en".to_string())
+}
+
+/// Returns the message `id` in the selected language with `args` substituted.
+///
+/// Unknown ids are returned verbatim so that missing entries are easy to spot.
+pub fn translate(id: &str, args: &[(&str, String)]) -> String {
+    let mut text = TRANSLATIONS.lookup(id).to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{ ${} }}", name), value);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut names = text
+            .match_indices("{ $")
+            .filter_map(|(start, _)| {
+                let rest = &text[start + 3..];
+                rest.find(" }").map(|end| &rest[..end])
+            })
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    #[test]
+    fn builtin_catalogs_translate_every_message() {
+        let fallback = parse(FALLBACK);
+        for (lang, source) in BUILTIN {
+            let catalog = parse(source);
+            let mut missing = fallback
+                .keys()
+                .filter(|id| !catalog.contains_key(*id))
+                .collect::<Vec<_>>();
+            missing.sort();
+            assert!(missing.is_empty(), "{}.ftl lacks {:?}", lang, missing);
+            for (id, text) in &catalog {
+                let english = fallback
+                    .get(id)
+                    .unwrap_or_else(|| panic!("{}.ftl has unknown id {}", lang, id));
+                assert_eq!(
+                    placeholders(text),
+                    placeholders(english),
+                    "{}.ftl: placeholders of {}",
+                    lang,
+                    id
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+#![recursion_limit = "512"]
+//! Collectors, models and renderers behind the `yagna-usd` CLI
+//!
+//! The whole provider status is collected with [`collect_status`], the sections to
+//! collect are selected with [`StatusQuery`]:
+//!
+//! ```no_run
+//! # async fn example(options: &yagna_usd_core::command::CommandOptions) -> anyhow::Result<()> {
+//! use yagna_usd_core::{collect_status, StatusQuery};
+//!
+//! let snapshot = collect_status(options, &StatusQuery::default()).await?;
+//! println!("yagna running: {}", snapshot.is_running);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Each CLI subcommand lives in its own module with a `run` function, so it can be
+//! embedded as a whole as well.
+
+#[macro_use]
+mod i18n;
+
+pub mod address;
+pub mod agreements;
+pub mod allocations;
+mod appkey;
+pub mod bench;
+pub mod command;
+pub mod diff;
+pub mod display;
+pub mod doctor;
+pub mod drivers;
+pub mod earnings;
+pub mod exe_units;
+pub mod gas;
+pub mod gpu;
+pub mod hardware;
+pub mod history;
+mod http;
+pub mod logs;
+pub mod market;
+pub mod net;
+pub mod outbound;
+pub mod payments;
+pub mod platform;
+pub mod presets;
+pub mod price;
+pub mod processes;
+pub mod profile;
+pub mod profit;
+mod progress;
+pub mod provider;
+pub mod rates;
+pub mod releases;
+pub mod requestor;
+pub mod resources;
+pub mod runtime;
+pub mod self_update;
+pub mod selftest;
+pub mod service;
+pub mod snapshot;
+pub mod stats;
+pub mod status;
+pub mod terms;
+pub mod top;
+pub mod uptime;
+mod utils;
+pub mod wallet;
+pub mod which;
+pub mod workload;
+
+pub use status::{collect_status, StatusQuery, StatusSnapshot};
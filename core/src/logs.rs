@@ -0,0 +1,398 @@
+//! Discovery, parsing and tailing of the yagna and ya-provider log files
+
+use ansi_term::{Colour, Style};
+use anyhow::{bail, Result};
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use structopt::StructOpt;
+
+use crate::utils::{provider_datadir, yagna_datadir};
+
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Daemon {
+    Yagna,
+    Provider,
+}
+
+impl Daemon {
+    pub fn name(self) -> &'static str {
+        match self {
+            Daemon::Yagna => "yagna",
+            Daemon::Provider => "ya-provider",
+        }
+    }
+
+    /// Directory the daemon writes its logs to, honoring the same variables as the daemon.
+    pub fn log_dir(self) -> Option<PathBuf> {
+        match self {
+            Daemon::Yagna => yagna_datadir(),
+            Daemon::Provider => provider_datadir(),
+        }
+    }
+
+    /// Log files of the daemon, oldest first, the current one last.
+    pub fn log_files(self) -> Vec<PathBuf> {
+        let prefix = format!("{}_r", self.name());
+        let entries = match self.log_dir().and_then(|dir| std::fs::read_dir(dir).ok()) {
+            Some(entries) => entries,
+            None => return vec![],
+        };
+        let mut files = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                name.starts_with(&prefix) && name.ends_with(".log")
+            })
+            .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+            .collect::<Vec<_>>();
+        files.sort();
+        files.into_iter().map(|(_, path)| path).collect()
+    }
+}
+
+pub struct LogLine {
+    pub ts: Option<DateTime<Utc>>,
+    pub level: Option<String>,
+    /// Module which logged the line, eg. "ya_provider::market"
+    pub target: Option<String>,
+    pub message: String,
+}
+
+impl LogLine {
+    /// Parses `[2021-06-01T12:00:00.000+0200 INFO  ya_provider::market] message`,
+    /// keeping unparsable lines (eg. continuation of multiline messages) as plain messages.
+    pub fn parse(line: &str) -> Self {
+        let plain = || LogLine {
+            ts: None,
+            level: None,
+            target: None,
+            message: line.to_string(),
+        };
+        let rest = match line.strip_prefix('[') {
+            Some(rest) => rest,
+            None => return plain(),
+        };
+        let (header, message) = match rest.split_once(']') {
+            Some(parts) => parts,
+            None => return plain(),
+        };
+        let mut fields = header.split_whitespace();
+        let ts = match fields.next().and_then(parse_timestamp) {
+            Some(ts) => ts,
+            None => return plain(),
+        };
+        LogLine {
+            ts: Some(ts),
+            level: fields.next().map(String::from),
+            target: fields.next().map(String::from),
+            message: message.trim().to_string(),
+        }
+    }
+}
+
+fn parse_timestamp(ts: &str) -> Option<DateTime<Utc>> {
+    DateTime::<FixedOffset>::parse_from_str(ts, "%Y-%m-%dT%H:%M:%S%.f%z")
+        .or_else(|_| DateTime::parse_from_rfc3339(ts))
+        .map(|ts| ts.with_timezone(&Utc))
+        .ok()
+}
+
+/// Replaces bracketed ids (`[0x1234…]`) with `[…]`, so messages about different
+/// agreements, activities or nodes can be grouped together.
+pub fn mask_ids(message: &str) -> String {
+    let mut masked = String::with_capacity(message.len());
+    let mut in_brackets = false;
+    for c in message.chars() {
+        match c {
+            '[' => {
+                in_brackets = true;
+                masked.push_str("[…]");
+            }
+            ']' => in_brackets = false,
+            _ if !in_brackets => masked.push(c),
+            _ => (),
+        }
+    }
+    masked
+}
+
+/// Log lines written by the daemon since `since` which `keep` accepts, the others are
+/// dropped while reading.
+pub fn lines_since(
+    daemon: Daemon,
+    since: DateTime<Utc>,
+    keep: impl Fn(&LogLine) -> bool,
+) -> Result<Vec<LogLine>> {
+    let mut lines = vec![];
+    for path in daemon.log_files() {
+        let modified = path
+            .metadata()?
+            .modified()
+            .unwrap_or_else(|_| SystemTime::now());
+        if DateTime::<Utc>::from(modified) < since {
+            continue;
+        }
+        lines.extend(read_since(BufReader::new(File::open(&path)?), since, &keep));
+    }
+    Ok(lines)
+}
+
+/// Timestamped lines of `reader` logged at `since` or later which `keep` accepts.
+fn read_since<'a>(
+    reader: impl BufRead + 'a,
+    since: DateTime<Utc>,
+    keep: &'a impl Fn(&LogLine) -> bool,
+) -> impl Iterator<Item = LogLine> + 'a {
+    reader
+        .lines()
+        .map_while(Result::ok)
+        .map(|line| LogLine::parse(&line))
+        .filter(move |line| line.ts.map(|ts| ts >= since).unwrap_or(false) && keep(line))
+}
+
+/// Deduplicated error or warning logged recently.
+#[derive(Deserialize, Serialize)]
+pub struct Problem {
+    pub daemon: Daemon,
+    pub level: String,
+    pub message: String,
+    pub count: usize,
+}
+
+/// Errors and warnings logged by both daemons since `since`, most frequent first.
+pub async fn problems(since: DateTime<Utc>) -> Result<Vec<Problem>> {
+    tokio::task::spawn_blocking(move || problems_since(since)).await?
+}
+
+fn is_problem(line: &LogLine) -> bool {
+    matches!(line.level.as_deref(), Some("ERROR") | Some("WARN"))
+}
+
+fn problems_since(since: DateTime<Utc>) -> Result<Vec<Problem>> {
+    let mut problems: Vec<Problem> = vec![];
+    for daemon in [Daemon::Yagna, Daemon::Provider] {
+        for line in lines_since(daemon, since, is_problem)? {
+            let level = line.level.unwrap_or_default();
+            let message = mask_ids(&line.message);
+            match problems
+                .iter_mut()
+                .find(|p| p.daemon == daemon && p.level == level && p.message == message)
+            {
+                Some(problem) => problem.count += 1,
+                None => problems.push(Problem {
+                    daemon,
+                    level,
+                    message,
+                    count: 1,
+                }),
+            }
+        }
+    }
+    problems.sort_by(|a, b| b.count.cmp(&a.count));
+    Ok(problems)
+}
+
+#[derive(StructOpt)]
+pub struct LogsCommand {
+    /// Keep printing lines as they are written
+    #[structopt(short, long)]
+    follow: bool,
+    /// Show ya-provider logs (the default)
+    #[structopt(long, conflicts_with = "yagna")]
+    provider: bool,
+    /// Show yagna logs
+    #[structopt(long)]
+    yagna: bool,
+    /// Number of last lines to show
+    #[structopt(short = "n", long, default_value = "50")]
+    lines: usize,
+}
+
+pub async fn run(args: LogsCommand) -> Result</*exit code*/ i32> {
+    let daemon = if args.yagna {
+        Daemon::Yagna
+    } else {
+        Daemon::Provider
+    };
+    let colored = atty::is(atty::Stream::Stdout);
+
+    if daemon.log_files().is_empty() {
+        return journal(daemon, &args, colored).await;
+    }
+    let (follow, lines) = (args.follow, args.lines);
+    tokio::task::spawn_blocking(move || tail(daemon, lines, follow, colored)).await??;
+    Ok(0)
+}
+
+/// Line colored by its severity.
+fn render(line: &str, colored: bool) -> String {
+    if !colored {
+        return line.to_string();
+    }
+    let style = match LogLine::parse(line).level.as_deref() {
+        Some("ERROR") => Style::new().fg(Colour::Red),
+        Some("WARN") => Style::new().fg(Colour::Yellow),
+        Some("DEBUG") | Some("TRACE") => Style::new().dimmed(),
+        _ => Style::new(),
+    };
+    style.paint(line).to_string()
+}
+
+fn tail(daemon: Daemon, lines: usize, follow: bool, colored: bool) -> Result<()> {
+    let mut path = match daemon.log_files().pop() {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+    let mut reader = BufReader::new(File::open(&path)?);
+    let mut last = VecDeque::with_capacity(lines);
+    for line in reader.by_ref().lines().map_while(Result::ok) {
+        last.push_back(line);
+        if last.len() > lines {
+            last.pop_front();
+        }
+    }
+    for line in last {
+        println!("{}", render(&line, colored));
+    }
+
+    while follow {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? > 0 {
+            print!("{}", render(&line, colored));
+            continue;
+        }
+        // the daemon rotates its log, switch to the newest file once the current one is drained
+        match daemon.log_files().pop() {
+            Some(newest) if newest != path => {
+                reader = BufReader::new(File::open(&newest)?);
+                path = newest;
+            }
+            _ => thread::sleep(FOLLOW_POLL_INTERVAL),
+        }
+    }
+    Ok(())
+}
+
+/// Falls back to the systemd journal when the daemon doesn't write log files.
+#[cfg(target_os = "linux")]
+async fn journal(daemon: Daemon, args: &LogsCommand, colored: bool) -> Result<i32> {
+    use anyhow::anyhow;
+    use std::process::Stdio;
+    use tokio::io::AsyncBufReadExt;
+
+    let mut command = tokio::process::Command::new("journalctl");
+    command
+        .arg(format!("_COMM={}", daemon.name()))
+        .args(&["--no-pager", "-o", "cat", "-n"])
+        .arg(args.lines.to_string())
+        .stdout(Stdio::piped())
+        .kill_on_drop(true);
+    if args.follow {
+        command.arg("--follow");
+    }
+    let mut child = command.spawn()?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| anyhow!("journalctl has no stdout"))?;
+    let mut lines = tokio::io::BufReader::new(stdout).lines();
+    let mut any = false;
+    while let Some(line) = lines.next_line().await? {
+        any = true;
+        println!("{}", render(&line, colored));
+    }
+    child.await?;
+    if !any {
+        bail!(tr!("logs-not-found", daemon = daemon.name()));
+    }
+    Ok(0)
+}
+
+#[cfg(not(target_os = "linux"))]
+async fn journal(daemon: Daemon, _args: &LogsCommand, _colored: bool) -> Result<i32> {
+    bail!(tr!("logs-not-found", daemon = daemon.name()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const LOG: &str = "\
+[2021-06-01T11:59:59.999+0200 WARN  ya_provider::market] too early
+[2021-06-01T12:00:00.000+0200 ERROR ya_provider::market] failed [0xabc]
+  continuation of the message
+[2021-06-01T12:30:00.000+0200 INFO  ya_provider::market] subscribed offer
+[2021-06-01T12:45:00+02:00 WARN  ya_provider::execution] retrying
+";
+
+    #[test]
+    fn parses_header() {
+        let line = LogLine::parse(
+            "[2021-06-01T12:00:00.000+0200 INFO  ya_provider::market] Subscribed offer [abc]",
+        );
+        assert_eq!(line.ts, Some(Utc.ymd(2021, 6, 1).and_hms(10, 0, 0)));
+        assert_eq!(line.level.as_deref(), Some("INFO"));
+        assert_eq!(line.target.as_deref(), Some("ya_provider::market"));
+        assert_eq!(line.message, "Subscribed offer [abc]");
+
+        let line = LogLine::parse("[2021-06-01T12:00:00Z WARN] no target");
+        assert_eq!(line.level.as_deref(), Some("WARN"));
+        assert_eq!(line.target, None);
+        assert_eq!(line.message, "no target");
+    }
+
+    #[test]
+    fn keeps_unparsable_lines_as_messages() {
+        for text in [
+            "  at line 3",
+            "[not a date INFO x] message",
+            "[unclosed",
+            "",
+        ] {
+            let line = LogLine::parse(text);
+            assert_eq!(line.ts, None);
+            assert_eq!(line.level, None);
+            assert_eq!(line.message, text);
+        }
+    }
+
+    #[test]
+    fn masks_bracketed_ids() {
+        assert_eq!(
+            mask_ids("agreement [0x12ab] of [node] failed"),
+            "agreement […] of […] failed"
+        );
+        assert_eq!(mask_ids("no ids"), "no ids");
+        assert_eq!(mask_ids("unclosed [0x12ab"), "unclosed […]");
+    }
+
+    #[test]
+    fn reads_the_window_only() {
+        let since = Utc.ymd(2021, 6, 1).and_hms(10, 0, 0);
+        let messages = |keep: &dyn Fn(&LogLine) -> bool| {
+            read_since(LOG.as_bytes(), since, &keep)
+                .map(|line| line.message)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            messages(&|_| true),
+            ["failed [0xabc]", "subscribed offer", "retrying"]
+        );
+        assert_eq!(messages(&is_problem), ["failed [0xabc]", "retrying"]);
+        let later = since + chrono::Duration::hours(1);
+        assert_eq!(
+            read_since(LOG.as_bytes(), later, &|_: &LogLine| true).count(),
+            0
+        );
+    }
+}
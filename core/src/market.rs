@@ -0,0 +1,125 @@
+//! Market activity of the provider: offers, proposals and agreements
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::appkey;
+use crate::command::YaCommand;
+use crate::logs::{self, Daemon, LogLine};
+
+/// ya-provider log messages marking market events, matched case-insensitively.
+const OFFER_SUBSCRIBED: &str = "subscribed offer";
+const PROPOSAL_RECEIVED: &str = "got proposal";
+const PROPOSAL_REJECTED: &str = "rejecting proposal";
+const AGREEMENT_RECEIVED: &str = "got agreement";
+const REASON: &str = "reason:";
+
+/// Number of distinct rejection reasons shown.
+const TOP_REASONS: usize = 5;
+
+#[derive(Default, Deserialize, Serialize)]
+pub struct Counters {
+    pub offers: usize,
+    pub proposals: usize,
+    pub rejected: usize,
+    pub agreements: usize,
+}
+
+/// Whether the line marks one of the events [`Counters`] counts.
+fn is_market_event(line: &LogLine) -> bool {
+    let message = line.message.to_lowercase();
+    [
+        OFFER_SUBSCRIBED,
+        PROPOSAL_RECEIVED,
+        PROPOSAL_REJECTED,
+        AGREEMENT_RECEIVED,
+    ]
+    .iter()
+    .any(|event| message.contains(event))
+}
+
+impl Counters {
+    fn count<'a>(lines: impl Iterator<Item = &'a LogLine>) -> Self {
+        let mut counters = Counters::default();
+        for line in lines {
+            let message = line.message.to_lowercase();
+            if message.contains(OFFER_SUBSCRIBED) {
+                counters.offers += 1;
+            } else if message.contains(PROPOSAL_RECEIVED) {
+                counters.proposals += 1;
+            } else if message.contains(PROPOSAL_REJECTED) {
+                counters.rejected += 1;
+            } else if message.contains(AGREEMENT_RECEIVED) {
+                counters.agreements += 1;
+            }
+        }
+        counters
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct MarketActivity {
+    /// Offers currently subscribed on the market
+    pub active_offers: usize,
+    pub last_hour: Counters,
+    pub last_day: Counters,
+}
+
+pub async fn collect(cmd: &YaCommand) -> Result<MarketActivity> {
+    let app_key = appkey::get_app_key(cmd).await?;
+    let mkt_api: ya_client::market::MarketProviderApi =
+        ya_client::web::WebClient::with_token(&app_key).interface()?;
+    let active_offers = mkt_api.get_offers().await?.len();
+
+    let now = Utc::now();
+    let lines = tokio::task::spawn_blocking(move || {
+        logs::lines_since(Daemon::Provider, now - Duration::days(1), is_market_event)
+    })
+    .await??;
+    let hour_ago = now - Duration::hours(1);
+
+    Ok(MarketActivity {
+        active_offers,
+        last_hour: Counters::count(
+            lines
+                .iter()
+                .filter(|line| line.ts.map(|ts| ts >= hour_ago).unwrap_or(false)),
+        ),
+        last_day: Counters::count(lines.iter()),
+    })
+}
+
+/// Reason of a rejected proposal or a failed agreement with ids masked, so
+/// the same reason given to different requestors is counted together.
+fn rejection_reason(line: &LogLine) -> Option<String> {
+    // ascii lowercasing keeps byte offsets valid for slicing the original message
+    let message = line.message.to_ascii_lowercase();
+    if !(message.contains("reject") || message.contains("fail")) {
+        return None;
+    }
+    let start = message.find(REASON)? + REASON.len();
+    let reason = line.message[start..].trim().trim_end_matches('.');
+    Some(logs::mask_ids(reason)).filter(|reason| !reason.is_empty())
+}
+
+/// Most frequent reasons of rejected proposals and agreements in the last 24h.
+pub async fn rejection_reasons() -> Result<Vec<(String, usize)>> {
+    let since = Utc::now() - Duration::days(1);
+    let lines = tokio::task::spawn_blocking(move || {
+        logs::lines_since(Daemon::Provider, since, |line| {
+            rejection_reason(line).is_some()
+        })
+    })
+    .await??;
+
+    let mut counts = HashMap::<String, usize>::new();
+    for reason in lines.iter().filter_map(rejection_reason) {
+        *counts.entry(reason).or_default() += 1;
+    }
+    let mut reasons = counts.into_iter().collect::<Vec<_>>();
+    reasons.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    reasons.truncate(TOP_REASONS);
+    Ok(reasons)
+}
@@ -0,0 +1,158 @@
+//! Network connectivity diagnostics
+
+use anyhow::{anyhow, Result};
+use std::net::{IpAddr, SocketAddr, UdpSocket};
+use structopt::StructOpt;
+use url::Url;
+
+use crate::command::{CommandOptions, NetStatus, YaCommand};
+use crate::doctor::{exit_code, print_checks, Check};
+use crate::processes;
+
+const DEFAULT_RELAY: &str = "yacn2.dev.golem.network:7477";
+const DEFAULT_BIND_URL: &str = "udp://0.0.0.0:11500";
+const DEFAULT_NET_TYPE: &str = "hybrid";
+
+#[derive(StructOpt)]
+pub enum NetCommand {
+    /// Check relay reachability, listening port, NAT and inbound connectivity
+    Check,
+}
+
+pub async fn run(options: &CommandOptions, command: NetCommand) -> Result</*exit code*/ i32> {
+    match command {
+        NetCommand::Check => check(options).await,
+    }
+}
+
+/// Relay server used by yagna, from `YA_NET_RELAY_HOST` of the running daemon.
+pub fn relay_host() -> String {
+    processes::daemon_env("yagna", "YA_NET_RELAY_HOST").unwrap_or_else(|| DEFAULT_RELAY.into())
+}
+
+/// Networking mode of yagna (hybrid or central), from `YA_NET_TYPE` of the running daemon.
+pub fn net_type() -> String {
+    processes::daemon_env("yagna", "YA_NET_TYPE").unwrap_or_else(|| DEFAULT_NET_TYPE.into())
+}
+
+async fn check(options: &CommandOptions) -> Result<i32> {
+    let cmd = YaCommand::new(options)?;
+    let relay_host = relay_host();
+    let bind_port = bind_port()?;
+
+    let net_status = if cmd.is_yagna_running().await? {
+        Some(cmd.yagna()?.net_status().await)
+    } else {
+        None
+    };
+    let relay = tokio::net::lookup_host(relay_host.as_str())
+        .await
+        .ok()
+        .and_then(|mut addrs| addrs.next());
+
+    let mut checks = vec![check_relay(&relay_host, relay, &net_status)];
+    checks.push(check_port(bind_port));
+
+    let public_address = match &net_status {
+        Some(Ok(NetStatus {
+            public_address: Some(address),
+            ..
+        })) => address.parse::<SocketAddr>().ok(),
+        _ => None,
+    };
+    checks.push(check_nat(relay, bind_port, public_address));
+    checks.push(check_inbound(&net_status, public_address));
+
+    print_checks(&checks);
+    Ok(exit_code(&checks))
+}
+
+/// UDP port yagna binds to, from `YA_NET_BIND_URL`.
+fn bind_port() -> Result<u16> {
+    let bind_url = processes::daemon_env("yagna", "YA_NET_BIND_URL")
+        .unwrap_or_else(|| DEFAULT_BIND_URL.into());
+    Url::parse(&bind_url)?
+        .port()
+        .ok_or_else(|| anyhow!("no port in YA_NET_BIND_URL: {}", bind_url))
+}
+
+fn check_relay(
+    host: &str,
+    relay: Option<SocketAddr>,
+    net_status: &Option<Result<NetStatus>>,
+) -> Check {
+    let name = tr!("net-relay");
+    let relay = match relay {
+        Some(relay) => relay,
+        None => {
+            return Check::fail(
+                name,
+                tr!("net-relay-unresolved", host = host),
+                tr!("net-relay-dns-hint"),
+            )
+        }
+    };
+    match net_status {
+        Some(Ok(status)) if status.public_address.is_some() || status.sessions > Some(0) => {
+            Check::pass(name, tr!("net-relay-connected", relay = relay))
+        }
+        Some(Ok(_)) => Check::fail(
+            name,
+            tr!("net-relay-not-connected", relay = relay),
+            tr!("net-relay-firewall-hint"),
+        ),
+        Some(Err(e)) => Check::warn(name, e.to_string(), tr!("net-relay-firewall-hint")),
+        None => Check::warn(
+            name,
+            tr!("net-relay-resolved-only", relay = relay),
+            tr!("doctor-daemon-hint"),
+        ),
+    }
+}
+
+fn check_port(port: u16) -> Check {
+    let name = tr!("net-port", port = port);
+    // yagna holds the port when it is listening, so a successful bind means nobody does
+    match UdpSocket::bind(("0.0.0.0", port)) {
+        Ok(_) => Check::warn(name, tr!("net-port-free"), tr!("doctor-daemon-hint")),
+        Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
+            Check::pass(name, tr!("net-port-listening"))
+        }
+        Err(e) => Check::warn(name, e.to_string(), tr!("net-port-hint")),
+    }
+}
+
+/// Address of the interface used to reach `remote`; no packets are sent.
+fn local_ip(remote: SocketAddr) -> Option<IpAddr> {
+    let socket = UdpSocket::bind(("0.0.0.0", 0)).ok()?;
+    socket.connect(remote).ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
+fn check_nat(relay: Option<SocketAddr>, bind_port: u16, public: Option<SocketAddr>) -> Check {
+    let name = tr!("net-nat");
+    let (public, local) = match (public, relay.and_then(local_ip)) {
+        (Some(public), Some(local)) => (public, local),
+        _ => return Check::warn(name, tr!("net-nat-unknown"), tr!("net-nat-unknown-hint")),
+    };
+    if public.ip() == local {
+        Check::pass(name, tr!("net-nat-none", address = public))
+    } else if public.port() == bind_port {
+        Check::pass(name, tr!("net-nat-port-preserving", address = public))
+    } else {
+        Check::warn(
+            name,
+            tr!("net-nat-port-translating", address = public),
+            tr!("net-port-hint"),
+        )
+    }
+}
+
+fn check_inbound(net_status: &Option<Result<NetStatus>>, public: Option<SocketAddr>) -> Check {
+    let name = tr!("net-inbound");
+    match (net_status, public) {
+        (_, Some(public)) => Check::pass(name, tr!("net-inbound-public", address = public)),
+        (Some(Ok(_)), None) => Check::warn(name, tr!("net-inbound-relayed"), tr!("net-port-hint")),
+        _ => Check::warn(name, tr!("net-inbound-unknown"), tr!("doctor-daemon-hint")),
+    }
+}
@@ -0,0 +1,212 @@
+//! Internet access of the payloads run by the provider: the outbound rules, the domain
+//! whitelist and the certificates trusted to sign manifests of audited payloads
+
+use ansi_term::{Colour, Style};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use prettytable::{cell, format, row, Table};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+use crate::command::{CommandOptions, KeystoreCert, OutboundRules, WhitelistEntry, YaCommand};
+
+#[derive(StructOpt)]
+pub struct OutboundCommand {
+    #[structopt(subcommand)]
+    action: Option<OutboundAction>,
+}
+
+#[derive(StructOpt)]
+pub enum OutboundAction {
+    /// Show the rules, whitelist and keystore (the default)
+    Show,
+    /// Domains payloads of any requestor may reach in whitelist mode
+    Whitelist(WhitelistAction),
+    /// Certificates trusted to sign manifests of audited payloads
+    Cert(CertAction),
+    /// Set what payloads of any requestor may reach
+    Everyone {
+        #[structopt(possible_values = &["all", "none", "whitelist"])]
+        mode: String,
+    },
+    /// Allow outbound access of payloads according to the rules
+    Enable,
+    /// Block all outbound access of payloads
+    Disable,
+}
+
+#[derive(StructOpt)]
+pub enum WhitelistAction {
+    List,
+    /// Add domain patterns, eg. `add github.com pypi.org`
+    Add {
+        #[structopt(required = true)]
+        patterns: Vec<String>,
+        /// How the patterns are matched
+        #[structopt(long = "type", default_value = "strict", possible_values = &["strict", "wildcard", "regex"])]
+        kind: String,
+    },
+    /// Remove entries by ID, as shown by `list`
+    Remove {
+        #[structopt(required = true)]
+        ids: Vec<String>,
+    },
+}
+
+#[derive(StructOpt)]
+pub enum CertAction {
+    /// Trust the certificates of a PEM or DER file
+    Import {
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+    },
+    /// Remove certificates by ID, as shown by `outbound`
+    Remove {
+        #[structopt(required = true)]
+        ids: Vec<String>,
+    },
+}
+
+/// Domains requestor payloads commonly download from.
+const COMMON_DOMAINS: &[&str] = &[
+    "github.com",
+    "raw.githubusercontent.com",
+    "pypi.org",
+    "files.pythonhosted.org",
+    "registry.npmjs.org",
+    "huggingface.co",
+    "crates.io",
+];
+
+/// Common domains payloads of any requestor can't reach with these settings.
+pub fn blocked_domains(rules: &OutboundRules, whitelist: &[WhitelistEntry]) -> Vec<&'static str> {
+    if !rules.enabled || rules.everyone == "none" {
+        return COMMON_DOMAINS.to_vec();
+    }
+    if rules.everyone != "whitelist" {
+        return vec![];
+    }
+    COMMON_DOMAINS
+        .iter()
+        .copied()
+        .filter(|domain| !whitelist.iter().any(|entry| entry.matches(domain)))
+        .collect()
+}
+
+fn expiry(cert: &KeystoreCert, now: DateTime<Utc>) -> ansi_term::ANSIString<'static> {
+    let not_after = match &cert.not_after {
+        Some(not_after) => not_after.clone(),
+        None => return Style::new().paint("-"),
+    };
+    match DateTime::parse_from_rfc3339(&not_after) {
+        Ok(ts) if ts.with_timezone(&Utc) < now => Style::new()
+            .fg(Colour::Red)
+            .paint(tr!("outbound-cert-expired", date = not_after)),
+        _ => Style::new().paint(not_after),
+    }
+}
+
+pub async fn run(options: &CommandOptions, command: OutboundCommand) -> Result</*exit code*/ i32> {
+    let cmd = YaCommand::new(options)?;
+    let provider = cmd.ya_provider()?;
+    match command.action.unwrap_or(OutboundAction::Show) {
+        OutboundAction::Show => return show(&cmd).await,
+        OutboundAction::Whitelist(WhitelistAction::List) => {
+            print_whitelist(cmd.ya_provider()?.whitelist().await?.as_slice());
+            return Ok(0);
+        }
+        OutboundAction::Whitelist(WhitelistAction::Add { patterns, kind }) => {
+            provider.whitelist_add(&patterns, &kind).await?
+        }
+        OutboundAction::Whitelist(WhitelistAction::Remove { ids }) => {
+            provider.whitelist_remove(&ids).await?
+        }
+        OutboundAction::Cert(CertAction::Import { path }) => provider.keystore_add(&path).await?,
+        OutboundAction::Cert(CertAction::Remove { ids }) => provider.keystore_remove(&ids).await?,
+        OutboundAction::Everyone { mode } => provider.set_outbound_everyone(&mode).await?,
+        OutboundAction::Enable => provider.set_outbound_enabled(true).await?,
+        OutboundAction::Disable => provider.set_outbound_enabled(false).await?,
+    }
+    // the state after the change
+    show(&cmd).await
+}
+
+fn print_whitelist(whitelist: &[WhitelistEntry]) {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    table.set_titles(row!["ID", tr!("outbound-pattern"), tr!("outbound-type")]);
+    for entry in whitelist {
+        table.add_row(row![entry.id, entry.pattern, entry.kind]);
+    }
+    table.printstd();
+}
+
+async fn show(cmd: &YaCommand) -> Result<i32> {
+    let (rules, whitelist, keystore) = futures::future::join3(
+        cmd.ya_provider()?.outbound_rules(),
+        cmd.ya_provider()?.whitelist(),
+        cmd.ya_provider()?.keystore(),
+    )
+    .await;
+    let rules = rules?;
+
+    let mode = if !rules.enabled {
+        Style::new().fg(Colour::Red).paint(tr!("outbound-disabled"))
+    } else {
+        Style::new().paint(tr!("outbound-mode", mode = rules.everyone.clone()))
+    };
+    println!("{} {}", tr!("outbound-everyone"), mode);
+    for (cert, mode) in &rules.audited_payload {
+        println!(
+            "{}",
+            tr!("outbound-audited", cert = cert.clone(), mode = mode.clone())
+        );
+    }
+
+    println!("\n{}", tr!("outbound-whitelist"));
+    let whitelist = match whitelist {
+        Ok(whitelist) => {
+            print_whitelist(&whitelist);
+            whitelist
+        }
+        Err(e) => {
+            println!(" {}", tr!("section-unavailable", reason = e.to_string()));
+            vec![]
+        }
+    };
+
+    println!("\n{}", tr!("outbound-keystore"));
+    match keystore {
+        Ok(certs) if certs.is_empty() => println!(" {}", tr!("outbound-no-certs")),
+        Ok(certs) => {
+            let now = Utc::now();
+            let mut table = Table::new();
+            table.set_format(*format::consts::FORMAT_CLEAN);
+            table.set_titles(row![
+                "ID",
+                tr!("outbound-subject"),
+                tr!("outbound-not-after")
+            ]);
+            for cert in &certs {
+                table.add_row(row![
+                    cert.id,
+                    cert.subject.clone().unwrap_or_default(),
+                    expiry(cert, now)
+                ]);
+            }
+            table.printstd();
+        }
+        Err(e) => println!(" {}", tr!("section-unavailable", reason = e.to_string())),
+    }
+
+    let blocked = blocked_domains(&rules, &whitelist);
+    if !blocked.is_empty() {
+        println!(
+            "\n{}",
+            Style::new()
+                .fg(Colour::Fixed(220))
+                .paint(tr!("outbound-blocked", domains = blocked.join(", ")))
+        );
+    }
+    Ok(0)
+}
@@ -0,0 +1,642 @@
+//! Payment documents of the provider, beyond the summaries yagna gives
+//!
+//! Acceptance times tell slow requestors apart, confirmation times slow payment drivers.
+
+use ansi_term::{Colour, Style};
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Duration, Utc};
+use prettytable::{row, Table};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use structopt::StructOpt;
+
+use ya_client::model::payment::{DebitNote, DocumentStatus, Invoice, InvoiceEventType};
+use ya_client::payment::PaymentApi;
+use ya_client::web::WebClient;
+
+use crate::appkey;
+use crate::command::{CommandOptions, YaCommand};
+use crate::display::{self, format_duration, TimeDisplay};
+use crate::gas::{self, GasSpent};
+use crate::logs::{self, Daemon, LogLine};
+use crate::rates::{self, CURRENCY};
+use crate::stats;
+use crate::status::section_table;
+use crate::utils::payment_account;
+
+/// Number of documents and the GLM they are for.
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct DocumentCount {
+    pub count: u64,
+    pub amount: BigDecimal,
+}
+
+/// Debit notes sent to requestors while their activities ran.
+#[derive(Default, Deserialize, Serialize)]
+pub struct DebitNoteStats {
+    pub issued: DocumentCount,
+    pub accepted: DocumentCount,
+    pub rejected: DocumentCount,
+}
+
+/// Counts the notes, the amount due grows with every note of an activity so the amount
+/// is taken from the latest note of each activity.
+fn count<'a>(notes: impl Iterator<Item = &'a DebitNote>) -> DocumentCount {
+    let mut count = 0;
+    let mut latest = HashMap::<&str, &DebitNote>::new();
+    for note in notes {
+        count += 1;
+        match latest.get(note.activity_id.as_str()) {
+            Some(known) if known.timestamp >= note.timestamp => (),
+            _ => {
+                latest.insert(&note.activity_id, note);
+            }
+        }
+    }
+    DocumentCount {
+        count,
+        amount: latest
+            .values()
+            .map(|note| note.total_amount_due.clone())
+            .sum(),
+    }
+}
+
+/// Debit notes issued by `node_id`, by the requestor's answer.
+pub async fn debit_note_stats(cmd: &YaCommand, node_id: &str) -> Result<DebitNoteStats> {
+    let app_key = appkey::get_app_key(cmd).await?;
+    let payment_api: PaymentApi = WebClient::with_token(&app_key).interface()?;
+    let notes = payment_api
+        .get_debit_notes::<Utc>(None, None)
+        .await?
+        .into_iter()
+        .filter(|note| note.issuer_id.to_string().eq_ignore_ascii_case(node_id))
+        .collect::<Vec<_>>();
+    Ok(DebitNoteStats {
+        issued: count(notes.iter()),
+        accepted: count(notes.iter().filter(|note| {
+            matches!(
+                note.status,
+                DocumentStatus::Accepted | DocumentStatus::Settled
+            )
+        })),
+        rejected: count(
+            notes
+                .iter()
+                .filter(|note| matches!(note.status, DocumentStatus::Rejected)),
+        ),
+    })
+}
+
+/// Requestors listed as the slowest to accept invoices.
+const SLOWEST_REQUESTORS: usize = 3;
+/// Events fetched per request to yagna.
+const EVENTS_PAGE: u32 = 1000;
+/// Longest span of `--days` and `--latency-days` honored, a century.
+const MAX_DAYS: i64 = 36_500;
+
+/// Time the requestors took to accept invoices.
+#[derive(Deserialize, Serialize)]
+pub struct AcceptanceLatency {
+    /// Invoices issued in the last `days` are taken into account
+    pub days: i64,
+    pub accepted: usize,
+    /// Median seconds from issuing an invoice to its acceptance
+    pub median: Option<i64>,
+    /// Requestor ids with their median seconds, slowest first
+    pub slowest: Vec<(String, i64)>,
+}
+
+/// Seconds below which `p` percent of the `latencies` fall, see [`stats::percentile`].
+fn percentile(latencies: &[i64], p: f64) -> Option<i64> {
+    let latencies = latencies.iter().map(|l| *l as f64).collect::<Vec<_>>();
+    stats::percentile(&latencies, p).map(|l| l as i64)
+}
+
+fn median(values: Vec<i64>) -> Option<i64> {
+    percentile(&values, 50.0)
+}
+
+/// `days` before now, longer spans than yagna has existed are cut so they can't overflow.
+fn days_ago(days: i64) -> DateTime<Utc> {
+    Utc::now() - Duration::days(days.clamp(0, MAX_DAYS))
+}
+
+/// When an invoice was accepted by the requestor and when its payment was confirmed.
+#[derive(Default)]
+struct InvoiceHistory {
+    accepted: Option<DateTime<Utc>>,
+    settled: Option<DateTime<Utc>>,
+}
+
+/// Invoices `node_id` issued since `since`, with their events.
+struct IssuedInvoices {
+    invoices: HashMap<String, Invoice>,
+    events: HashMap<String, InvoiceHistory>,
+}
+
+impl IssuedInvoices {
+    async fn fetch(cmd: &YaCommand, node_id: &str, since: DateTime<Utc>) -> Result<Self> {
+        let app_key = appkey::get_app_key(cmd).await?;
+        let payment_api: PaymentApi = WebClient::with_token(&app_key).interface()?;
+
+        let invoices = payment_api
+            .get_invoices(Some(since), None)
+            .await?
+            .into_iter()
+            .filter(|invoice| invoice.issuer_id.to_string().eq_ignore_ascii_case(node_id))
+            .map(|invoice| (invoice.invoice_id.clone(), invoice))
+            .collect::<HashMap<_, _>>();
+
+        let mut events = HashMap::<String, InvoiceHistory>::new();
+        let mut after = since;
+        loop {
+            let page = payment_api
+                .get_invoice_events(
+                    Some(&after),
+                    Some(std::time::Duration::from_millis(100)),
+                    Some(EVENTS_PAGE),
+                    None,
+                )
+                .await?;
+            let len = page.len();
+            for event in page {
+                after = after.max(event.event_date);
+                let history = events.entry(event.invoice_id).or_default();
+                match event.event_type {
+                    InvoiceEventType::InvoiceAcceptedEvent => {
+                        history.accepted = history.accepted.or(Some(event.event_date))
+                    }
+                    InvoiceEventType::InvoiceSettledEvent => {
+                        history.settled = history.settled.or(Some(event.event_date))
+                    }
+                    _ => (),
+                }
+            }
+            if len < EVENTS_PAGE as usize {
+                break;
+            }
+        }
+        Ok(IssuedInvoices { invoices, events })
+    }
+
+    /// Seconds between two events of the issued invoices, with the invoice.
+    fn latencies<'a>(
+        &'a self,
+        from: impl Fn(&Invoice, &InvoiceHistory) -> Option<DateTime<Utc>> + 'a,
+        to: impl Fn(&InvoiceHistory) -> Option<DateTime<Utc>> + 'a,
+    ) -> impl Iterator<Item = (&'a Invoice, i64)> + 'a {
+        self.events.iter().filter_map(move |(invoice_id, history)| {
+            let invoice = self.invoices.get(invoice_id)?;
+            let latency = (to(history)? - from(invoice, history)?).num_seconds();
+            Some((invoice, latency.max(0)))
+        })
+    }
+}
+
+/// Median acceptance time of the invoices `node_id` issued in the last `days`.
+pub async fn acceptance_latency(
+    cmd: &YaCommand,
+    node_id: &str,
+    days: i64,
+) -> Result<AcceptanceLatency> {
+    let issued = IssuedInvoices::fetch(cmd, node_id, days_ago(days)).await?;
+    Ok(acceptance(&issued, days))
+}
+
+fn acceptance(issued: &IssuedInvoices, days: i64) -> AcceptanceLatency {
+    let mut by_requestor = HashMap::<String, Vec<i64>>::new();
+    for (invoice, latency) in issued.latencies(
+        |invoice, _| Some(invoice.timestamp),
+        |history| history.accepted,
+    ) {
+        by_requestor
+            .entry(invoice.recipient_id.to_string())
+            .or_default()
+            .push(latency);
+    }
+    let all = by_requestor.values().flatten().copied().collect::<Vec<_>>();
+    let mut slowest = by_requestor
+        .into_iter()
+        .filter_map(|(requestor, latencies)| Some((requestor, median(latencies)?)))
+        .collect::<Vec<_>>();
+    slowest.sort_by(|a, b| b.1.cmp(&a.1));
+    slowest.truncate(SLOWEST_REQUESTORS);
+    AcceptanceLatency {
+        days,
+        accepted: all.len(),
+        median: median(all),
+        slowest,
+    }
+}
+
+/// Time from accepting invoices to confirming their payment on a payment platform.
+#[derive(Deserialize, Serialize)]
+pub struct ConfirmationLatency {
+    /// eg. "erc20-polygon-glm"
+    pub platform: String,
+    pub confirmed: usize,
+    /// Seconds
+    pub p50: Option<i64>,
+    pub p95: Option<i64>,
+}
+
+fn confirmation(issued: &IssuedInvoices) -> Vec<ConfirmationLatency> {
+    let mut by_platform = BTreeMap::<String, Vec<i64>>::new();
+    for (invoice, latency) in
+        issued.latencies(|_, history| history.accepted, |history| history.settled)
+    {
+        by_platform
+            .entry(invoice.payment_platform.clone())
+            .or_default()
+            .push(latency);
+    }
+    by_platform
+        .into_iter()
+        .map(|(platform, latencies)| ConfirmationLatency {
+            platform,
+            confirmed: latencies.len(),
+            p50: percentile(&latencies, 50.0),
+            p95: percentile(&latencies, 95.0),
+        })
+        .collect()
+}
+
+/// Hours of yagna logs searched for payment failures.
+pub const FAILURES_WINDOW_HOURS: i64 = 24;
+
+/// Log targets of the payment service and its drivers.
+const PAYMENT_TARGETS: &[&str] = &[
+    "ya_payment",
+    "ya_erc20_driver",
+    "ya_zksync_driver",
+    "erc20_payment_lib",
+];
+
+/// Payment error or retry logged by yagna, deduplicated.
+#[derive(Deserialize, Serialize)]
+pub struct PaymentFailure {
+    /// Module which logged it, eg. "ya_erc20_driver::erc20::wallet"
+    pub target: String,
+    pub reason: String,
+    pub count: usize,
+    pub last: DateTime<Utc>,
+}
+
+/// Payments which failed or are retried, as a provider and as a requestor.
+#[derive(Deserialize, Serialize)]
+pub struct FailedPayments {
+    /// Agreements with failed invoices, issued by the node
+    pub provider_failed: u64,
+    /// Agreements with invoices the node failed to pay
+    pub requestor_failed: u64,
+    /// Most frequent first
+    pub failures: Vec<PaymentFailure>,
+}
+
+impl FailedPayments {
+    pub fn is_empty(&self) -> bool {
+        self.provider_failed == 0 && self.requestor_failed == 0 && self.failures.is_empty()
+    }
+}
+
+/// Error, warning or retry logged by the payment drivers or the payment service.
+fn is_payment_failure(line: &LogLine) -> bool {
+    let is_payment = match &line.target {
+        Some(target) => PAYMENT_TARGETS.iter().any(|t| target.starts_with(t)),
+        None => false,
+    };
+    let failed = matches!(line.level.as_deref(), Some("ERROR") | Some("WARN"));
+    is_payment && (failed || line.message.to_lowercase().contains("retry"))
+}
+
+fn payment_failures(since: DateTime<Utc>) -> Result<Vec<PaymentFailure>> {
+    let mut failures: Vec<PaymentFailure> = vec![];
+    for line in logs::lines_since(Daemon::Yagna, since, is_payment_failure)? {
+        let target = line.target.unwrap_or_default();
+        let reason = logs::mask_ids(&line.message);
+        let ts = line.ts.unwrap_or(since);
+        match failures
+            .iter_mut()
+            .find(|f| f.target == target && f.reason == reason)
+        {
+            Some(failure) => {
+                failure.count += 1;
+                failure.last = failure.last.max(ts);
+            }
+            None => failures.push(PaymentFailure {
+                target,
+                reason,
+                count: 1,
+                last: ts,
+            }),
+        }
+    }
+    failures.sort_by(|a, b| b.count.cmp(&a.count));
+    Ok(failures)
+}
+
+/// Failed invoices, and payment errors yagna logged in the last [`FAILURES_WINDOW_HOURS`].
+pub async fn failed_payments(cmd: &YaCommand) -> Result<FailedPayments> {
+    let since = Utc::now() - Duration::hours(FAILURES_WINDOW_HOURS);
+    let (invoice_status, failures) =
+        futures::future::try_join(cmd.yagna()?.invoice_status(), async {
+            tokio::task::spawn_blocking(move || payment_failures(since)).await?
+        })
+        .await?;
+    Ok(FailedPayments {
+        provider_failed: invoice_status.provider.failed.agreements_count,
+        requestor_failed: invoice_status.requestor.failed.agreements_count,
+        failures,
+    })
+}
+
+#[derive(StructOpt)]
+pub struct PaymentsCommand {
+    /// Days of invoices taken into account
+    #[structopt(long, env = "YAGNA_USD_LATENCY_DAYS", default_value = "7")]
+    days: i64,
+    /// Count the gas spent on the test networks instead of the mainnets
+    #[structopt(long)]
+    testnet: bool,
+    /// Print the details as JSON instead of tables
+    #[structopt(long)]
+    json: bool,
+}
+
+/// Detailed payment view, telling slow requestors apart from slow payment drivers.
+#[derive(Serialize)]
+pub struct PaymentDetails {
+    pub debit_notes: DebitNoteStats,
+    pub acceptance: AcceptanceLatency,
+    pub confirmation: Vec<ConfirmationLatency>,
+    /// Left out when the block explorers could not be asked
+    pub gas: Option<Vec<GasSpent>>,
+}
+
+fn seconds(secs: Option<i64>) -> String {
+    secs.map(|secs| format_duration(Duration::seconds(secs)))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn detail_tables(details: &PaymentDetails) -> Vec<Table> {
+    let mut debit_notes = section_table(tr!("wallet-debit-notes"));
+    let rows = [
+        (
+            tr!("wallet-debit-notes-issued"),
+            &details.debit_notes.issued,
+        ),
+        (
+            tr!("wallet-debit-notes-accepted"),
+            &details.debit_notes.accepted,
+        ),
+        (
+            tr!("wallet-debit-notes-rejected"),
+            &details.debit_notes.rejected,
+        ),
+    ];
+    for (label, notes) in rows {
+        debit_notes.add_row(row![label, r->display::glm(&notes.amount), r->notes.count]);
+    }
+
+    let acceptance = &details.acceptance;
+    let mut accepted = section_table(tr!("wallet-acceptance", days = acceptance.days));
+    accepted.add_row(row![
+        tr!("payments-median"),
+        tr!(
+            "wallet-acceptance-value",
+            median = seconds(acceptance.median),
+            count = acceptance.accepted
+        )
+    ]);
+    for (requestor, median) in &acceptance.slowest {
+        accepted.add_row(row![requestor, seconds(Some(*median))]);
+    }
+
+    let mut confirmed = section_table(tr!("payments-confirmation"));
+    confirmed.add_row(row![
+        tr!("payments-platform"),
+        tr!("payments-confirmed"),
+        tr!("payments-p50"),
+        tr!("payments-p95")
+    ]);
+    if details.confirmation.is_empty() {
+        confirmed.add_row(row![tr!("payments-none-confirmed")]);
+    }
+    for latency in &details.confirmation {
+        confirmed.add_row(row![
+            latency.platform,
+            r->latency.confirmed,
+            r->seconds(latency.p50),
+            r->seconds(latency.p95)
+        ]);
+    }
+
+    let mut tables = vec![debit_notes, accepted, confirmed];
+    if let Some(gas) = &details.gas {
+        let mut spent = section_table(tr!("payments-gas"));
+        spent.add_row(row![
+            tr!("payments-network"),
+            tr!("payments-transactions"),
+            tr!("payments-gas-native"),
+            tr!("payments-gas-fiat", currency = CURRENCY.to_uppercase())
+        ]);
+        for network in gas {
+            if let Some(error) = &network.error {
+                spent.add_row(row![
+                    network.network,
+                    H3->Style::new().fg(Colour::Red).paint(tr!("payments-gas-failed", error = error.clone()))
+                ]);
+                continue;
+            }
+            spent.add_row(row![
+                network.network,
+                r->network.transactions,
+                r->format!("{:.6} {}", network.native, network.symbol),
+                r->network
+                    .fiat
+                    .map(display::fiat_f64)
+                    .unwrap_or_else(|| "-".to_string())
+            ]);
+        }
+        tables.push(spent);
+    }
+    tables
+}
+
+pub async fn run(options: &CommandOptions, args: PaymentsCommand) -> Result</*exit code*/ i32> {
+    let cmd = YaCommand::new(options)?;
+    let id = cmd.yagna()?.default_id().await?;
+    let since = days_ago(args.days);
+    let account = cmd
+        .ya_provider()?
+        .get_config()
+        .await
+        .map_err(|e| tracing::debug!("ya-provider config unavailable: {:?}", e))
+        .ok()
+        .and_then(|config| config.account);
+    let address = payment_account(&cmd, &account).await?;
+    let (debit_notes, issued, gas) = futures::future::join3(
+        debit_note_stats(&cmd, &id.node_id),
+        IssuedInvoices::fetch(&cmd, &id.node_id, since),
+        gas::collect(&address, args.testnet),
+    )
+    .await;
+    let (debit_notes, issued) = (debit_notes?, issued?);
+    let gas = gas
+        .map_err(|e| tracing::warn!("gas spending unavailable: {:#}", e))
+        .ok();
+    let details = PaymentDetails {
+        debit_notes,
+        acceptance: acceptance(&issued, args.days),
+        confirmation: confirmation(&issued),
+        gas,
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&details)?);
+    } else {
+        for table in detail_tables(&details) {
+            table.printstd();
+        }
+        let quotes = details
+            .gas
+            .iter()
+            .flatten()
+            .filter_map(|gas| Some((gas.symbol.as_str(), gas.rate.as_ref()?)))
+            .collect::<Vec<_>>();
+        let now = Utc::now();
+        if let Some(footnote) = rates::footnote(&quotes, now, &TimeDisplay::local(now)) {
+            println!("\n {}", footnote);
+        }
+    }
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use ya_client::model::NodeId;
+
+    fn node(n: u8) -> NodeId {
+        format!("0x{:040x}", n).parse().unwrap()
+    }
+
+    /// Invoices issued at the same time to `requestor` on `platform`, accepted and
+    /// settled the given seconds later.
+    fn issued(documents: &[(u8, &str, Option<i64>, Option<i64>)]) -> IssuedInvoices {
+        let issued_at = Utc.ymd(2021, 6, 1).and_hms(12, 0, 0);
+        let at = |secs: Option<i64>| secs.map(|secs| issued_at + Duration::seconds(secs));
+        let mut invoices = HashMap::new();
+        let mut events = HashMap::new();
+        for (n, (requestor, platform, accepted, settled)) in documents.iter().enumerate() {
+            let invoice_id = format!("invoice-{}", n);
+            invoices.insert(
+                invoice_id.clone(),
+                Invoice {
+                    invoice_id: invoice_id.clone(),
+                    issuer_id: node(0),
+                    recipient_id: node(*requestor),
+                    payee_addr: node(0).to_string(),
+                    payer_addr: node(*requestor).to_string(),
+                    payment_platform: platform.to_string(),
+                    timestamp: issued_at,
+                    agreement_id: format!("agreement-{}", n),
+                    activity_ids: vec![],
+                    amount: BigDecimal::from(1),
+                    payment_due_date: issued_at,
+                    status: DocumentStatus::Accepted,
+                },
+            );
+            events.insert(
+                invoice_id,
+                InvoiceHistory {
+                    accepted: at(*accepted),
+                    settled: at(*settled),
+                },
+            );
+        }
+        // events of invoices issued before the window have no invoice
+        events.insert(
+            "earlier".to_string(),
+            InvoiceHistory {
+                accepted: at(Some(1_000_000)),
+                settled: None,
+            },
+        );
+        IssuedInvoices { invoices, events }
+    }
+
+    #[test]
+    fn days_ago_is_bounded() {
+        let now = Utc::now();
+        let week = now - days_ago(7);
+        assert!(week >= Duration::days(7) && week < Duration::days(7) + Duration::minutes(1));
+        assert!(now - days_ago(-5) < Duration::minutes(1));
+        let longest = now - days_ago(i64::MAX);
+        assert!(longest >= Duration::days(MAX_DAYS));
+        assert!(longest < Duration::days(MAX_DAYS) + Duration::minutes(1));
+    }
+
+    #[test]
+    fn acceptance_medians_slowest_first() {
+        let issued = issued(&[
+            (1, "erc20-polygon-glm", Some(10), None),
+            (1, "erc20-polygon-glm", Some(30), None),
+            (1, "erc20-polygon-glm", Some(20), None),
+            (2, "erc20-polygon-glm", Some(600), None),
+            (3, "erc20-polygon-glm", Some(60), None),
+            // accepted before it was issued, by clock skew
+            (4, "erc20-polygon-glm", Some(-30), None),
+            (5, "erc20-polygon-glm", None, None),
+        ]);
+        let latency = acceptance(&issued, 7);
+        assert_eq!(latency.days, 7);
+        assert_eq!(latency.accepted, 6);
+        assert_eq!(latency.median, Some(20));
+        assert_eq!(
+            latency.slowest,
+            vec![
+                (node(2).to_string(), 600),
+                (node(3).to_string(), 60),
+                (node(1).to_string(), 20),
+            ]
+        );
+
+        let latency = acceptance(&issued(&[(4, "erc20-polygon-glm", Some(-30), None)]), 1);
+        assert_eq!(latency.median, Some(0));
+        assert_eq!(latency.slowest, vec![(node(4).to_string(), 0)]);
+
+        let latency = acceptance(&issued(&[]), 1);
+        assert_eq!((latency.accepted, latency.median), (0, None));
+        assert!(latency.slowest.is_empty());
+    }
+
+    #[test]
+    fn confirmation_by_platform() {
+        let mut documents = (1..=20)
+            .map(|n| (1, "erc20-polygon-glm", Some(0), Some(n * 10)))
+            .collect::<Vec<_>>();
+        documents.push((2, "erc20-mainnet-glm", Some(100), Some(40)));
+        documents.push((2, "erc20-mainnet-glm", Some(100), None));
+        let latencies = confirmation(&issued(&documents));
+
+        let platforms = latencies
+            .iter()
+            .map(|latency| latency.platform.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(platforms, ["erc20-mainnet-glm", "erc20-polygon-glm"]);
+        let mainnet = &latencies[0];
+        assert_eq!(
+            (mainnet.confirmed, mainnet.p50, mainnet.p95),
+            (1, Some(0), Some(0))
+        );
+        let polygon = &latencies[1];
+        assert_eq!(
+            (polygon.confirmed, polygon.p50, polygon.p95),
+            (20, Some(100), Some(190))
+        );
+    }
+}
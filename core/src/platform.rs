@@ -0,0 +1,257 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::ops::Not;
+
+#[cfg(target_os = "linux")]
+mod systemd;
+
+#[cfg(target_os = "linux")]
+pub use systemd::service_states;
+
+#[cfg(windows)]
+mod windows;
+
+#[cfg(windows)]
+pub use windows::service_states;
+
+#[cfg(target_os = "macos")]
+mod launchd;
+
+#[cfg(target_os = "macos")]
+pub use launchd::service_states;
+
+#[allow(dead_code)]
+#[derive(PartialEq, Deserialize, Serialize)]
+#[serde(tag = "status", content = "reason", rename_all = "kebab-case")]
+pub enum Status {
+    Valid,
+    Permission(Cow<'static, str>),
+    InvalidEnv(Cow<'static, str>),
+    NotImplemented,
+}
+
+impl Status {
+    pub fn is_implemented(&self) -> bool {
+        matches!(self, Status::NotImplemented).not()
+    }
+
+    pub fn problem(&self) -> Option<&str> {
+        match self {
+            Status::Permission(msg) => Some(msg.as_ref()),
+            Status::InvalidEnv(msg) => Some(msg.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn kvm_status() -> Status {
+    use nix::unistd::AccessFlags;
+    use std::path;
+    let dev_kvm = path::Path::new("/dev/kvm");
+    if !dev_kvm.exists() {
+        if path::Path::new("/dev/xen").exists() {
+            return Status::InvalidEnv(Cow::Owned(tr!("vm-xen-unsupported")));
+        }
+        if path::Path::new("/.dockerenv").exists() {
+            return Status::InvalidEnv(Cow::Owned(tr!("vm-docker-no-kvm")));
+        }
+        return Status::Permission(Cow::Owned(tr!("vm-kvm-not-installed")));
+    }
+    match nix::unistd::access(dev_kvm, AccessFlags::W_OK | AccessFlags::R_OK) {
+        Ok(()) => Status::Valid,
+        Err(_) => Status::Permission(Cow::Owned(tr!("vm-kvm-no-access"))),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn kvm_status() -> Status {
+    Status::NotImplemented
+}
+
+/// State of a yagna or provider service registered in the system service manager
+#[derive(Deserialize, Serialize)]
+pub struct ServiceState {
+    pub name: String,
+    pub manager: Cow<'static, str>,
+    pub state: String,
+    pub sub_state: Option<String>,
+    /// When the service entered its current state, ie. was last (re)started.
+    pub since: Option<DateTime<Utc>>,
+    pub restarts: Option<u32>,
+    pub recent_errors: Vec<String>,
+}
+
+impl ServiceState {
+    pub fn is_running(&self) -> bool {
+        matches!(self.state.as_str(), "active" | "running")
+    }
+
+    /// Command running `action` ("start" or "stop") on the service with its manager.
+    pub fn control_command(&self, action: &str) -> Vec<String> {
+        let argv: Vec<&str> = match self.manager.as_ref() {
+            "systemd" => vec!["systemctl", action, &self.name],
+            "systemd --user" => vec!["systemctl", "--user", action, &self.name],
+            "launchd" => vec!["launchctl", action, &self.name],
+            "windows service" => vec!["sc", action, &self.name],
+            "task scheduler" if action == "start" => vec!["schtasks", "/run", "/tn", &self.name],
+            "task scheduler" => vec!["schtasks", "/end", "/tn", &self.name],
+            _ => vec![],
+        };
+        argv.into_iter().map(str::to_string).collect()
+    }
+
+    /// Command starting the service with its manager, eg. "systemctl --user start golemsp".
+    pub fn start_command(&self) -> String {
+        match self.manager.as_ref() {
+            "systemd" => format!("sudo systemctl start {}", self.name),
+            "systemd --user" => format!("systemctl --user start {}", self.name),
+            "launchd" => format!("launchctl start {}", self.name),
+            "windows service" => format!("sc start {}", self.name),
+            "task scheduler" => format!("schtasks /run /tn {}", self.name),
+            manager => format!("{} start {}", manager, self.name),
+        }
+    }
+}
+
+/// Service names configured with `YAGNA_USD_SERVICE_UNITS` (comma separated),
+/// used instead of auto detection.
+#[cfg(any(target_os = "linux", target_os = "macos", windows))]
+fn configured_units() -> Option<Vec<String>> {
+    let units = std::env::var("YAGNA_USD_SERVICE_UNITS").ok()?;
+    Some(
+        units
+            .split(',')
+            .map(str::trim)
+            .filter(|unit| !unit.is_empty())
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub async fn service_states() -> Vec<ServiceState> {
+    vec![]
+}
+
+/// Kind of environment the node runs in, as far as it can be detected
+#[derive(Default, Deserialize, Serialize)]
+pub struct Environment {
+    pub container: Option<Cow<'static, str>>,
+    pub wsl: Option<u8>,
+    pub hypervisor: Option<String>,
+}
+
+impl Environment {
+    pub fn describe(&self) -> String {
+        let mut parts = vec![];
+        if let Some(container) = &self.container {
+            parts.push(container.to_string());
+        }
+        if let Some(version) = self.wsl {
+            parts.push(format!("WSL{}", version));
+        }
+        if let Some(hypervisor) = &self.hypervisor {
+            parts.push(hypervisor.clone());
+        }
+        if parts.is_empty() {
+            tr!("env-bare-metal")
+        } else {
+            parts.join(" / ")
+        }
+    }
+
+    /// Known limitations of this environment for providers.
+    pub fn limitations(&self, kvm_status: &Status) -> Vec<String> {
+        let kvm_ok = matches!(kvm_status, Status::Valid);
+        let mut limitations = vec![];
+        if let Some(container) = &self.container {
+            limitations.push(tr!("env-container-limits", container = container));
+        }
+        match self.wsl {
+            Some(1) => limitations.push(tr!("env-wsl1-limits")),
+            Some(_) if !kvm_ok => limitations.push(tr!("env-wsl2-limits")),
+            _ => (),
+        }
+        if let Some(hypervisor) = &self.hypervisor {
+            if !kvm_ok && self.wsl.is_none() {
+                limitations.push(tr!("env-nested-virt", hypervisor = hypervisor));
+            }
+        }
+        limitations
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn environment() -> Environment {
+    use std::fs;
+    use std::path::Path;
+
+    let read = |path: &str| fs::read_to_string(path).unwrap_or_default();
+
+    let cgroup = read("/proc/1/cgroup");
+    let container = if Path::new("/.dockerenv").exists() {
+        Some("docker")
+    } else if Path::new("/run/.containerenv").exists() {
+        Some("podman")
+    } else if cgroup.contains("kubepods") {
+        Some("kubernetes")
+    } else if cgroup.contains("docker") {
+        Some("docker")
+    } else if cgroup.contains("lxc") {
+        Some("lxc")
+    } else {
+        None
+    };
+
+    let osrelease = read("/proc/sys/kernel/osrelease").to_lowercase();
+    let wsl = if osrelease.contains("wsl2") {
+        Some(2)
+    } else if osrelease.contains("microsoft") {
+        Some(1)
+    } else {
+        None
+    };
+
+    let dmi = format!(
+        "{} {}",
+        read("/sys/class/dmi/id/sys_vendor"),
+        read("/sys/class/dmi/id/product_name")
+    )
+    .to_lowercase();
+    const HYPERVISORS: &[(&str, &str)] = &[
+        ("vmware", "VMware"),
+        ("virtualbox", "VirtualBox"),
+        ("innotek", "VirtualBox"),
+        ("qemu", "QEMU"),
+        ("kvm", "KVM"),
+        ("xen", "Xen"),
+        ("amazon ec2", "Amazon EC2"),
+        ("google compute engine", "Google Compute Engine"),
+        ("virtual machine", "Hyper-V"),
+        ("parallels", "Parallels"),
+    ];
+    let hypervisor = HYPERVISORS
+        .iter()
+        .find(|(pattern, _)| dmi.contains(pattern))
+        .map(|(_, name)| name.to_string())
+        .or_else(|| {
+            read("/proc/cpuinfo")
+                .lines()
+                .any(|line| line.starts_with("flags") && line.contains(" hypervisor"))
+                .then(|| tr!("env-unknown-hypervisor"))
+        })
+        .filter(|_| wsl.is_none());
+
+    Environment {
+        container: container.map(Cow::Borrowed),
+        wsl,
+        hypervisor,
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn environment() -> Environment {
+    Environment::default()
+}
@@ -0,0 +1,80 @@
+//! Service state of yagna launchd jobs on macOS
+
+use directories::UserDirs;
+
+use super::{configured_units, ServiceState};
+use crate::utils::get_command_output;
+
+/// Jobs whose label contains any of these are reported.
+const LABEL_KEYWORDS: &[&str] = &["yagna", "golem"];
+
+pub async fn service_states() -> Vec<ServiceState> {
+    let configured = configured_units();
+    let matches = |label: &str| match &configured {
+        Some(labels) => labels.iter().any(|configured| configured == label),
+        None => {
+            let label = label.to_lowercase();
+            LABEL_KEYWORDS.iter().any(|keyword| label.contains(keyword))
+        }
+    };
+
+    let mut states = match get_command_output("launchctl", &["list"]).await {
+        Ok(output) => output
+            .lines()
+            .skip(1)
+            .filter_map(|line| {
+                // PID	Status	Label
+                let mut columns = line.split('\t');
+                let pid = columns.next()?;
+                let last_exit = columns.next()?;
+                let label = columns.next()?;
+                Some((pid, last_exit, label))
+            })
+            .filter(|(_, _, label)| matches(label))
+            .map(|(pid, last_exit, label)| ServiceState {
+                name: label.to_string(),
+                manager: "launchd".into(),
+                state: if pid.parse::<u32>().is_ok() {
+                    "running".to_string()
+                } else {
+                    "loaded".to_string()
+                },
+                sub_state: Some(format!("last exit {}", last_exit)),
+                since: None,
+                restarts: None,
+                recent_errors: vec![],
+            })
+            .collect::<Vec<_>>(),
+        Err(e) => {
+            tracing::debug!("launchctl list failed: {}", e);
+            vec![]
+        }
+    };
+
+    // agents installed by the user but not loaded into launchd
+    let agents_dir = UserDirs::new().map(|dirs| dirs.home_dir().join("Library/LaunchAgents"));
+    if let Some(entries) = agents_dir.and_then(|dir| std::fs::read_dir(dir).ok()) {
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            if path.extension().map(|ext| ext != "plist").unwrap_or(true) {
+                continue;
+            }
+            let label = match path.file_stem() {
+                Some(stem) => stem.to_string_lossy().to_string(),
+                None => continue,
+            };
+            if matches(&label) && !states.iter().any(|state| state.name == label) {
+                states.push(ServiceState {
+                    name: label,
+                    manager: "launchd".into(),
+                    state: "not loaded".to_string(),
+                    sub_state: None,
+                    since: None,
+                    restarts: None,
+                    recent_errors: vec![],
+                });
+            }
+        }
+    }
+    states
+}
@@ -0,0 +1,112 @@
+//! Service state of yagna units managed by systemd
+
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
+
+use super::{configured_units, ServiceState};
+use crate::utils::get_command_output;
+
+/// Units installed by the golem installer, distro packages and common guides.
+const KNOWN_UNITS: &[&str] = &[
+    "golemsp.service",
+    "golem-provider.service",
+    "yagna.service",
+    "ya-provider.service",
+];
+
+const RECENT_ERRORS: &str = "5";
+
+pub async fn service_states() -> Vec<ServiceState> {
+    let units = configured_units()
+        .unwrap_or_else(|| KNOWN_UNITS.iter().map(|unit| unit.to_string()).collect());
+
+    let mut states = vec![];
+    for user in &[false, true] {
+        for unit in &units {
+            if let Some(state) = unit_state(unit, *user).await {
+                states.push(state);
+            }
+        }
+    }
+    states
+}
+
+fn scope_args(user: bool) -> Vec<&'static str> {
+    if user {
+        vec!["--user"]
+    } else {
+        vec![]
+    }
+}
+
+async fn unit_state(unit: &str, user: bool) -> Option<ServiceState> {
+    let mut args: Vec<&str> = scope_args(user);
+    args.extend(&[
+        "show",
+        unit,
+        "--property=LoadState,ActiveState,SubState,ActiveEnterTimestampMonotonic,NRestarts",
+    ]);
+    let output = get_command_output("systemctl", &args).await.ok()?;
+    let props = output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, '=');
+            Some((parts.next()?, parts.next()?))
+        })
+        .collect::<HashMap<_, _>>();
+    if props.get("LoadState").copied() != Some("loaded") {
+        return None;
+    }
+
+    Some(ServiceState {
+        name: unit.to_string(),
+        manager: if user { "systemd --user" } else { "systemd" }.into(),
+        state: props.get("ActiveState").unwrap_or(&"unknown").to_string(),
+        sub_state: props.get("SubState").map(|sub| sub.to_string()),
+        since: props
+            .get("ActiveEnterTimestampMonotonic")
+            .and_then(|usec| usec.parse::<i64>().ok())
+            .filter(|usec| *usec > 0)
+            .and_then(|usec| {
+                let boot = Utc::now() - Duration::seconds(uptime_secs()? as i64);
+                Some(boot + Duration::microseconds(usec))
+            }),
+        restarts: props.get("NRestarts").and_then(|n| n.parse().ok()),
+        recent_errors: recent_errors(unit, user).await,
+    })
+}
+
+fn uptime_secs() -> Option<f64> {
+    std::fs::read_to_string("/proc/uptime")
+        .ok()?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+async fn recent_errors(unit: &str, user: bool) -> Vec<String> {
+    let mut args: Vec<&str> = scope_args(user);
+    args.extend(&[
+        "-u",
+        unit,
+        "-p",
+        "err",
+        "--since=-24h",
+        "-n",
+        RECENT_ERRORS,
+        "-o",
+        "cat",
+        "--no-pager",
+    ]);
+    get_command_output("journalctl", &args)
+        .await
+        .map(|output| {
+            output
+                .lines()
+                .filter(|line| !line.trim().is_empty() && !line.starts_with("-- "))
+                .map(|line| line.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
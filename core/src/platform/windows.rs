@@ -0,0 +1,84 @@
+//! Service state of yagna registered as a Windows service or scheduled task
+
+use super::{configured_units, ServiceState};
+use crate::utils::get_command_output;
+
+const KNOWN_SERVICES: &[&str] = &["yagna", "golemsp", "golem-provider"];
+/// Scheduled tasks whose name contains any of these are reported.
+const TASK_KEYWORDS: &[&str] = &["yagna", "golem"];
+
+pub async fn service_states() -> Vec<ServiceState> {
+    let configured = configured_units();
+    let names = configured
+        .clone()
+        .unwrap_or_else(|| KNOWN_SERVICES.iter().map(|name| name.to_string()).collect());
+
+    let mut states = vec![];
+    for name in &names {
+        if let Some(state) = windows_service(name).await {
+            states.push(state);
+        }
+    }
+    states.extend(scheduled_tasks(configured.as_deref()).await);
+    states
+}
+
+async fn windows_service(name: &str) -> Option<ServiceState> {
+    // `sc query` exits with an error for services which are not installed
+    let output = get_command_output("sc", &["query", name]).await.ok()?;
+    let state = output
+        .lines()
+        .find(|line| line.trim_start().starts_with("STATE"))?
+        .split_whitespace()
+        .last()?
+        .to_lowercase();
+    Some(ServiceState {
+        name: name.to_string(),
+        manager: "windows service".into(),
+        state,
+        sub_state: None,
+        since: None,
+        restarts: None,
+        recent_errors: vec![],
+    })
+}
+
+async fn scheduled_tasks(configured: Option<&[String]>) -> Vec<ServiceState> {
+    let output = match get_command_output("schtasks", &["/Query", "/FO", "CSV", "/NH"]).await {
+        Ok(output) => output,
+        Err(e) => {
+            tracing::debug!("schtasks query failed: {}", e);
+            return vec![];
+        }
+    };
+    output
+        .lines()
+        .filter_map(|line| {
+            let columns = line
+                .trim()
+                .trim_matches('"')
+                .split("\",\"")
+                .collect::<Vec<_>>();
+            // "TaskName","Next Run Time","Status"
+            let name = columns.first()?.trim_start_matches('\\');
+            let status = columns.get(2)?;
+            Some((name.to_string(), status.to_lowercase()))
+        })
+        .filter(|(name, _)| match configured {
+            Some(names) => names.iter().any(|configured| configured == name),
+            None => {
+                let name = name.to_lowercase();
+                TASK_KEYWORDS.iter().any(|keyword| name.contains(keyword))
+            }
+        })
+        .map(|(name, state)| ServiceState {
+            name,
+            manager: "task scheduler".into(),
+            state,
+            sub_state: None,
+            since: None,
+            restarts: None,
+            recent_errors: vec![],
+        })
+        .collect()
+}
@@ -0,0 +1,462 @@
+//! ya-provider presets with prices converted to fiat
+
+use ansi_term::{Colour, Style};
+use anyhow::{bail, Result};
+use chrono::Utc;
+use prettytable::{cell, format, row, Table};
+use structopt::StructOpt;
+
+use crate::command::{CommandOptions, Preset, RuntimeInfo, YaCommand};
+use crate::display::{self, TimeDisplay};
+use crate::processes;
+use crate::rates::{self, CURRENCY};
+use crate::stats::{self, percentile, ProviderPricing};
+
+pub const SECONDS_PER_HOUR: f64 = 3600.0;
+
+pub const CPU_SEC: &str = "cpu_sec";
+pub const DURATION_SEC: &str = "duration_sec";
+
+#[derive(StructOpt)]
+pub struct PresetsCommand {
+    #[structopt(subcommand)]
+    action: Option<PresetAction>,
+}
+
+#[derive(StructOpt)]
+pub enum PresetAction {
+    /// List presets with estimated fiat prices (the default)
+    List,
+    /// Offer a defined preset on the market
+    Activate {
+        name: String,
+        /// Don't ask for confirmation
+        #[structopt(long, short)]
+        yes: bool,
+    },
+    /// Stop offering a preset, it stays defined
+    Deactivate {
+        name: String,
+        /// Don't ask for confirmation
+        #[structopt(long, short)]
+        yes: bool,
+    },
+}
+
+/// Prices this many times above or below the network median are taken for a mistake.
+const PRICE_OFF_FACTOR: f64 = 10.0;
+
+/// Pricing coefficient of the usage counter ending with `suffix`, eg. "golem.usage.cpu_sec".
+pub fn usage_coeff(preset: &Preset, suffix: &str) -> f64 {
+    preset
+        .usage_coeffs
+        .iter()
+        .filter(|(name, _)| name.ends_with(suffix))
+        .map(|(_, value)| value)
+        .sum()
+}
+
+/// Estimated GLM earned in an hour of a task which keeps one CPU thread busy.
+pub fn price_per_hour(preset: &Preset) -> f64 {
+    (usage_coeff(preset, DURATION_SEC) + usage_coeff(preset, CPU_SEC)) * SECONDS_PER_HOUR
+}
+
+/// Prices of `preset` which make the node work for free or price it out of the market,
+/// compared with the medians of `network` when there are any.
+pub fn pricing_warnings(preset: &Preset, network: &[ProviderPricing]) -> Vec<String> {
+    let cpu = usage_coeff(preset, CPU_SEC) * SECONDS_PER_HOUR;
+    let env = usage_coeff(preset, DURATION_SEC) * SECONDS_PER_HOUR;
+    if cpu == 0.0 && env == 0.0 && preset.initial_price == 0.0 {
+        return vec![tr!("presets-free", name = preset.name.clone())];
+    }
+    let metrics = [
+        (
+            tr!("price-cpu-hour"),
+            cpu,
+            network.iter().map(|p| p.cpu_per_hour).collect::<Vec<_>>(),
+        ),
+        (
+            tr!("price-env-hour"),
+            env,
+            network.iter().map(|p| p.env_per_hour).collect(),
+        ),
+    ];
+    let mut warnings = vec![];
+    for (label, current, prices) in metrics {
+        let name = preset.name.clone();
+        if current == 0.0 {
+            warnings.push(tr!("presets-metric-free", name = name, metric = label));
+            continue;
+        }
+        let median = match percentile(&prices, 50.0) {
+            Some(median) if median > 0.0 => median,
+            _ => continue,
+        };
+        if current > median * PRICE_OFF_FACTOR {
+            warnings.push(tr!(
+                "presets-metric-high",
+                name = name,
+                metric = label,
+                factor = format!("{:.0}", current / median)
+            ));
+        } else if current < median / PRICE_OFF_FACTOR {
+            warnings.push(tr!(
+                "presets-metric-low",
+                name = name,
+                metric = label,
+                factor = format!("{:.0}", median / current)
+            ));
+        }
+    }
+    warnings
+}
+
+/// GLM an hour the best paying active preset earns with all `threads` offered busy and
+/// `agreements` running at once, each charging its duration price.
+pub fn full_utilization_rate(
+    presets: &[Preset],
+    active: &[String],
+    threads: usize,
+    agreements: u32,
+) -> Option<f64> {
+    presets
+        .iter()
+        .filter(|preset| active.contains(&preset.name))
+        .map(|preset| {
+            (usage_coeff(preset, DURATION_SEC) * agreements as f64
+                + usage_coeff(preset, CPU_SEC) * threads as f64)
+                * SECONDS_PER_HOUR
+        })
+        .fold(None, |best: Option<f64>, rate| {
+            Some(best.map_or(rate, |best| best.max(rate)))
+        })
+}
+
+/// [`full_utilization_rate`] of the node as it is configured now.
+pub async fn earning_potential(cmd: &YaCommand) -> Result<Option<f64>> {
+    let (presets, active, mut profiles, profile) = futures::future::try_join4(
+        cmd.ya_provider()?.presets(),
+        cmd.ya_provider()?.active_presets(),
+        cmd.ya_provider()?.profiles(),
+        cmd.ya_provider()?.active_profile(),
+    )
+    .await?;
+    let threads = match profiles.remove(&profile) {
+        Some(resources) => resources.cpu_threads,
+        None => return Ok(None),
+    };
+    Ok(full_utilization_rate(
+        &presets,
+        &active,
+        threads,
+        processes::max_simultaneous_agreements(),
+    ))
+}
+
+/// Installed runtimes no active preset offers, tasks needing them never come.
+pub fn idle_runtimes<'a>(
+    runtimes: &'a [RuntimeInfo],
+    presets: &[Preset],
+    active: &[String],
+) -> Vec<&'a str> {
+    runtimes
+        .iter()
+        .map(|runtime| runtime.name.as_str())
+        .filter(|runtime| {
+            !presets
+                .iter()
+                .any(|preset| preset.exeunit_name == *runtime && active.contains(&preset.name))
+        })
+        .collect()
+}
+
+pub async fn run(options: &CommandOptions, command: PresetsCommand) -> Result</*exit code*/ i32> {
+    match command.action.unwrap_or(PresetAction::List) {
+        PresetAction::List => list(options).await,
+        PresetAction::Activate { name, yes } => toggle(options, &name, true, yes).await,
+        PresetAction::Deactivate { name, yes } => toggle(options, &name, false, yes).await,
+    }
+}
+
+/// Activates or deactivates `name` and lists the presets as they are now.
+async fn toggle(options: &CommandOptions, name: &str, activate: bool, yes: bool) -> Result<i32> {
+    let cmd = YaCommand::new(options)?;
+    let (presets, active) = futures::future::try_join(
+        cmd.ya_provider()?.presets(),
+        cmd.ya_provider()?.active_presets(),
+    )
+    .await?;
+    if !presets.iter().any(|preset| preset.name == name) {
+        bail!(tr!(
+            "presets-unknown",
+            name = name,
+            defined = presets
+                .iter()
+                .map(|preset| preset.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    let is_active = active.iter().any(|preset| preset == name);
+    if is_active == activate {
+        let state = if activate {
+            "presets-already-active"
+        } else {
+            "presets-already-inactive"
+        };
+        println!("{}", tr!(state, name = name));
+        return Ok(0);
+    }
+    if !activate && active.len() == 1 {
+        eprintln!("{}", tr!("presets-last-active", name = name));
+    }
+    let question = if activate {
+        tr!("presets-confirm-activate", name = name)
+    } else {
+        tr!("presets-confirm-deactivate", name = name)
+    };
+    if !yes && !promptly::prompt_default(question, true)? {
+        return Ok(1);
+    }
+    if activate {
+        cmd.ya_provider()?.activate_preset(name).await?;
+    } else {
+        cmd.ya_provider()?.deactivate_preset(name).await?;
+    }
+    println!();
+    list(options).await
+}
+
+async fn list(options: &CommandOptions) -> Result<i32> {
+    let cmd = YaCommand::new(options)?;
+    let (presets, active, glm_price, network, runtimes) = futures::future::join5(
+        cmd.ya_provider()?.presets(),
+        cmd.ya_provider()?.active_presets(),
+        rates::glm_price(CURRENCY),
+        stats::pricing(),
+        cmd.ya_provider()?.exe_units(),
+    )
+    .await;
+    let runtimes = runtimes
+        .map_err(|e| tracing::debug!("ya-provider exe-unit list failed: {:?}", e))
+        .unwrap_or_default();
+    let network = network
+        .map_err(|e| tracing::debug!("network prices unavailable: {:?}", e))
+        .unwrap_or_default();
+    let (mut presets, active) = (presets?, active?);
+    // the offered presets first, the merely defined ones below
+    presets.sort_by_key(|preset| !active.contains(&preset.name));
+    let quote = glm_price
+        .map_err(|e| tracing::warn!("failed to fetch GLM price: {}", e))
+        .ok();
+    let glm_price = quote.as_ref().and_then(|quote| quote.price);
+    let currency = CURRENCY.to_uppercase();
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    table.set_titles(row![
+        tr!("presets-name"),
+        tr!("presets-runtime"),
+        tr!("presets-active"),
+        tr!("presets-start-price"),
+        tr!("presets-hourly-glm"),
+        tr!("presets-hourly-fiat", currency = currency)
+    ]);
+    for preset in &presets {
+        let is_active = if active.contains(&preset.name) {
+            Style::new().fg(Colour::Green).paint(tr!("presets-yes"))
+        } else {
+            Style::new().paint(tr!("presets-no"))
+        };
+        let hourly = price_per_hour(preset);
+        let fiat = match glm_price {
+            Some(rate) => display::fiat_f64(hourly * rate),
+            None => "-".to_string(),
+        };
+        table.add_row(row![
+            preset.name,
+            preset.exeunit_name,
+            is_active,
+            r->display::glm_f64(preset.initial_price),
+            r->display::glm_f64(hourly),
+            r->fiat
+        ]);
+    }
+    table.printstd();
+
+    println!();
+    if glm_price.is_none() {
+        println!(" {}", tr!("presets-no-rate"));
+    }
+    println!(" {}", tr!("presets-hourly-note"));
+    println!(
+        " {}",
+        tr!(
+            "presets-active-count",
+            active = presets
+                .iter()
+                .filter(|preset| active.contains(&preset.name))
+                .count(),
+            defined = presets.len()
+        )
+    );
+    match earning_potential(&cmd).await {
+        Ok(Some(glm)) => {
+            let glm_text = display::glm_f64(glm);
+            let potential = match glm_price {
+                Some(rate) => tr!(
+                    "status-earning-value-fiat",
+                    glm = glm_text,
+                    fiat = display::fiat_f64(glm * rate),
+                    currency = currency.clone()
+                ),
+                None => tr!("status-earning-value", glm = glm_text),
+            };
+            println!(" {}: {}", tr!("status-earning-potential"), potential);
+        }
+        Ok(None) => (),
+        Err(e) => tracing::debug!("earning potential unavailable: {:?}", e),
+    }
+
+    let mut warnings = idle_runtimes(&runtimes, &presets, &active)
+        .into_iter()
+        .map(|runtime| tr!("presets-runtime-inactive", runtime = runtime))
+        .collect::<Vec<_>>();
+    warnings.extend(
+        presets
+            .iter()
+            .filter(|preset| active.contains(&preset.name))
+            .flat_map(|preset| pricing_warnings(preset, &network)),
+    );
+    if !warnings.is_empty() {
+        println!();
+        for warning in warnings {
+            println!(" {}", Style::new().fg(Colour::Fixed(220)).paint(warning));
+        }
+    }
+    let now = Utc::now();
+    let time_display = TimeDisplay::local(now);
+    if let Some(footnote) =
+        quote.and_then(|quote| rates::footnote(&[("GLM", &quote)], now, &time_display))
+    {
+        println!("\n {}", footnote);
+    }
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(name: &str, runtime: &str, env_per_hour: f64, cpu_per_hour: f64) -> Preset {
+        Preset {
+            name: name.to_string(),
+            exeunit_name: runtime.to_string(),
+            initial_price: 0.0,
+            usage_coeffs: [
+                (
+                    format!("golem.usage.{}", DURATION_SEC),
+                    env_per_hour / SECONDS_PER_HOUR,
+                ),
+                (
+                    format!("golem.usage.{}", CPU_SEC),
+                    cpu_per_hour / SECONDS_PER_HOUR,
+                ),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+        }
+    }
+
+    fn network(cpu_per_hour: f64, env_per_hour: f64) -> Vec<ProviderPricing> {
+        (0..3)
+            .map(|_| ProviderPricing {
+                cpu_per_hour,
+                env_per_hour,
+                start_price: 0.0,
+            })
+            .collect()
+    }
+
+    fn runtime(name: &str) -> RuntimeInfo {
+        RuntimeInfo {
+            name: name.to_string(),
+            version: None,
+            description: None,
+            supervisor_path: None,
+            runtime_path: None,
+            extra_args: vec![],
+        }
+    }
+
+    #[test]
+    fn free_prices_are_reported_without_network_data() {
+        let free = preset("vm", "vm", 0.0, 0.0);
+        assert_eq!(
+            pricing_warnings(&free, &[]),
+            vec![tr!("presets-free", name = "vm")]
+        );
+        let free_cpu = preset("vm", "vm", 0.01, 0.0);
+        assert_eq!(
+            pricing_warnings(&free_cpu, &[]),
+            vec![tr!(
+                "presets-metric-free",
+                name = "vm",
+                metric = tr!("price-cpu-hour")
+            )]
+        );
+    }
+
+    #[test]
+    fn prices_far_from_the_median_are_reported() {
+        let network = network(0.1, 0.02);
+        assert!(pricing_warnings(&preset("vm", "vm", 0.02, 0.1), &network).is_empty());
+        assert!(pricing_warnings(&preset("vm", "vm", 0.02, 0.1), &[]).is_empty());
+        assert_eq!(
+            pricing_warnings(&preset("vm", "vm", 0.02, 5.0), &network),
+            vec![tr!(
+                "presets-metric-high",
+                name = "vm",
+                metric = tr!("price-cpu-hour"),
+                factor = "50"
+            )]
+        );
+        assert_eq!(
+            pricing_warnings(&preset("vm", "vm", 0.0005, 0.1), &network),
+            vec![tr!(
+                "presets-metric-low",
+                name = "vm",
+                metric = tr!("price-env-hour"),
+                factor = "40"
+            )]
+        );
+    }
+
+    #[test]
+    fn full_utilization_takes_the_best_active_preset() {
+        let presets = [
+            preset("vm", "vm", 0.1, 1.0),
+            preset("wasm", "wasmtime", 0.2, 0.5),
+            preset("expensive", "vm", 10.0, 10.0),
+        ];
+        let active = ["vm".to_string(), "wasm".to_string()];
+        let rate = full_utilization_rate(&presets, &active, 4, 2).unwrap();
+        assert!((rate - 4.2).abs() < 1e-9, "{}", rate);
+        let rate = full_utilization_rate(&presets, &active, 1, 10).unwrap();
+        assert!((rate - 2.5).abs() < 1e-9, "{}", rate);
+        assert_eq!(full_utilization_rate(&presets, &[], 4, 2), None);
+    }
+
+    #[test]
+    fn runtimes_without_active_preset_are_idle() {
+        let runtimes = [runtime("vm"), runtime("wasmtime"), runtime("gpu")];
+        let presets = [preset("vm", "vm", 0.1, 1.0), preset("gpu", "gpu", 1.0, 1.0)];
+        let active = ["vm".to_string()];
+        assert_eq!(
+            idle_runtimes(&runtimes, &presets, &active),
+            vec!["wasmtime", "gpu"]
+        );
+        assert!(idle_runtimes(&[], &presets, &active).is_empty());
+    }
+}
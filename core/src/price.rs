@@ -0,0 +1,146 @@
+//! Pricing advice based on the prices of other providers
+
+use ansi_term::{Colour, Style};
+use anyhow::{bail, Result};
+use prettytable::{cell, format, row, Table};
+use structopt::StructOpt;
+
+use crate::command::{CommandOptions, Preset, YaCommand};
+use crate::display;
+use crate::presets::{usage_coeff, CPU_SEC, DURATION_SEC, SECONDS_PER_HOUR};
+use crate::stats::{self, percentile};
+
+#[derive(StructOpt)]
+pub enum PriceCommand {
+    /// Compare active presets with network prices and suggest new coefficients
+    Suggest {
+        /// Network price percentile to aim for (50 = median)
+        #[structopt(long, default_value = "50")]
+        percentile: f64,
+        /// Update the presets with the suggested prices
+        #[structopt(long)]
+        apply: bool,
+    },
+}
+
+pub async fn run(options: &CommandOptions, command: PriceCommand) -> Result</*exit code*/ i32> {
+    match command {
+        PriceCommand::Suggest { percentile, apply } => suggest(options, percentile, apply).await,
+    }
+}
+
+/// Prices compared per hour, the way they are shown on the stats page.
+struct Metric {
+    label: String,
+    current: f64,
+    network: Vec<f64>,
+}
+
+impl Metric {
+    fn suggested(&self, target: f64) -> Option<f64> {
+        percentile(&self.network, target)
+    }
+
+    /// Current price falls between the network quartiles.
+    fn is_typical(&self) -> bool {
+        match (
+            percentile(&self.network, 25.0),
+            percentile(&self.network, 75.0),
+        ) {
+            (Some(low), Some(high)) => self.current >= low && self.current <= high,
+            _ => true,
+        }
+    }
+}
+
+fn metrics(preset: &Preset, network: &[stats::ProviderPricing]) -> [Metric; 3] {
+    [
+        Metric {
+            label: tr!("price-cpu-hour"),
+            current: usage_coeff(preset, CPU_SEC) * SECONDS_PER_HOUR,
+            network: network.iter().map(|p| p.cpu_per_hour).collect(),
+        },
+        Metric {
+            label: tr!("price-env-hour"),
+            current: usage_coeff(preset, DURATION_SEC) * SECONDS_PER_HOUR,
+            network: network.iter().map(|p| p.env_per_hour).collect(),
+        },
+        Metric {
+            label: tr!("price-start"),
+            current: preset.initial_price,
+            network: network.iter().map(|p| p.start_price).collect(),
+        },
+    ]
+}
+
+async fn suggest(options: &CommandOptions, target: f64, apply: bool) -> Result<i32> {
+    if !(0.0..=100.0).contains(&target) {
+        bail!(tr!("price-bad-percentile"));
+    }
+    let cmd = YaCommand::new(options)?;
+    let (presets, active, network) = futures::future::join3(
+        cmd.ya_provider()?.presets(),
+        cmd.ya_provider()?.active_presets(),
+        stats::pricing(),
+    )
+    .await;
+    let (presets, active, network) = (presets?, active?, network?);
+    if network.is_empty() {
+        bail!(tr!("price-no-network-data"));
+    }
+    println!("{}\n", tr!("price-providers-seen", count = network.len()));
+
+    for preset in presets
+        .iter()
+        .filter(|preset| active.contains(&preset.name))
+    {
+        let [cpu, env, start] = metrics(preset, &network);
+
+        let mut table = Table::new();
+        table.set_format(*format::consts::FORMAT_CLEAN);
+        table.set_titles(row![
+            Style::new().fg(Colour::Yellow).paint(preset.name.clone()),
+            tr!("price-current"),
+            "p25",
+            "p50",
+            "p75",
+            tr!("price-suggested")
+        ]);
+        for metric in [&cpu, &env, &start] {
+            let show =
+                |value: Option<f64>| value.map(display::glm_f64).unwrap_or_else(|| "-".into());
+            let colour = if metric.is_typical() {
+                Colour::Green
+            } else {
+                Colour::Red
+            };
+            table.add_row(row![
+                metric.label,
+                r->Style::new().fg(colour).paint(display::glm_f64(metric.current)),
+                r->show(percentile(&metric.network, 25.0)),
+                r->show(percentile(&metric.network, 50.0)),
+                r->show(percentile(&metric.network, 75.0)),
+                r->show(metric.suggested(target))
+            ]);
+        }
+        table.printstd();
+        println!();
+
+        if apply {
+            let suggested = |metric: &Metric| metric.suggested(target).unwrap_or(metric.current);
+            cmd.ya_provider()?
+                .update_preset_prices(
+                    &preset.name,
+                    suggested(&cpu) / SECONDS_PER_HOUR,
+                    suggested(&env) / SECONDS_PER_HOUR,
+                    suggested(&start),
+                )
+                .await?;
+            println!("{}\n", tr!("price-applied", preset = preset.name));
+        }
+    }
+    if !apply {
+        println!("{}", tr!("price-apply-hint"));
+    }
+    Ok(0)
+}
@@ -0,0 +1,267 @@
+//! Resource usage of the yagna and ya-provider processes
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+use sysinfo::{Pid, Process, ProcessExt, System, SystemExt};
+use tokio::time;
+
+use crate::utils;
+
+pub const DAEMONS: &[&str] = &["yagna", "ya-provider"];
+
+/// Delay between two refreshes needed by sysinfo to compute cpu usage.
+const CPU_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Deserialize, Serialize)]
+pub struct ProcessMetrics {
+    pub name: String,
+    pub pid: String,
+    pub rss_bytes: u64,
+    pub cpu_percent: f32,
+    pub open_fds: Option<usize>,
+    /// When the process was started
+    #[serde(default)]
+    pub started_at: Option<DateTime<Utc>>,
+}
+
+/// yagna daemon found in the process table and how it was started
+#[derive(Deserialize, Serialize)]
+pub struct RunningYagna {
+    pub pid: String,
+    /// `--api-url` or `YAGNA_API_URL` of the daemon, when not the default
+    pub api_url: Option<String>,
+    /// `--datadir` or `YAGNA_DATADIR` of the daemon, when not the default
+    pub datadir: Option<PathBuf>,
+}
+
+/// Value of `--name value` or `--name=value` among `args`.
+fn flag_value(args: &[String], names: &[&str]) -> Option<String> {
+    args.iter().enumerate().find_map(|(i, arg)| {
+        names.iter().find_map(|name| {
+            if arg == name {
+                args.get(i + 1).cloned()
+            } else {
+                arg.strip_prefix(name)?
+                    .strip_prefix('=')
+                    .map(str::to_string)
+            }
+        })
+    })
+}
+
+/// Value of `name` in a `KEY=value` environment listing.
+fn env_value(environ: &[String], name: &str) -> Option<String> {
+    environ.iter().find_map(|var| {
+        var.strip_prefix(name)?
+            .strip_prefix('=')
+            .map(str::to_string)
+    })
+}
+
+/// The `yagna service run` process, with the API and datadir it was given on the command
+/// line or in its environment. The environment of processes of other users can't be read,
+/// those settings then stay unknown.
+///
+/// With `YAGNA_DATADIR` set only the daemon of that datadir is looked for. Otherwise the
+/// only daemon running is taken, of several none is, as any pick would be a guess.
+pub fn detect_yagna() -> Option<RunningYagna> {
+    let mut sys = System::new();
+    sys.refresh_processes();
+    let mut services = sys
+        .processes()
+        .iter()
+        .filter(|(_, process)| is_yagna_service(process))
+        .collect::<Vec<_>>();
+    let (pid, process) = if std::env::var_os("YAGNA_DATADIR").is_some() {
+        services
+            .into_iter()
+            .find(|(_, process)| is_node_yagna(process))?
+    } else if services.len() > 1 {
+        tracing::warn!(
+            "{} yagna daemons are running, select one with a profile or --datadir",
+            services.len()
+        );
+        return None;
+    } else {
+        services.pop()?
+    };
+    let api_url = flag_value(process.cmd(), &["--api-url"])
+        .or_else(|| env_value(process.environ(), "YAGNA_API_URL"));
+    Some(RunningYagna {
+        pid: pid.to_string(),
+        api_url,
+        datadir: process_datadir(process, &["--datadir", "-d"], "YAGNA_DATADIR"),
+    })
+}
+
+fn process_name(process: &Process) -> &str {
+    process.name().trim_end_matches(".exe")
+}
+
+fn is_yagna_service(process: &Process) -> bool {
+    process_name(process) == "yagna" && process.cmd().iter().any(|arg| arg == "service")
+}
+
+/// `ya-provider run`, not one of the short-lived `ya-provider` commands run by the collectors.
+fn is_provider_daemon(process: &Process) -> bool {
+    process_name(process) == "ya-provider" && process.cmd().iter().any(|arg| arg == "run")
+}
+
+/// Datadir `process` was given with one of `flags` or the `var` environment variable.
+fn process_datadir(process: &Process, flags: &[&str], var: &str) -> Option<PathBuf> {
+    flag_value(process.cmd(), flags)
+        .or_else(|| env_value(process.environ(), var))
+        .map(|datadir| process.cwd().join(datadir))
+}
+
+/// Whether `datadir` of a process (None for the default) is `selected`.
+fn same_datadir(
+    datadir: Option<PathBuf>,
+    selected: &Option<PathBuf>,
+    default: Option<PathBuf>,
+) -> bool {
+    let resolve = |path: PathBuf| path.canonicalize().unwrap_or(path);
+    match (datadir.or(default), selected) {
+        (Some(datadir), Some(selected)) => resolve(datadir) == resolve(selected.clone()),
+        _ => false,
+    }
+}
+
+/// The yagna service using the datadir selected by the options, profile or running daemon.
+fn is_node_yagna(process: &Process) -> bool {
+    is_yagna_service(process)
+        && same_datadir(
+            process_datadir(process, &["--datadir", "-d"], "YAGNA_DATADIR"),
+            &utils::yagna_datadir(),
+            utils::default_yagna_datadir(),
+        )
+}
+
+/// The ya-provider daemon using the datadir selected by the options or profile.
+fn is_node_provider(process: &Process) -> bool {
+    is_provider_daemon(process)
+        && same_datadir(
+            process_datadir(process, &["--datadir", "--data-dir"], "DATA_DIR"),
+            &utils::provider_datadir(),
+            utils::default_provider_datadir(),
+        )
+}
+
+/// Processes of the node using the datadirs selected by the options, profile or running
+/// daemon: the yagna service and ya-provider with those datadirs, and golemsp when it
+/// started that yagna. Nodes of other datadirs on the same machine are left out.
+pub fn node_processes() -> Vec<(Pid, String)> {
+    let mut sys = System::new();
+    sys.refresh_processes();
+    let yagna = sys
+        .processes()
+        .iter()
+        .filter(|(_, process)| is_node_yagna(process))
+        .map(|(pid, process)| (*pid, process))
+        .collect::<Vec<_>>();
+    let golemsp = yagna
+        .iter()
+        .filter_map(|(_, process)| process.parent())
+        .filter(|parent| {
+            sys.process(*parent)
+                .map_or(false, |parent| process_name(parent) == "golemsp")
+        })
+        .collect::<Vec<_>>();
+    let provider = sys
+        .processes()
+        .iter()
+        .filter(|(_, process)| is_node_provider(process));
+    // the provider first, so it can terminate its agreements through yagna
+    golemsp
+        .into_iter()
+        .map(|pid| (pid, "golemsp".to_string()))
+        .chain(provider.map(|(pid, _)| (*pid, "ya-provider".to_string())))
+        .chain(yagna.into_iter().map(|(pid, _)| (pid, "yagna".to_string())))
+        .collect()
+}
+
+/// Process of the node's `daemon` ("yagna" or "ya-provider") among `sys` processes.
+/// Commands run by the collectors meanwhile and daemons of other datadirs are passed over.
+fn running_daemon<'a>(sys: &'a System, daemon: &str) -> Option<&'a sysinfo::Process> {
+    sys.processes().values().find(|process| match daemon {
+        "yagna" => is_node_yagna(process),
+        "ya-provider" => is_node_provider(process),
+        _ => false,
+    })
+}
+
+/// Variable `name` in the environment the running `daemon` was started with, the same
+/// variable in our own environment when the daemon doesn't run or its environment can't be
+/// read (processes of other users).
+pub fn daemon_env(daemon: &str, name: &str) -> Option<String> {
+    let mut sys = System::new();
+    sys.refresh_processes();
+    match running_daemon(&sys, daemon) {
+        Some(process) if !process.environ().is_empty() => env_value(process.environ(), name),
+        _ => std::env::var(name).ok(),
+    }
+}
+
+/// ya-provider runs one agreement at a time unless told otherwise.
+pub const DEFAULT_MAX_AGREEMENTS: u32 = 1;
+
+/// `--max-simultaneous-agreements` (or `MAX_SIMULTANEOUS_AGREEMENTS`) of the running
+/// ya-provider, the same variable in our own environment when it doesn't run.
+pub fn max_simultaneous_agreements() -> u32 {
+    let mut sys = System::new();
+    sys.refresh_processes();
+    let value = match running_daemon(&sys, "ya-provider") {
+        Some(process) => flag_value(process.cmd(), &["--max-simultaneous-agreements"])
+            .or_else(|| env_value(process.environ(), "MAX_SIMULTANEOUS_AGREEMENTS")),
+        None => std::env::var("MAX_SIMULTANEOUS_AGREEMENTS").ok(),
+    };
+    value
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(DEFAULT_MAX_AGREEMENTS)
+}
+
+fn is_daemon(process_name: &str) -> bool {
+    let name = process_name.trim_end_matches(".exe");
+    DAEMONS.contains(&name)
+}
+
+/// Samples all running yagna and ya-provider processes.
+pub async fn collect() -> Vec<ProcessMetrics> {
+    let mut sys = System::new();
+    sys.refresh_processes();
+    time::delay_for(CPU_SAMPLE_INTERVAL).await;
+    sys.refresh_processes();
+
+    let mut metrics = sys
+        .processes()
+        .iter()
+        .filter(|(_, process)| is_daemon(process.name()))
+        .map(|(pid, process)| ProcessMetrics {
+            name: process.name().trim_end_matches(".exe").to_string(),
+            pid: pid.to_string(),
+            // sysinfo reports memory in KiB
+            rss_bytes: process.memory() * 1024,
+            cpu_percent: process.cpu_usage(),
+            open_fds: open_fds(&pid.to_string()),
+            started_at: i64::try_from(process.start_time())
+                .ok()
+                .and_then(|secs| Utc.timestamp_opt(secs, 0).single()),
+        })
+        .collect::<Vec<_>>();
+    metrics.sort_by(|a, b| a.name.cmp(&b.name));
+    metrics
+}
+
+#[cfg(target_os = "linux")]
+fn open_fds(pid: &str) -> Option<usize> {
+    std::fs::read_dir(format!("/proc/{}/fd", pid))
+        .ok()
+        .map(|entries| entries.count())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn open_fds(_pid: &str) -> Option<usize> {
+    None
+}
@@ -0,0 +1,41 @@
+//! Named profiles selecting one of several yagna instances running on the same host
+//!
+//! A profile is a dotenv file, `<config dir>/profiles/<name>.env`, setting the variables
+//! which tell instances apart, eg.
+//!
+//! ```text
+//! YAGNA_DATADIR=/srv/golem/node2/yagna
+//! DATA_DIR=/srv/golem/node2/ya-provider
+//! YAGNA_API_URL=http://127.0.0.1:7466
+//! GSB_URL=tcp://127.0.0.1:7467
+//! ```
+
+use anyhow::{anyhow, Context, Result};
+use std::env;
+use std::path::PathBuf;
+
+use crate::utils::project_dirs;
+
+fn profiles_dir() -> Result<PathBuf> {
+    let dirs = project_dirs().ok_or_else(|| anyhow!("unable to determine config directory"))?;
+    Ok(dirs.config_dir().join("profiles"))
+}
+
+/// Exports the variables of profile `name`, overriding the current environment.
+pub fn load(name: &str) -> Result<()> {
+    let path = profiles_dir()?.join(format!("{}.env", name));
+    if !path.exists() {
+        return Err(anyhow!(tr!(
+            "profile-not-found",
+            name = name,
+            path = path.display()
+        )));
+    }
+    let vars = dotenv::from_path_iter(&path)
+        .with_context(|| format!("reading profile {}", path.display()))?;
+    for var in vars {
+        let (key, value) = var.with_context(|| format!("parsing profile {}", path.display()))?;
+        env::set_var(key, value);
+    }
+    Ok(())
+}
@@ -0,0 +1,132 @@
+//! Energy cost of running the node set against what it earns
+//!
+//! The machine's power draw and the electricity price are configured with
+//! `YAGNA_USD_POWER_WATTS` and `YAGNA_USD_ELECTRICITY_PRICE` (fiat per kWh), eg. in a profile.
+//! Earnings are sampled by `status` runs, the daily rate is averaged over the recent samples.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::history::{self, Entry};
+
+const HISTORY: &str = "earnings";
+
+/// Days of samples the earnings rate is averaged over.
+const WINDOW_DAYS: i64 = 7;
+/// Samples spanning less than this say too little about the daily rate.
+const MIN_SPAN_HOURS: i64 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub struct Sample {
+    /// GLM of all accepted invoices so far, on all payment networks
+    pub earned: f64,
+}
+
+pub fn record(earned: f64) -> Result<()> {
+    history::append(HISTORY, Sample { earned })
+}
+
+/// Power draw of the machine and what the energy costs.
+#[derive(Clone, Copy, Debug)]
+pub struct PowerCost {
+    pub watts: f64,
+    /// Fiat per kWh
+    pub price: f64,
+}
+
+impl PowerCost {
+    /// Configured cost, `None` until both the wattage and the price are set.
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()?
+                .trim()
+                .parse::<f64>()
+                .map_err(|e| tracing::warn!("ignoring {}: {}", name, e))
+                .ok()
+        };
+        Some(PowerCost {
+            watts: var("YAGNA_USD_POWER_WATTS")?,
+            price: var("YAGNA_USD_ELECTRICITY_PRICE")?,
+        })
+    }
+
+    pub fn per_hour(&self) -> f64 {
+        self.watts / 1000.0 * self.price
+    }
+
+    pub fn per_day(&self) -> f64 {
+        self.per_hour() * 24.0
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct Profitability {
+    pub watts: f64,
+    /// Electricity price, fiat per kWh
+    pub price: f64,
+    /// Energy cost in fiat
+    pub cost_per_hour: f64,
+    pub cost_per_day: f64,
+    /// GLM earned per day, `None` until enough samples were recorded
+    pub earned_per_day: Option<f64>,
+    /// Fiat value of `earned_per_day`, when the GLM price is known
+    pub earned_fiat_per_day: Option<f64>,
+    /// Fiat earned minus energy cost, per day
+    pub net_per_day: Option<f64>,
+    /// Current GLM price in fiat
+    #[serde(default)]
+    pub glm_price: Option<f64>,
+    /// GLM price at which earnings just cover the energy cost
+    #[serde(default)]
+    pub break_even_price: Option<f64>,
+}
+
+impl Profitability {
+    /// The market price of GLM doesn't cover the energy cost at the current throughput.
+    pub fn below_break_even(&self) -> bool {
+        matches!(
+            (self.glm_price, self.break_even_price),
+            (Some(price), Some(break_even)) if price < break_even
+        )
+    }
+}
+
+/// GLM earned per day between the oldest sample of the window and the latest one.
+fn earned_per_day(samples: &[Entry<Sample>], now: DateTime<Utc>) -> Option<f64> {
+    let start = now - Duration::days(WINDOW_DAYS);
+    let first = samples.iter().find(|sample| sample.ts >= start)?;
+    let last = samples.last()?;
+    let span = last.ts - first.ts;
+    if span < Duration::hours(MIN_SPAN_HOURS) {
+        return None;
+    }
+    // earnings only grow, a drop means the samples came from another node or datadir
+    let earned = (last.record.earned - first.record.earned).max(0.0);
+    Some(earned * Duration::days(1).num_seconds() as f64 / span.num_seconds() as f64)
+}
+
+/// Costs and earnings per day, fiat values need `glm_price`.
+///
+/// The break-even price assumes the node keeps earning as much GLM as recently.
+pub fn report(cost: PowerCost, glm_price: Option<f64>) -> Result<Profitability> {
+    let samples = history::load::<Sample>(HISTORY)?;
+    let earned_per_day = earned_per_day(&samples, Utc::now());
+    let earned_fiat_per_day = earned_per_day
+        .zip(glm_price)
+        .map(|(glm, price)| glm * price);
+    Ok(Profitability {
+        watts: cost.watts,
+        price: cost.price,
+        cost_per_hour: cost.per_hour(),
+        cost_per_day: cost.per_day(),
+        earned_per_day,
+        earned_fiat_per_day,
+        net_per_day: earned_fiat_per_day.map(|earned| earned - cost.per_day()),
+        glm_price,
+        break_even_price: earned_per_day
+            .filter(|glm| *glm > 0.0)
+            .map(|glm| cost.per_day() / glm),
+    })
+}
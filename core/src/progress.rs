@@ -0,0 +1,103 @@
+//! Step indicator printed on stderr while status data is being collected
+
+use futures::future::{self, Either};
+use futures::prelude::*;
+use std::cell::{Cell, RefCell};
+use std::io::Write;
+use std::time::Duration;
+use tokio::time;
+use tracing::Instrument;
+
+const TICK: Duration = Duration::from_millis(100);
+const FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+const ASCII_FRAMES: &[&str] = &["|", "/", "-", "\\"];
+
+pub struct Progress {
+    enabled: bool,
+    ascii: bool,
+    steps: RefCell<Vec<(usize, String)>>,
+    next_id: Cell<usize>,
+}
+
+impl Progress {
+    /// Creates an indicator which only draws when stderr is a terminal.
+    pub fn new(ascii: bool) -> Self {
+        Progress {
+            enabled: atty::is(atty::Stream::Stderr),
+            ascii,
+            steps: RefCell::new(vec![]),
+            next_id: Cell::new(0),
+        }
+    }
+
+    /// Creates an indicator which never draws, for collecting without a terminal.
+    pub fn hidden() -> Self {
+        Progress {
+            enabled: false,
+            ascii: true,
+            steps: RefCell::new(vec![]),
+            next_id: Cell::new(0),
+        }
+    }
+
+    /// Marks `fut` as a running step described by `label`.
+    pub async fn step<T>(&self, label: String, fut: impl Future<Output = T>) -> T {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        let span = tracing::debug_span!("collect", step = %label);
+        self.steps.borrow_mut().push((id, label));
+        let output = fut.instrument(span).await;
+        self.steps
+            .borrow_mut()
+            .retain(|(step_id, _)| *step_id != id);
+        output
+    }
+
+    /// Polls `fut` to completion while redrawing the indicator.
+    pub async fn drive<T>(&self, fut: impl Future<Output = T>) -> T {
+        if !self.enabled {
+            return fut.await;
+        }
+        let ticker = async {
+            let mut interval = time::interval(TICK);
+            let mut frame = 0;
+            loop {
+                interval.tick().await;
+                self.draw(frame);
+                frame += 1;
+            }
+        };
+        let output = match future::select(Box::pin(fut), Box::pin(ticker)).await {
+            Either::Left((output, _)) => output,
+            Either::Right(((), _)) => unreachable!("progress ticker never completes"),
+        };
+        self.clear();
+        output
+    }
+
+    fn draw(&self, frame: usize) {
+        let steps = self.steps.borrow();
+        let (_, label) = match steps.last() {
+            Some(step) => step,
+            None => return,
+        };
+        let (frames, ellipsis) = if self.ascii {
+            (ASCII_FRAMES, "...")
+        } else {
+            (FRAMES, "…")
+        };
+        let mut line = format!("{} {}{}", frames[frame % frames.len()], label, ellipsis);
+        if steps.len() > 1 {
+            line.push_str(&format!(" (+{})", steps.len() - 1));
+        }
+        let mut stderr = std::io::stderr();
+        let _ = write!(stderr, "\r\x1b[2K{}", line);
+        let _ = stderr.flush();
+    }
+
+    fn clear(&self) {
+        let mut stderr = std::io::stderr();
+        let _ = write!(stderr, "\r\x1b[2K");
+        let _ = stderr.flush();
+    }
+}
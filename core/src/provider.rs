@@ -0,0 +1,81 @@
+//! Basic ya-provider settings changed without remembering `ya-provider config set` syntax
+
+use anyhow::{bail, Result};
+use structopt::StructOpt;
+
+use crate::address;
+use crate::command::{CommandOptions, YaCommand, PUBLIC_SUBNET};
+
+#[derive(StructOpt)]
+pub enum ProviderCommand {
+    /// Change the node name, subnet or payment account, the others are kept
+    Set {
+        /// Name the node is shown with on the network
+        #[structopt(long)]
+        node_name: Option<String>,
+        /// Subnet to offer in, "public" for all requestors
+        #[structopt(long)]
+        subnet: Option<String>,
+        /// Address the earnings are paid to
+        #[structopt(long)]
+        account: Option<String>,
+    },
+}
+
+pub async fn run(options: &CommandOptions, command: ProviderCommand) -> Result</*exit code*/ i32> {
+    match command {
+        ProviderCommand::Set {
+            node_name,
+            subnet,
+            account,
+        } => set(options, node_name, subnet, account).await,
+    }
+}
+
+async fn set(
+    options: &CommandOptions,
+    node_name: Option<String>,
+    subnet: Option<String>,
+    account: Option<String>,
+) -> Result<i32> {
+    if node_name.is_none() && subnet.is_none() && account.is_none() {
+        bail!(tr!("provider-set-nothing"));
+    }
+    let account = match account {
+        Some(account) => {
+            address::validate(&account)?;
+            Some(address::checksummed(&account))
+        }
+        None => None,
+    };
+
+    let cmd = YaCommand::new(options)?;
+    cmd.ya_provider()?
+        .set_config(node_name.as_deref(), subnet.as_deref(), account.as_deref())
+        .await?;
+
+    let (config, account_as_configured) = cmd.ya_provider()?.get_config_with_account().await?;
+    println!(
+        "{}",
+        tr!(
+            "provider-set-done",
+            node_name = config.node_name.clone().unwrap_or_default(),
+            subnet = config.subnet.clone().unwrap_or_default(),
+            account = account_as_configured
+                .as_deref()
+                .map(address::checksummed)
+                .unwrap_or_else(|| tr!("status-account-identity"))
+        )
+    );
+    if !config.is_public_subnet() {
+        eprintln!(
+            "{}",
+            tr!(
+                "subnet-not-public",
+                subnet = config.subnet.unwrap_or_default(),
+                public = PUBLIC_SUBNET
+            )
+        );
+    }
+    Ok(0)
+}
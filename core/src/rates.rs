@@ -0,0 +1,290 @@
+//! Exchange rates of GLM and of the native tokens paying for gas
+//!
+//! Lookups are retried `YAGNA_USD_RATE_RETRIES` times (2 by default) with a backoff starting
+//! at `YAGNA_USD_RATE_BACKOFF` seconds (1) and doubling, each request given
+//! `YAGNA_USD_RATE_TIMEOUT` seconds (10), a lookup gives up after 30 s in total whatever the
+//! settings. Rates fetched are cached, when all attempts fail the cached ones are used and
+//! marked stale.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::display::{format_relative, TimeDisplay};
+use crate::http;
+use crate::utils::{project_dirs, write_atomic};
+
+/// Fiat currency used for showing GLM values.
+pub const CURRENCY: &str = "usd";
+
+/// Where the rates come from, named in the footnotes below fiat values.
+pub const SOURCE: &str = "CoinGecko";
+
+/// Price of one coin in a fiat currency
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FiatPrice {
+    /// Lowercase currency code, eg. "eur"
+    pub currency: String,
+    /// `None` if coingecko doesn't quote the currency
+    pub price: Option<f64>,
+    /// When the price was fetched, left out in snapshots older than the footnotes
+    #[serde(default)]
+    pub fetched_at: Option<DateTime<Utc>>,
+    /// Taken from the cache because the rate could not be fetched
+    #[serde(default)]
+    pub stale: bool,
+}
+
+impl FiatPrice {
+    /// " (stale)" for rates taken from the cache, empty for fresh ones.
+    pub fn stale_marker(&self) -> String {
+        if self.stale {
+            format!(" ({})", tr!("rates-stale"))
+        } else {
+            String::new()
+        }
+    }
+}
+
+/// Longest a lookup takes with all its retries.
+const MAX_LOOKUP: Duration = Duration::from_secs(30);
+
+/// How rate lookups are retried
+#[derive(Clone, Copy, Debug)]
+struct RetryPolicy {
+    retries: u32,
+    backoff: Duration,
+    timeout: Duration,
+}
+
+fn env_or<T: FromStr>(name: &str, default: T) -> T {
+    match std::env::var(name) {
+        Ok(value) => value.trim().parse().unwrap_or_else(|_| {
+            tracing::warn!("ignoring {}={}, not a valid value", name, value);
+            default
+        }),
+        Err(_) => default,
+    }
+}
+
+/// Seconds in `name`, at least `min`; `default` when they aren't a representable duration.
+fn seconds_or(name: &str, default: f64, min: f64) -> Duration {
+    let secs = env_or(name, default).max(min);
+    Duration::try_from_secs_f64(secs).unwrap_or_else(|_| {
+        tracing::warn!("ignoring {}={}, not a valid value", name, secs);
+        Duration::from_secs_f64(default)
+    })
+}
+
+impl RetryPolicy {
+    fn from_env() -> Self {
+        RetryPolicy {
+            retries: env_or("YAGNA_USD_RATE_RETRIES", 2),
+            // a longer pause than the whole lookup may take would never end in a retry
+            backoff: seconds_or("YAGNA_USD_RATE_BACKOFF", 1.0, 0.0).min(MAX_LOOKUP),
+            timeout: seconds_or("YAGNA_USD_RATE_TIMEOUT", 10.0, 0.1),
+        }
+    }
+}
+
+/// Current price of one GLM in `currency`.
+pub async fn glm_price(currency: &'static str) -> Result<FiatPrice> {
+    price("golem", currency).await
+}
+
+/// Current prices of one GLM in each of `currencies`, asked for in a single request.
+pub async fn glm_prices(currencies: Vec<String>) -> Result<Vec<FiatPrice>> {
+    let mut quotes = tokio::task::spawn_blocking(move || quotes(&["golem"], &currencies)).await??;
+    Ok(quotes.remove(0))
+}
+
+/// Current price of the coingecko `coin` (eg. "ethereum") in `currency`.
+pub async fn price(coin: &'static str, currency: &'static str) -> Result<FiatPrice> {
+    let mut prices = prices(vec![coin], currency).await?;
+    Ok(prices.remove(0))
+}
+
+/// Current prices of the coingecko `coins` in `currency`, in their order, asked for in a
+/// single request.
+pub async fn prices(coins: Vec<&'static str>, currency: &'static str) -> Result<Vec<FiatPrice>> {
+    let quotes =
+        tokio::task::spawn_blocking(move || quotes(&coins, &[currency.to_string()])).await??;
+    Ok(quotes
+        .into_iter()
+        .map(|mut quotes| quotes.remove(0))
+        .collect())
+}
+
+/// Prices of each of `coins` in `currencies`, the cached ones when they can't be fetched.
+fn quotes(coins: &[&str], currencies: &[String]) -> Result<Vec<Vec<FiatPrice>>> {
+    let ids = coins.join(",");
+    let error = match fetch_prices(&ids, &currencies.join(","), RetryPolicy::from_env()) {
+        Ok(response) => {
+            let fetched_at = Some(Utc::now());
+            let quotes = coins
+                .iter()
+                .map(|coin| {
+                    currencies
+                        .iter()
+                        .map(|currency| FiatPrice {
+                            currency: currency.clone(),
+                            price: response[*coin][currency.as_str()].as_f64(),
+                            fetched_at,
+                            stale: false,
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            if let Err(e) = write_cache(coins, &quotes) {
+                tracing::debug!("failed to cache {} rates: {:?}", ids, e);
+            }
+            return Ok(quotes);
+        }
+        Err(e) => e,
+    };
+    let cache = read_cache();
+    if !coins.iter().any(|coin| {
+        currencies
+            .iter()
+            .any(|currency| cache.contains_key(&cache_key(coin, currency)))
+    }) {
+        return Err(error);
+    }
+    tracing::warn!("{} rates unavailable, using cached ones: {:#}", ids, error);
+    // currencies never fetched stay unknown, as if coingecko didn't quote them
+    Ok(coins
+        .iter()
+        .map(|coin| {
+            currencies
+                .iter()
+                .map(|currency| match cache.get(&cache_key(coin, currency)) {
+                    Some(cached) => FiatPrice {
+                        stale: true,
+                        ..cached.clone()
+                    },
+                    None => FiatPrice {
+                        currency: currency.clone(),
+                        price: None,
+                        fetched_at: None,
+                        stale: true,
+                    },
+                })
+                .collect()
+        })
+        .collect())
+}
+
+fn fetch_prices(ids: &str, currencies: &str, policy: RetryPolicy) -> Result<serde_json::Value> {
+    let started = Instant::now();
+    let mut attempt = 0;
+    loop {
+        let remaining = MAX_LOOKUP.saturating_sub(started.elapsed());
+        let response =
+            http::get("https://api.coingecko.com/api/v3/simple/price").and_then(|request| {
+                Ok(request
+                    .timeout(policy.timeout.min(remaining))
+                    .query("ids", ids)
+                    .query("vs_currencies", currencies)
+                    .call()
+                    .map_err(http::explain)?
+                    .into_json::<serde_json::Value>()?)
+            });
+        // `None` when the pause would end past the lookup's limit, overflowing included
+        let backoff = policy
+            .backoff
+            .checked_mul(2u32.pow(attempt.min(10)))
+            .filter(|backoff| {
+                started
+                    .elapsed()
+                    .checked_add(*backoff)
+                    .map_or(false, |retry_at| retry_at < MAX_LOOKUP)
+            });
+        match (response, backoff) {
+            (Ok(response), _) => return Ok(response),
+            (Err(e), _) if attempt >= policy.retries => return Err(e),
+            // no time left for another attempt
+            (Err(e), None) => return Err(e),
+            (Err(e), Some(backoff)) => {
+                tracing::debug!("{} rates failed, retrying in {:?}: {:#}", ids, backoff, e);
+                std::thread::sleep(backoff);
+                attempt += 1;
+            }
+        }
+    }
+}
+
+fn cache_key(coin: &str, currency: &str) -> String {
+    format!("{}/{}", coin, currency)
+}
+
+fn cache_path() -> Option<PathBuf> {
+    Some(project_dirs()?.cache_dir().join("rates.json"))
+}
+
+/// Last rates fetched, by "coin/currency".
+fn read_cache() -> BTreeMap<String, FiatPrice> {
+    cache_path()
+        .and_then(|path| fs::read(path).ok())
+        .and_then(|content| serde_json::from_slice(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_cache(coins: &[&str], quotes: &[Vec<FiatPrice>]) -> Result<()> {
+    let path =
+        cache_path().ok_or_else(|| anyhow::anyhow!("unable to determine cache directory"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut cache = read_cache();
+    for (coin, quotes) in coins.iter().zip(quotes) {
+        for quote in quotes.iter().filter(|quote| quote.price.is_some()) {
+            cache.insert(cache_key(coin, &quote.currency), quote.clone());
+        }
+    }
+    write_atomic(&path, &serde_json::to_vec(&cache)?)
+}
+
+/// Source, rates and fetch time of the fiat values shown, for auditing the conversion.
+///
+/// `quotes` pairs a token symbol with its price, `fetched_at` stands in for quotes without
+/// a fetch time, shown in the zone of `time_display`. `None` when no rate is known and so no
+/// fiat value was shown.
+pub fn footnote(
+    quotes: &[(&str, &FiatPrice)],
+    fetched_at: DateTime<Utc>,
+    time_display: &TimeDisplay,
+) -> Option<String> {
+    let rates = quotes
+        .iter()
+        .filter_map(|(symbol, quote)| {
+            Some(format!(
+                "1 {} = {} {}{}",
+                symbol,
+                quote.price?,
+                quote.currency.to_uppercase(),
+                quote.stale_marker()
+            ))
+        })
+        .collect::<Vec<_>>();
+    if rates.is_empty() {
+        return None;
+    }
+    // the oldest rate tells how stale the conversion may be
+    let fetched_at = quotes
+        .iter()
+        .filter_map(|(_, quote)| quote.fetched_at)
+        .min()
+        .unwrap_or(fetched_at);
+    Some(tr!(
+        "rates-footnote",
+        source = SOURCE,
+        rates = rates.join(", "),
+        fetched = time_display.render_absolute(fetched_at),
+        ago = format_relative(fetched_at, time_display.now)
+    ))
+}
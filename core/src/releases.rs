@@ -0,0 +1,194 @@
+//! Latest releases of yagna and yagna-usd published on GitHub
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::http;
+use crate::utils::{project_dirs, write_atomic};
+
+const YAGNA_REPO: &str = "golemfactory/yagna";
+
+/// Unauthenticated GitHub API calls are limited to 60 per hour, so the answer is reused.
+const CACHE_TTL_HOURS: i64 = 12;
+
+#[derive(Serialize, Deserialize)]
+struct CachedRelease {
+    checked: DateTime<Utc>,
+    version: String,
+}
+
+#[derive(Deserialize)]
+pub struct GithubAsset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+#[derive(Deserialize)]
+pub struct GithubRelease {
+    pub tag_name: String,
+    #[serde(default)]
+    pub assets: Vec<GithubAsset>,
+}
+
+impl GithubRelease {
+    /// Tag without the leading "v", eg. "0.9.3".
+    pub fn version(&self) -> &str {
+        self.tag_name.trim_start_matches('v')
+    }
+}
+
+/// Latest stable release of a GitHub `repo` ("owner/name"), blocking.
+pub fn fetch_latest_release(repo: &str) -> Result<GithubRelease> {
+    let url = format!("https://api.github.com/repos/{}/releases/latest", repo);
+    Ok(http::get(&url)?
+        .set("Accept", "application/vnd.github.v3+json")
+        .call()
+        .map_err(http::explain)?
+        .into_json()?)
+}
+
+fn cache_path() -> Result<PathBuf> {
+    let dirs = project_dirs().ok_or_else(|| anyhow!("unable to determine cache directory"))?;
+    Ok(dirs.cache_dir().join("latest-release.json"))
+}
+
+fn read_cache() -> Option<CachedRelease> {
+    let cached: CachedRelease = serde_json::from_slice(&fs::read(cache_path().ok()?).ok()?).ok()?;
+    Some(cached).filter(|cached| Utc::now() - cached.checked < Duration::hours(CACHE_TTL_HOURS))
+}
+
+fn write_cache(version: &str) -> Result<()> {
+    let path = cache_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let cached = CachedRelease {
+        checked: Utc::now(),
+        version: version.to_string(),
+    };
+    write_atomic(&path, &serde_json::to_vec(&cached)?)
+}
+
+/// Version of the latest stable yagna release, eg. "0.9.3".
+pub async fn latest_version() -> Result<String> {
+    if let Some(cached) = read_cache() {
+        return Ok(cached.version);
+    }
+    let version = tokio::task::spawn_blocking(|| fetch_latest_release(YAGNA_REPO))
+        .await??
+        .version()
+        .to_string();
+    if let Err(e) = write_cache(&version) {
+        tracing::debug!("failed to cache latest release: {:?}", e);
+    }
+    Ok(version)
+}
+
+/// Numeric components and pre-release tag of eg. "v0.10.0-rc.1+build.5", trailing zero
+/// components dropped so that "0.10" equals "0.10.0". Build metadata is ignored.
+fn parse_version(version: &str) -> (Vec<u64>, Option<&str>) {
+    let version = version.trim().trim_start_matches('v');
+    let version = version.split('+').next().unwrap_or_default();
+    let (release, pre_release) = match version.find('-') {
+        Some(dash) => (&version[..dash], Some(&version[dash + 1..])),
+        None => (version, None),
+    };
+    let mut numbers = release
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect::<Vec<u64>>();
+    while numbers.last() == Some(&0) {
+        numbers.pop();
+    }
+    (numbers, pre_release)
+}
+
+/// Orders pre-release tags the semver way: identifier by identifier, numbers numerically
+/// and below words, a tag extending another one after it ("rc.1.1" after "rc.1").
+fn compare_pre_releases(a: &str, b: &str) -> Ordering {
+    let mut a = a.split('.');
+    let mut b = b.split('.');
+    loop {
+        let order = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => a.cmp(b),
+            },
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+}
+
+/// Compares dotted versions numerically, a release being newer than its pre-releases
+/// ("0.10.0" than "0.10.0-rc.1").
+pub fn is_newer(candidate: &str, installed: &str) -> bool {
+    let (candidate, candidate_pre_release) = parse_version(candidate);
+    let (installed, installed_pre_release) = parse_version(installed);
+    let order = candidate.cmp(&installed).then_with(|| {
+        match (candidate_pre_release, installed_pre_release) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (Some(candidate), Some(installed)) => compare_pre_releases(candidate, installed),
+        }
+    });
+    order == Ordering::Greater
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newer_by_components() {
+        assert!(is_newer("0.10.0", "0.9.3"));
+        assert!(is_newer("0.9.10", "0.9.3"));
+        assert!(is_newer("1.0.0", "0.99.99"));
+        assert!(!is_newer("0.9.3", "0.10.0"));
+        assert!(!is_newer("0.9.3", "0.9.3"));
+    }
+
+    #[test]
+    fn v_prefix_is_ignored() {
+        assert!(is_newer("v0.10.0", "0.9.3"));
+        assert!(is_newer("0.10.0", "v0.9.3"));
+        assert!(!is_newer("v0.9.3", "0.9.3"));
+    }
+
+    #[test]
+    fn missing_components_count_as_zero() {
+        assert!(!is_newer("0.10", "0.10.0"));
+        assert!(!is_newer("0.10.0", "0.10"));
+        assert!(is_newer("0.10.1", "0.10"));
+        assert!(!is_newer("0.10", "0.10.1"));
+    }
+
+    #[test]
+    fn release_is_newer_than_its_pre_releases() {
+        assert!(is_newer("0.10.0", "0.10.0-rc.1"));
+        assert!(!is_newer("0.10.0-rc.1", "0.10.0"));
+        assert!(is_newer("0.10.0-rc.1", "0.9.3"));
+        assert!(!is_newer("0.10.0-rc.1", "0.10.1"));
+    }
+
+    #[test]
+    fn pre_releases_are_ordered() {
+        assert!(is_newer("0.10.0-rc.2", "0.10.0-rc.1"));
+        assert!(is_newer("0.10.0-rc.10", "0.10.0-rc.2"));
+        assert!(is_newer("0.10.0-rc", "0.10.0-beta.3"));
+        assert!(is_newer("0.10.0-rc.1.1", "0.10.0-rc.1"));
+        assert!(!is_newer("0.10.0-rc.1", "0.10.0-rc.1"));
+        assert!(!is_newer("0.10.0-rc.1+build.7", "0.10.0-rc.1"));
+    }
+}
@@ -0,0 +1,142 @@
+//! The node seen as a requestor: what it spends and what it still has to pay
+
+use anyhow::Result;
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use ya_client::market::MarketRequestorApi;
+use ya_client::model::payment::{DebitNote, DocumentStatus};
+use ya_client::payment::PaymentApi;
+use ya_client::web::WebClient;
+use ya_core_model::payment::local::InvoiceStatusNotes;
+
+use crate::appkey;
+use crate::command::YaCommand;
+use crate::display::{self, format_duration};
+
+/// Share of an allocation left below which it's reported as running out.
+const LOW_REMAINING: f64 = 0.1;
+/// Allocations released sooner than this are reported as expiring.
+const EXPIRY_WARNING_HOURS: i64 = 1;
+
+/// Allocation reserving GLM for the payments of tasks.
+#[derive(Deserialize, Serialize)]
+pub struct AllocationSummary {
+    pub allocation_id: String,
+    pub payment_platform: String,
+    pub total: BigDecimal,
+    pub remaining: BigDecimal,
+    /// The allocation is released at this time
+    pub timeout: Option<DateTime<Utc>>,
+}
+
+impl AllocationSummary {
+    /// Why the allocation needs attention, when it's running out of GLM or time.
+    pub fn warning(&self, now: DateTime<Utc>) -> Option<String> {
+        let id = self.allocation_id.get(..8).unwrap_or(&self.allocation_id);
+        if let Some(timeout) = self.timeout {
+            if timeout - now < Duration::hours(EXPIRY_WARNING_HOURS) {
+                return Some(tr!(
+                    "allocation-expiring",
+                    id = id,
+                    left = format_duration(timeout - now)
+                ));
+            }
+        }
+        let total = self.total.to_f64().filter(|total| *total > 0.0)?;
+        if self.remaining.to_f64()? / total < LOW_REMAINING {
+            return Some(tr!(
+                "allocation-low",
+                id = id,
+                remaining = display::glm(&self.remaining)
+            ));
+        }
+        None
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct RequestorStatus {
+    /// Invoices received from providers, by state
+    pub invoices: InvoiceStatusNotes,
+    pub allocations: Vec<AllocationSummary>,
+    /// Activities with debit notes received and not accepted yet, and the amount due
+    pub pending_debit_notes: usize,
+    pub pending_debit_notes_amount: BigDecimal,
+    /// Demands currently subscribed on the market
+    pub demands: usize,
+}
+
+impl RequestorStatus {
+    pub fn warnings(&self, now: DateTime<Utc>) -> Vec<String> {
+        self.allocations
+            .iter()
+            .filter_map(|allocation| allocation.warning(now))
+            .collect()
+    }
+}
+
+pub async fn allocations(payment_api: &PaymentApi) -> Result<Vec<AllocationSummary>> {
+    Ok(payment_api
+        .get_allocations::<Utc>(None, None)
+        .await?
+        .into_iter()
+        .map(|allocation| AllocationSummary {
+            allocation_id: allocation.allocation_id,
+            payment_platform: allocation.payment_platform,
+            total: allocation.total_amount,
+            remaining: allocation.remaining_amount,
+            timeout: allocation.timeout,
+        })
+        .collect())
+}
+
+pub async fn collect(cmd: &YaCommand) -> Result<RequestorStatus> {
+    let app_key = appkey::get_app_key(cmd).await?;
+    let client = WebClient::with_token(&app_key);
+    let payment_api: PaymentApi = client.interface()?;
+    let market_api: MarketRequestorApi = client.interface()?;
+
+    let (id, (invoice_status, allocations, debit_notes, demands)) = futures::future::try_join(
+        cmd.yagna()?.default_id(),
+        futures::future::try_join4(
+            cmd.yagna()?.invoice_status(),
+            allocations(&payment_api),
+            async { Ok::<_, anyhow::Error>(payment_api.get_debit_notes::<Utc>(None, None).await?) },
+            async { Ok::<_, anyhow::Error>(market_api.get_demands().await?) },
+        ),
+    )
+    .await?;
+    // debit notes are Received by the requestor until it accepts them, the amount due
+    // grows with every note of an activity so only the latest one counts.
+    // Notes this node issued as a provider are listed too, they're for others to accept.
+    let mut pending = HashMap::<String, DebitNote>::new();
+    for note in debit_notes {
+        if !matches!(note.status, DocumentStatus::Received)
+            || !note
+                .recipient_id
+                .to_string()
+                .eq_ignore_ascii_case(&id.node_id)
+        {
+            continue;
+        }
+        match pending.get(&note.activity_id) {
+            Some(known) if known.timestamp >= note.timestamp => (),
+            _ => {
+                pending.insert(note.activity_id.clone(), note);
+            }
+        }
+    }
+    Ok(RequestorStatus {
+        invoices: invoice_status.requestor,
+        allocations,
+        pending_debit_notes: pending.len(),
+        pending_debit_notes_amount: pending
+            .into_values()
+            .map(|note| note.total_amount_due)
+            .sum(),
+        demands: demands.len(),
+    })
+}
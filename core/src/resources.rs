@@ -0,0 +1,194 @@
+//! Resources offered to requestors, the `ya-provider profile` settings
+//!
+//! Not to be confused with [`crate::profile`], which selects one of several yagna instances.
+
+use ansi_term::{Colour, Style};
+use anyhow::{anyhow, bail, Result};
+use prettytable::{cell, format, row, Table};
+use structopt::StructOpt;
+
+use crate::command::{CommandOptions, YaCommand};
+use crate::display;
+use crate::hardware;
+use crate::presets::{usage_coeff, CPU_SEC, DURATION_SEC, SECONDS_PER_HOUR};
+
+#[derive(StructOpt)]
+pub enum ResourcesCommand {
+    /// Show the resource profiles, the active one first
+    Show,
+    /// Change the resources offered through a profile
+    Set {
+        /// Threads offered
+        #[structopt(long)]
+        cores: Option<usize>,
+        /// RAM offered, eg. "48g" or "512m" (GiB without a unit)
+        #[structopt(long, parse(try_from_str = parse_gib))]
+        mem: Option<f64>,
+        /// Disk space offered, eg. "200g" or "1t" (GiB without a unit)
+        #[structopt(long, parse(try_from_str = parse_gib))]
+        storage: Option<f64>,
+        /// Profile to change (the active one by default)
+        #[structopt(long)]
+        name: Option<String>,
+    },
+}
+
+/// Size in GiB from "48g", "48GiB", "512m", "1t" or a plain number of GiB.
+fn parse_gib(size: &str) -> Result<f64> {
+    let size = size.trim().to_lowercase();
+    let digits = size.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let factor = match &size[digits.len()..] {
+        "" | "g" | "gb" | "gib" => 1.0,
+        "m" | "mb" | "mib" => 1.0 / 1024.0,
+        "t" | "tb" | "tib" => 1024.0,
+        unit => bail!(tr!("resources-bad-unit", unit = unit)),
+    };
+    let value: f64 = digits
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!(tr!("resources-bad-size", size = size.clone())))?;
+    Ok(value * factor)
+}
+
+pub async fn run(options: &CommandOptions, command: ResourcesCommand) -> Result</*exit code*/ i32> {
+    let cmd = YaCommand::new(options)?;
+    match command {
+        ResourcesCommand::Show => show(&cmd).await,
+        ResourcesCommand::Set {
+            cores,
+            mem,
+            storage,
+            name,
+        } => set(&cmd, cores, mem, storage, name).await,
+    }
+}
+
+async fn show(cmd: &YaCommand) -> Result<i32> {
+    let (profiles, active) = futures::future::try_join(
+        cmd.ya_provider()?.profiles(),
+        cmd.ya_provider()?.active_profile(),
+    )
+    .await?;
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    table.set_titles(row![
+        tr!("resources-profile"),
+        tr!("presets-active"),
+        r->tr!("resources-threads"),
+        r->tr!("resources-mem"),
+        r->tr!("resources-storage")
+    ]);
+    let mut profiles = profiles.into_iter().collect::<Vec<_>>();
+    profiles.sort_by_key(|(name, _)| *name != active);
+    for (name, resources) in &profiles {
+        let is_active = if *name == active {
+            Style::new().fg(Colour::Green).paint(tr!("presets-yes"))
+        } else {
+            Style::new().paint(tr!("presets-no"))
+        };
+        table.add_row(row![
+            name,
+            is_active,
+            r->resources.cpu_threads,
+            r->format!("{:.1}", resources.mem_gib),
+            r->format!("{:.1}", resources.storage_gib)
+        ]);
+    }
+    table.printstd();
+    print_warnings(cmd).await;
+    Ok(0)
+}
+
+/// Hourly GLM of each active preset with all offered threads busy.
+async fn preset_earnings(cmd: &YaCommand, threads: usize) -> Result<Vec<(String, f64)>> {
+    let (presets, active) = futures::future::try_join(
+        cmd.ya_provider()?.presets(),
+        cmd.ya_provider()?.active_presets(),
+    )
+    .await?;
+    Ok(presets
+        .iter()
+        .filter(|preset| active.contains(&preset.name))
+        .map(|preset| {
+            let hourly = (usage_coeff(preset, DURATION_SEC)
+                + usage_coeff(preset, CPU_SEC) * threads as f64)
+                * SECONDS_PER_HOUR;
+            (preset.name.clone(), hourly)
+        })
+        .collect())
+}
+
+async fn set(
+    cmd: &YaCommand,
+    cores: Option<usize>,
+    mem: Option<f64>,
+    storage: Option<f64>,
+    name: Option<String>,
+) -> Result<i32> {
+    if cores.is_none() && mem.is_none() && storage.is_none() {
+        bail!(tr!("resources-set-nothing"));
+    }
+    let name = match name {
+        Some(name) => name,
+        None => cmd.ya_provider()?.active_profile().await?,
+    };
+    let mut profiles = cmd.ya_provider()?.profiles().await?;
+    let before = profiles
+        .remove(&name)
+        .ok_or_else(|| anyhow!(tr!("resources-unknown", name = name.clone())))?;
+    let earnings_before = preset_earnings(cmd, before.cpu_threads).await?;
+
+    cmd.ya_provider()?
+        .update_profile(&name, cores, mem, storage)
+        .await?;
+
+    let after = cmd
+        .ya_provider()?
+        .profiles()
+        .await?
+        .remove(&name)
+        .ok_or_else(|| anyhow!(tr!("resources-unknown", name = name.clone())))?;
+    println!(
+        "{}\n",
+        tr!(
+            "resources-updated",
+            name = name,
+            threads = after.cpu_threads,
+            mem = format!("{:.1}", after.mem_gib),
+            storage = format!("{:.1}", after.storage_gib)
+        )
+    );
+
+    let earnings_after = preset_earnings(cmd, after.cpu_threads).await?;
+    if !earnings_after.is_empty() {
+        let mut table = Table::new();
+        table.set_format(*format::consts::FORMAT_CLEAN);
+        table.set_titles(row![
+            tr!("presets-name"),
+            r->tr!("resources-hourly-before", threads = before.cpu_threads),
+            r->tr!("resources-hourly-after", threads = after.cpu_threads)
+        ]);
+        for ((preset, before), (_, after)) in earnings_before.iter().zip(&earnings_after) {
+            table.add_row(row![
+                preset,
+                r->display::glm_f64(*before),
+                r->display::glm_f64(*after)
+            ]);
+        }
+        table.printstd();
+        println!("\n {}", tr!("resources-hourly-note"));
+    }
+    print_warnings(cmd).await;
+    Ok(0)
+}
+
+/// What the active profile offers beyond or far below the machine.
+async fn print_warnings(cmd: &YaCommand) {
+    let hardware = hardware::collect_with_offer(cmd).await;
+    for warning in hardware.overcommitted() {
+        println!("{}", Style::new().fg(Colour::Red).paint(warning));
+    }
+    if let Some(warning) = hardware.underused() {
+        println!("{}", Style::new().fg(Colour::Fixed(220)).paint(warning));
+    }
+}
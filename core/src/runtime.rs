@@ -0,0 +1,180 @@
+//! Inventory and self-tests of the runtimes installed for ya-provider
+
+use anyhow::{bail, Result};
+use std::process::Stdio;
+use std::time::Duration;
+use structopt::StructOpt;
+use tokio::process::Command;
+
+use crate::command::{output_with_retry, CommandOptions, ExecPolicy, RuntimeInfo, YaCommand};
+use crate::doctor::{exit_code, print_checks, Check};
+use crate::releases;
+
+/// Oldest runtime versions known to work with newer ya-provider releases:
+/// (runtime, minimal runtime version, first ya-provider version requiring it).
+const MIN_RUNTIME_VERSIONS: &[(&str, &str, &str)] =
+    &[("vm", "0.3.0", "0.10.0"), ("wasmtime", "0.2.1", "0.10.0")];
+
+#[derive(StructOpt)]
+pub enum RuntimeCommand {
+    /// List installed runtimes with their versions and compatibility
+    List,
+    /// Run the exe-unit self-test of installed runtimes
+    Test {
+        /// Runtime to test, eg. "vm" (all installed runtimes by default)
+        name: Option<String>,
+        /// Seconds to wait for a single runtime test
+        #[structopt(long, env = "YAGNA_USD_RUNTIME_TEST_TIMEOUT", default_value = "300")]
+        timeout: u64,
+    },
+}
+
+pub async fn run(options: &CommandOptions, command: RuntimeCommand) -> Result</*exit code*/ i32> {
+    match command {
+        RuntimeCommand::List => list(options).await,
+        RuntimeCommand::Test { name, timeout } => test(options, name, timeout).await,
+    }
+}
+
+async fn list(options: &CommandOptions) -> Result<i32> {
+    let cmd = YaCommand::new(options)?;
+    let (runtimes, provider_version) =
+        futures::future::join(cmd.ya_provider()?.exe_units(), cmd.ya_provider()?.version()).await;
+    let runtimes = runtimes?;
+    if runtimes.is_empty() {
+        bail!(tr!("runtime-none-installed"));
+    }
+    let provider_version = provider_version
+        .map_err(|e| tracing::warn!("unable to determine ya-provider version: {}", e))
+        .ok();
+    if let Some(version) = &provider_version {
+        println!("{}\n", tr!("runtime-provider-version", version = version));
+    }
+
+    let checks = runtimes
+        .iter()
+        .map(|runtime| check_compatibility(runtime, provider_version.as_deref()))
+        .collect::<Vec<_>>();
+    print_checks(&checks);
+    Ok(exit_code(&checks))
+}
+
+fn check_compatibility(runtime: &RuntimeInfo, provider_version: Option<&str>) -> Check {
+    let version = runtime.version.as_deref().unwrap_or("?");
+    let name = format!("{} {}", runtime.name, version);
+    let path = match &runtime.runtime_path {
+        Some(path) if path.exists() => path.display().to_string(),
+        Some(path) => {
+            return Check::fail(
+                name,
+                tr!("runtime-binary-missing", path = path.display()),
+                tr!("runtime-reinstall-hint"),
+            )
+        }
+        None => {
+            return Check::fail(
+                name,
+                tr!("runtime-no-supervisor"),
+                tr!("runtime-reinstall-hint"),
+            )
+        }
+    };
+
+    let incompatible = MIN_RUNTIME_VERSIONS
+        .iter()
+        .filter(|(runtime_name, _, _)| *runtime_name == runtime.name)
+        .find(|(_, min_version, since)| {
+            let provider_requires = provider_version
+                .map(|provider| !releases::is_newer(since, provider))
+                .unwrap_or(false);
+            provider_requires
+                && runtime
+                    .version
+                    .as_deref()
+                    .map(|version| releases::is_newer(min_version, version))
+                    .unwrap_or(false)
+        });
+    match incompatible {
+        Some((_, min_version, since)) => Check::warn(
+            name,
+            tr!(
+                "runtime-incompatible",
+                min_version = min_version,
+                provider_version = since
+            ),
+            tr!("runtime-reinstall-hint"),
+        ),
+        None => Check::pass(name, path),
+    }
+}
+
+async fn test(options: &CommandOptions, name: Option<String>, timeout: u64) -> Result<i32> {
+    let cmd = YaCommand::new(options)?;
+    let runtimes = cmd
+        .ya_provider()?
+        .exe_units()
+        .await?
+        .into_iter()
+        .filter(|runtime| match &name {
+            Some(name) => name == &runtime.name,
+            None => true,
+        })
+        .collect::<Vec<_>>();
+    if runtimes.is_empty() {
+        match name {
+            Some(name) => bail!(tr!("runtime-unknown", name = name)),
+            None => bail!(tr!("runtime-none-installed")),
+        }
+    }
+
+    // the VM runtime boots a whole image, so the usual command timeout is far too short
+    let policy = ExecPolicy {
+        timeout: Duration::from_secs(timeout),
+        retries: 0,
+        ..ExecPolicy::from(options)
+    };
+    let mut checks = vec![];
+    for runtime in &runtimes {
+        eprintln!("{}", tr!("runtime-testing", name = runtime.name));
+        checks.push(test_runtime(runtime, policy).await);
+    }
+
+    print_checks(&checks);
+    Ok(exit_code(&checks))
+}
+
+/// Runs `<supervisor> --binary <runtime> test`, the same self-test ya-provider does on startup.
+async fn test_runtime(runtime: &RuntimeInfo, policy: ExecPolicy) -> Check {
+    let name = runtime.name.clone();
+    let (supervisor, binary) = match (&runtime.supervisor_path, &runtime.runtime_path) {
+        (Some(supervisor), Some(binary)) => (supervisor, binary),
+        _ => return Check::fail(name, tr!("runtime-no-supervisor"), tr!("runtime-test-hint")),
+    };
+
+    let mut command = Command::new(supervisor);
+    command.arg("--binary").arg(binary);
+    for arg in &runtime.extra_args {
+        command.arg("--runtime-arg").arg(arg);
+    }
+    command
+        .arg("test")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    match output_with_retry(&mut command, policy).await {
+        Ok(_) => Check::pass(name, tr!("runtime-test-passed")),
+        Err(e) => {
+            tracing::debug!("runtime {} self-test failed: {:?}", runtime.name, e);
+            let error = format!("{:#}", e);
+            let reason = error
+                .lines()
+                .rev()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .unwrap_or_default()
+                .to_string();
+            Check::fail(name, reason, tr!("runtime-test-hint"))
+        }
+    }
+}
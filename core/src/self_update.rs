@@ -0,0 +1,154 @@
+//! Replacing the running yagna-usd binary with the latest release
+
+use anyhow::{anyhow, bail, Context, Result};
+use sha2::{Digest, Sha256};
+use std::env::{self, consts};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use structopt::StructOpt;
+
+use crate::http;
+use crate::releases::{self, GithubRelease};
+
+const REPO: &str = "golemfactory/yagna-usd";
+
+/// Release binaries are a few MB, anything much larger is not what we expect.
+const MAX_DOWNLOAD_BYTES: u64 = 200 * 1024 * 1024;
+
+#[derive(StructOpt)]
+pub struct SelfUpdateCommand {
+    /// Reinstall even if the latest release is already installed
+    #[structopt(long)]
+    force: bool,
+}
+
+pub async fn run(args: SelfUpdateCommand) -> Result</*exit code*/ i32> {
+    let release = tokio::task::spawn_blocking(|| releases::fetch_latest_release(REPO)).await??;
+    let installed = env!("CARGO_PKG_VERSION");
+    if !args.force && !releases::is_newer(release.version(), installed) {
+        println!("{}", tr!("self-update-up-to-date", version = installed));
+        return Ok(0);
+    }
+    println!(
+        "{}",
+        tr!(
+            "self-update-updating",
+            from = installed,
+            to = release.version()
+        )
+    );
+    tokio::task::spawn_blocking(move || update(&release)).await??;
+    println!("{}", tr!("self-update-done"));
+    Ok(0)
+}
+
+/// Name of the release asset built for this platform, eg. "yagna-usd-linux-x86_64".
+fn asset_name() -> String {
+    format!(
+        "yagna-usd-{}-{}{}",
+        consts::OS,
+        consts::ARCH,
+        consts::EXE_SUFFIX
+    )
+}
+
+fn download(url: &str) -> Result<Vec<u8>> {
+    tracing::debug!("downloading {}", url);
+    let mut body = vec![];
+    http::get(url)?
+        .call()
+        .map_err(http::explain)?
+        .into_reader()
+        .take(MAX_DOWNLOAD_BYTES)
+        .read_to_end(&mut body)?;
+    Ok(body)
+}
+
+fn update(release: &GithubRelease) -> Result<()> {
+    let name = asset_name();
+    let checksum_name = format!("{}.sha256", name);
+    let find = |name: &str| {
+        release
+            .assets
+            .iter()
+            .find(|asset| asset.name == name)
+            .ok_or_else(|| anyhow!(tr!("self-update-no-asset", name = name)))
+    };
+    let (binary, checksum) = (find(&name)?, find(&checksum_name)?);
+
+    let expected = String::from_utf8(download(&checksum.browser_download_url)?)?;
+    let expected = expected
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    let body = download(&binary.browser_download_url)?;
+    let actual = hex::encode(Sha256::digest(&body));
+    if actual != expected {
+        bail!(tr!("self-update-checksum-mismatch", name = name));
+    }
+
+    replace_current_exe(&body)
+}
+
+/// Writes the new binary next to the current one and renames it over it, so an
+/// interrupted update never leaves a half written executable behind.
+fn replace_current_exe(body: &[u8]) -> Result<()> {
+    let exe = env::current_exe()?.canonicalize()?;
+    let dir = exe
+        .parent()
+        .ok_or_else(|| anyhow!("{} has no parent directory", exe.display()))?;
+    let staged = dir.join(format!(".yagna-usd-update{}", consts::EXE_SUFFIX));
+    let replaced = install(&staged, &exe, body);
+    if replaced.is_err() {
+        let _ = fs::remove_file(&staged);
+    }
+    replaced
+}
+
+/// Writes `body` to `staged` and renames it to `exe`.
+fn install(staged: &Path, exe: &Path, body: &[u8]) -> Result<()> {
+    fs::write(staged, body).with_context(|| {
+        format!(
+            "writing {} (try running as the owner of the binary)",
+            staged.display()
+        )
+    })?;
+    make_executable(staged)?;
+
+    // Windows doesn't allow replacing a running executable, but allows renaming it
+    #[cfg(windows)]
+    let old = {
+        let old = exe.with_extension("old.exe");
+        let _ = fs::remove_file(&old);
+        fs::rename(exe, &old)?;
+        old
+    };
+    if let Err(e) = fs::rename(staged, exe) {
+        // the current binary is put back rather than leaving none at all
+        #[cfg(windows)]
+        if let Err(restore) = fs::rename(&old, exe) {
+            tracing::warn!(
+                "failed to restore {} from {}: {:?}",
+                exe.display(),
+                old.display(),
+                restore
+            );
+        }
+        return Err(e).with_context(|| format!("replacing {}", exe.display()));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,455 @@
+//! End-to-end test of the local provider: the same yagna node acts as a requestor,
+//! orders a task from its own provider on a testnet and pays for it
+//!
+//! The provider is moved to a private subnet for the test, so no other requestor gets its
+//! offers meanwhile and the demand reaches no other provider; the subnet is restored after.
+
+use anyhow::{anyhow, bail, Result};
+use bigdecimal::BigDecimal;
+use chrono::{Duration, Utc};
+use futures::future::{self, Either};
+use sha2::{Digest, Sha256};
+use std::time::Instant;
+use structopt::StructOpt;
+
+use ya_client::activity::ActivityRequestorControlApi;
+use ya_client::market::MarketRequestorApi;
+use ya_client::model::activity::{CommandResult, ExeScriptRequest};
+use ya_client::model::market::proposal::State;
+use ya_client::model::market::{AgreementProposal, NewDemand, NewProposal, RequestorEvent};
+use ya_client::model::payment::{Acceptance, NewAllocation};
+use ya_client::payment::PaymentApi;
+use ya_client::web::WebClient;
+use ya_core_model::payment::local::NetworkName;
+
+use crate::appkey;
+use crate::command::{
+    CommandOptions, NetworkGroup, YaCommand, ERC20_DRIVER, NETWORK_GROUP_MAP, PUBLIC_SUBNET,
+};
+use crate::doctor::{exit_code, print_checks, Check};
+use crate::drivers;
+
+/// GLM reserved for the test task, far more than a few seconds of any sane preset cost.
+const ALLOCATION_GLM: u32 = 1;
+/// Seconds a single market or payment poll waits for events.
+const POLL_TIMEOUT: f32 = 5.0;
+
+#[derive(StructOpt)]
+pub struct SelftestCommand {
+    /// Payment network of the test, only test networks are accepted
+    #[structopt(long, default_value = "goerli")]
+    network: NetworkName,
+    /// VM image to run, as "hash:sha3:<hash>:<url>"; without it no command is executed,
+    /// an activity is only started and stopped
+    #[structopt(long, env = "YAGNA_USD_SELFTEST_PACKAGE")]
+    package: Option<String>,
+    /// Seconds to wait for each stage
+    #[structopt(long, default_value = "180")]
+    timeout: u64,
+}
+
+/// Requestor side of the test together with what has to be cleaned up afterwards.
+struct Session {
+    market: MarketRequestorApi,
+    activity: ActivityRequestorControlApi,
+    payment: PaymentApi,
+    timeout: std::time::Duration,
+    checks: Vec<Check>,
+    subscription: Option<String>,
+    agreement: Option<String>,
+    allocation: Option<String>,
+    /// Subnet of the provider before the test, put back by the cleanup
+    provider_subnet: Option<String>,
+}
+
+/// Subnet tag no one else uses, eg. "selftest-1a2b3c4d".
+fn private_subnet() -> String {
+    let seed = format!("{}-{}", std::process::id(), Utc::now().timestamp_nanos());
+    format!(
+        "selftest-{}",
+        hex::encode(&Sha256::digest(seed.as_bytes())[..4])
+    )
+}
+
+pub async fn run(options: &CommandOptions, args: SelftestCommand) -> Result</*exit code*/ i32> {
+    // real funds would be spent on anything but a known testnet
+    if !NETWORK_GROUP_MAP[&NetworkGroup::Testnet].contains(&args.network) {
+        bail!(tr!("selftest-mainnet", network = args.network));
+    }
+    let provider_networks = drivers::networks();
+    if !provider_networks.contains(&args.network) {
+        bail!(tr!(
+            "selftest-provider-network",
+            network = args.network,
+            networks = provider_networks
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    let cmd = YaCommand::new(options)?;
+    let app_key = appkey::get_app_key(&cmd).await?;
+    let client = WebClient::with_token(&app_key);
+    let mut session = Session {
+        market: client.interface()?,
+        activity: client.interface()?,
+        payment: client.interface()?,
+        timeout: std::time::Duration::from_secs(args.timeout),
+        checks: vec![],
+        subscription: None,
+        agreement: None,
+        allocation: None,
+        provider_subnet: None,
+    };
+
+    // Ctrl-C stops the test but not the cleanup, the provider mustn't stay in the private subnet
+    let interrupted = {
+        let test = session.test(&cmd, &args);
+        let ctrl_c = tokio::signal::ctrl_c();
+        futures::pin_mut!(test, ctrl_c);
+        match future::select(test, ctrl_c).await {
+            Either::Left((result, _)) => {
+                if let Err(e) = result {
+                    tracing::debug!("selftest stopped: {:?}", e);
+                }
+                false
+            }
+            Either::Right(_) => {
+                eprintln!("{}", tr!("selftest-interrupted"));
+                true
+            }
+        }
+    };
+    session.cleanup(&cmd).await;
+
+    print_checks(&session.checks);
+    Ok(if interrupted {
+        130
+    } else {
+        exit_code(&session.checks)
+    })
+}
+
+impl Session {
+    /// Records the outcome of a stage, an error ends the test.
+    fn stage<T>(&mut self, name: String, hint: String, result: Result<(T, String)>) -> Result<T> {
+        match result {
+            Ok((value, detail)) => {
+                eprintln!("{}", tr!("selftest-passed", stage = name));
+                self.checks.push(Check::pass(name, detail));
+                Ok(value)
+            }
+            Err(e) => {
+                self.checks
+                    .push(Check::fail(name.clone(), format!("{:#}", e), hint));
+                Err(anyhow!(tr!("selftest-failed", stage = name)))
+            }
+        }
+    }
+
+    async fn test(&mut self, cmd: &YaCommand, args: &SelftestCommand) -> Result<()> {
+        let platform = ERC20_DRIVER.platform(&args.network)?;
+
+        let result: Result<(_, String)> = async {
+            let (id, config) = futures::future::try_join(
+                cmd.yagna()?.default_id(),
+                cmd.ya_provider()?.get_config(),
+            )
+            .await?;
+            // remembered first, a set interrupted half way is undone by the cleanup as well;
+            // no subnet configured means the public one
+            self.provider_subnet = Some(config.subnet.unwrap_or_else(|| PUBLIC_SUBNET.to_string()));
+            let subnet = private_subnet();
+            cmd.ya_provider()?
+                .set_config(None, Some(&subnet), None)
+                .await?;
+            let detail = tr!(
+                "selftest-identity-detail",
+                node = id.node_id,
+                subnet = subnet.clone()
+            );
+            Ok(((id.node_id, subnet), detail))
+        }
+        .await;
+        let (node_id, subnet) = self.stage(
+            tr!("selftest-identity"),
+            tr!("selftest-identity-hint"),
+            result,
+        )?;
+
+        let result: Result<(_, String)> = async {
+            cmd.yagna()?
+                .payment_init_sender(&node_id, &args.network, &ERC20_DRIVER)
+                .await?;
+            if let Err(e) = cmd
+                .yagna()?
+                .payment_fund(&node_id, &args.network, &ERC20_DRIVER)
+                .await
+            {
+                // enough test tokens may be there already
+                tracing::debug!("payment fund failed: {:?}", e);
+            }
+            let allocation = self
+                .payment
+                .create_allocation(&NewAllocation {
+                    address: Some(node_id.clone()),
+                    payment_platform: Some(platform.platform.to_string()),
+                    total_amount: BigDecimal::from(ALLOCATION_GLM),
+                    timeout: None,
+                    make_deposit: false,
+                })
+                .await?;
+            let detail = tr!(
+                "selftest-allocation-detail",
+                amount = ALLOCATION_GLM,
+                token = platform.token
+            );
+            Ok((allocation.allocation_id, detail))
+        }
+        .await;
+        let allocation = self.stage(
+            tr!("selftest-allocation"),
+            tr!("selftest-allocation-hint"),
+            result,
+        )?;
+        self.allocation = Some(allocation.clone());
+
+        let mut properties = serde_json::json!({
+            "golem.node.debug.subnet": subnet,
+            "golem.srv.comp.expiration": (Utc::now() + Duration::minutes(30)).timestamp_millis(),
+            "golem.com.payment.debit-notes.accept-timeout?": 240,
+        });
+        properties[format!("golem.com.payment.platform.{}.address", platform.platform)] =
+            node_id.clone().into();
+        let constraints = match &args.package {
+            Some(package) => {
+                properties["golem.srv.comp.task_package"] = package.clone().into();
+                format!(
+                    "(&(golem.node.debug.subnet={})(golem.runtime.name=vm))",
+                    subnet
+                )
+            }
+            None => format!("(golem.node.debug.subnet={})", subnet),
+        };
+        let result: Result<(_, String)> = async {
+            let subscription = self
+                .market
+                .subscribe(&NewDemand::new(properties.clone(), constraints.clone()))
+                .await?;
+            let detail = tr!("selftest-demand-detail", subnet = subnet);
+            Ok((subscription, detail))
+        }
+        .await;
+        let subscription =
+            self.stage(tr!("selftest-demand"), tr!("selftest-demand-hint"), result)?;
+        self.subscription = Some(subscription.clone());
+
+        let result = self
+            .negotiate(&subscription, &node_id, properties, constraints)
+            .await;
+        let agreement = self.stage(
+            tr!("selftest-agreement"),
+            tr!("selftest-agreement-hint"),
+            result,
+        )?;
+        self.agreement = Some(agreement.clone());
+
+        let result = self.execute(&agreement, args.package.is_some()).await;
+        self.stage(
+            tr!("selftest-activity"),
+            tr!("selftest-activity-hint"),
+            result,
+        )?;
+
+        if let Err(e) = self.market.terminate_agreement(&agreement, &None).await {
+            tracing::debug!("terminating agreement {} failed: {:?}", agreement, e);
+        }
+        self.agreement = None;
+
+        let result = self.settle(&agreement, &allocation).await;
+        self.stage(
+            tr!("selftest-invoice"),
+            tr!("selftest-invoice-hint"),
+            result,
+        )?;
+        Ok(())
+    }
+
+    /// Waits for an offer of our own provider and negotiates an agreement with it.
+    async fn negotiate(
+        &self,
+        subscription: &str,
+        node_id: &str,
+        properties: serde_json::Value,
+        constraints: String,
+    ) -> Result<(String, String)> {
+        let started = Instant::now();
+        let mut countered = false;
+        let proposal = loop {
+            if started.elapsed() > self.timeout {
+                bail!(tr!(if countered {
+                    "selftest-no-counter-proposal"
+                } else {
+                    "selftest-no-offer"
+                }));
+            }
+            let events = self
+                .market
+                .collect(subscription, Some(POLL_TIMEOUT), None)
+                .await?;
+            let mut found = None;
+            for event in events {
+                let proposal = match event {
+                    RequestorEvent::ProposalEvent { proposal, .. } => proposal,
+                    RequestorEvent::ProposalRejectedEvent { reason, .. } => {
+                        bail!(tr!(
+                            "selftest-rejected",
+                            reason = reason.map(|r| r.message).unwrap_or_default()
+                        ))
+                    }
+                    _ => continue,
+                };
+                if proposal.issuer_id.to_string() != node_id {
+                    continue;
+                }
+                match proposal.state {
+                    State::Initial if !countered => {
+                        self.market
+                            .counter_proposal(
+                                &NewProposal {
+                                    properties: properties.clone(),
+                                    constraints: constraints.clone(),
+                                },
+                                subscription,
+                                &proposal.proposal_id,
+                            )
+                            .await?;
+                        countered = true;
+                    }
+                    State::Draft => found = Some(proposal),
+                    _ => (),
+                }
+            }
+            if let Some(proposal) = found {
+                break proposal;
+            }
+        };
+
+        let agreement = self
+            .market
+            .create_agreement(&AgreementProposal::new(
+                proposal.proposal_id,
+                Utc::now() + Duration::hours(1),
+            ))
+            .await?;
+        self.market.confirm_agreement(&agreement, None).await?;
+        self.market
+            .wait_for_approval(&agreement, Some(self.timeout.as_secs_f32()))
+            .await?;
+        let detail = tr!("selftest-agreement-detail", agreement = agreement);
+        Ok((agreement, detail))
+    }
+
+    /// Starts an activity and, with a package, runs a single command in it.
+    async fn execute(&self, agreement: &str, with_package: bool) -> Result<((), String)> {
+        let activity = self.activity.create_activity(agreement).await?;
+        let result: Result<String> = async {
+            if !with_package {
+                return Ok(tr!("selftest-activity-no-package"));
+            }
+            let script = serde_json::json!([
+                {"deploy": {}},
+                {"start": {}},
+                {"run": {"entry_point": "/bin/echo", "args": ["yagna-usd selftest"]}},
+            ]);
+            let batch = self
+                .activity
+                .exec(ExeScriptRequest::new(script.to_string()), &activity)
+                .await?;
+            let started = Instant::now();
+            loop {
+                if started.elapsed() > self.timeout {
+                    bail!(tr!("selftest-exec-timeout"));
+                }
+                let results = self
+                    .activity
+                    .get_exec_batch_results(&activity, &batch, Some(POLL_TIMEOUT), None)
+                    .await?;
+                if let Some(failed) = results
+                    .iter()
+                    .find(|result| matches!(result.result, CommandResult::Error))
+                {
+                    bail!(failed.message.clone().unwrap_or_default());
+                }
+                if results.iter().any(|result| result.is_batch_finished) {
+                    return Ok(tr!("selftest-activity-executed"));
+                }
+            }
+        }
+        .await;
+        if let Err(e) = self.activity.destroy_activity(&activity).await {
+            tracing::debug!("destroying activity {} failed: {:?}", activity, e);
+        }
+        result.map(|detail| ((), detail))
+    }
+
+    /// Waits for the provider's invoice for `agreement` and accepts it.
+    async fn settle(&self, agreement: &str, allocation: &str) -> Result<((), String)> {
+        let started = Instant::now();
+        let invoice = loop {
+            if started.elapsed() > self.timeout {
+                bail!(tr!("selftest-no-invoice"));
+            }
+            let invoices = self.payment.get_invoices::<Utc>(None, None).await?;
+            if let Some(invoice) = invoices
+                .into_iter()
+                .find(|invoice| invoice.agreement_id == agreement)
+            {
+                break invoice;
+            }
+            tokio::time::delay_for(std::time::Duration::from_secs_f32(POLL_TIMEOUT)).await;
+        };
+        self.payment
+            .accept_invoice(
+                &invoice.invoice_id,
+                &Acceptance {
+                    total_amount_accepted: invoice.amount.clone(),
+                    allocation_id: allocation.to_string(),
+                },
+            )
+            .await?;
+        let detail = tr!("selftest-invoice-detail", amount = invoice.amount);
+        Ok(((), detail))
+    }
+
+    /// Best effort, a failed test leaves no subscriptions, agreements or allocations behind
+    /// and the provider back in its subnet.
+    async fn cleanup(&mut self, cmd: &YaCommand) {
+        if let Some(agreement) = self.agreement.take() {
+            if let Err(e) = self.market.terminate_agreement(&agreement, &None).await {
+                tracing::debug!("terminating agreement {} failed: {:?}", agreement, e);
+            }
+        }
+        if let Some(subscription) = self.subscription.take() {
+            if let Err(e) = self.market.unsubscribe(&subscription).await {
+                tracing::debug!("unsubscribing demand {} failed: {:?}", subscription, e);
+            }
+        }
+        if let Some(allocation) = self.allocation.take() {
+            if let Err(e) = self.payment.release_allocation(&allocation).await {
+                tracing::debug!("releasing allocation {} failed: {:?}", allocation, e);
+            }
+        }
+        if let Some(subnet) = self.provider_subnet.take() {
+            let restored = match cmd.ya_provider() {
+                Ok(provider) => provider.set_config(None, Some(&subnet), None).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = restored {
+                eprintln!("{}", tr!("selftest-subnet-not-restored", subnet = subnet));
+                tracing::debug!("restoring subnet {} failed: {:?}", subnet, e);
+            }
+        }
+    }
+}
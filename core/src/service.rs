@@ -0,0 +1,220 @@
+//! Starting and stopping yagna and ya-provider, through the service manager when they
+//! are registered there and directly otherwise
+
+use anyhow::{anyhow, bail, Result};
+use std::path::Path;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+use sysinfo::{Pid, ProcessExt, Signal, System, SystemExt};
+use tokio::process::Command;
+
+use crate::command::{CommandOptions, YaCommand};
+use crate::platform::{self, ServiceState};
+use crate::processes;
+use crate::utils::yagna_api_url;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(StructOpt)]
+pub enum ServiceCommand {
+    /// Start yagna and ya-provider and wait until they are ready
+    Start {
+        /// Seconds to wait for the daemons to get ready
+        #[structopt(long, default_value = "60")]
+        timeout: u64,
+    },
+    /// Stop ya-provider and yagna
+    Stop {
+        /// Seconds to wait for the daemons to exit
+        #[structopt(long, default_value = "30")]
+        timeout: u64,
+    },
+    /// Stop and start again
+    Restart {
+        /// Seconds to wait for the daemons to exit and then to get ready
+        #[structopt(long, default_value = "60")]
+        timeout: u64,
+    },
+}
+
+pub async fn run(options: &CommandOptions, command: ServiceCommand) -> Result</*exit code*/ i32> {
+    let cmd = YaCommand::new(options)?;
+    let services = platform::service_states().await;
+    match command {
+        ServiceCommand::Start { timeout } => start(&cmd, &services, timeout).await,
+        ServiceCommand::Stop { timeout } => stop(&services, timeout).await,
+        ServiceCommand::Restart { timeout } => {
+            let code = stop(&services, timeout).await?;
+            if code != 0 {
+                return Ok(code);
+            }
+            start(&cmd, &services, timeout).await
+        }
+    }
+}
+
+/// Names of the node's processes running, daemons of other datadirs are left out.
+fn running_daemons() -> Vec<String> {
+    let mut running = processes::node_processes()
+        .into_iter()
+        .map(|(_, name)| name)
+        .collect::<Vec<_>>();
+    running.sort();
+    running.dedup();
+    running
+}
+
+/// Names of the `node` processes that haven't exited yet.
+fn still_running(node: &[(Pid, String)]) -> Vec<String> {
+    let mut sys = System::new();
+    sys.refresh_processes();
+    let mut running = node
+        .iter()
+        .filter(|(pid, _)| sys.process(*pid).is_some())
+        .map(|(_, name)| name.clone())
+        .collect::<Vec<_>>();
+    running.dedup();
+    running
+}
+
+/// The yagna unit goes first when the daemons are registered as separate services.
+fn start_order(services: &[ServiceState]) -> Vec<&ServiceState> {
+    let mut ordered = services.iter().collect::<Vec<_>>();
+    ordered.sort_by_key(|service| service.name.contains("provider"));
+    ordered
+}
+
+async fn control(service: &ServiceState, action: &str) -> Result<()> {
+    let argv = service.control_command(action);
+    let (program, args) = argv
+        .split_first()
+        .ok_or_else(|| anyhow!("no command to {} {}", action, service.name))?;
+    println!("$ {}", argv.join(" "));
+    // inherited stdio lets sudo and polkit ask for a password
+    let status = Command::new(program).args(args).status().await?;
+    if !status.success() {
+        bail!("{} failed with {}", argv.join(" "), status);
+    }
+    Ok(())
+}
+
+/// Starts `program` in its own session, so it outlives yagna-usd and the terminal.
+fn spawn_detached(program: &Path, args: &[&str]) -> Result<()> {
+    let mut command = std::process::Command::new(program);
+    command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    #[cfg(unix)]
+    unsafe {
+        use std::os::unix::process::CommandExt;
+        command.pre_exec(|| {
+            nix::unistd::setsid().map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+            Ok(())
+        });
+    }
+    println!("$ {} {}", program.display(), args.join(" "));
+    command
+        .spawn()
+        .map_err(|e| anyhow!("starting {}: {}", program.display(), e))?;
+    Ok(())
+}
+
+async fn start(cmd: &YaCommand, services: &[ServiceState], timeout: u64) -> Result<i32> {
+    if cmd.is_yagna_running().await? {
+        println!("{}", tr!("service-already-running"));
+        return Ok(0);
+    }
+    if services.is_empty() {
+        // golemsp runs both daemons, without it they are started one by one
+        match cmd.resolve("golemsp") {
+            Some(golemsp) => spawn_detached(&golemsp, &["run"])?,
+            None => {
+                let yagna = cmd
+                    .resolve("yagna")
+                    .ok_or_else(|| anyhow!(tr!("service-not-installed")))?;
+                spawn_detached(&yagna, &["service", "run"])?;
+                if wait_ready(cmd, timeout, false).await? {
+                    if let Some(provider) = cmd.resolve("ya-provider") {
+                        spawn_detached(&provider, &["run"])?;
+                    }
+                }
+            }
+        }
+    } else {
+        for service in start_order(services) {
+            control(service, "start").await?;
+        }
+    }
+
+    Ok(if wait_ready(cmd, timeout, true).await? {
+        println!("{}", tr!("service-ready", url = yagna_api_url()));
+        0
+    } else {
+        eprintln!("{}", tr!("service-not-ready", seconds = timeout));
+        1
+    })
+}
+
+/// Polls until the yagna API answers and, with `provider`, ya-provider runs too.
+async fn wait_ready(cmd: &YaCommand, timeout: u64, provider: bool) -> Result<bool> {
+    let started = Instant::now();
+    eprintln!("{}", tr!("service-waiting"));
+    while started.elapsed() < Duration::from_secs(timeout) {
+        let provider_running =
+            !provider || running_daemons().iter().any(|name| name == "ya-provider");
+        if provider_running && cmd.is_yagna_running().await? {
+            return Ok(true);
+        }
+        tokio::time::delay_for(POLL_INTERVAL).await;
+    }
+    Ok(false)
+}
+
+async fn stop(services: &[ServiceState], timeout: u64) -> Result<i32> {
+    // without a service manager only the daemons of the selected datadirs are signalled
+    // and waited for, nodes of other datadirs keep running
+    let signalled = if services.is_empty() {
+        let mut sys = System::new();
+        sys.refresh_processes();
+        let node = processes::node_processes();
+        for (pid, name) in &node {
+            if let Some(process) = sys.process(*pid) {
+                println!("{}", tr!("service-stopping", name = name.as_str()));
+                // SIGTERM lets them shut down cleanly, only where it doesn't exist (Windows)
+                // they are killed right away
+                if process.kill_with(Signal::Term).is_none() {
+                    process.kill();
+                }
+            }
+        }
+        Some(node)
+    } else {
+        for service in start_order(services).into_iter().rev() {
+            control(service, "stop").await?;
+        }
+        None
+    };
+
+    let started = Instant::now();
+    loop {
+        let running = match &signalled {
+            Some(node) => still_running(node),
+            None => running_daemons(),
+        };
+        if running.is_empty() {
+            println!("{}", tr!("service-stopped"));
+            return Ok(0);
+        }
+        if started.elapsed() >= Duration::from_secs(timeout) {
+            eprintln!(
+                "{}",
+                tr!("service-still-running", names = running.join(", "))
+            );
+            return Ok(1);
+        }
+        tokio::time::delay_for(POLL_INTERVAL).await;
+    }
+}
@@ -0,0 +1,88 @@
+//! Status snapshots saved to a file, eg. to attach to a support request, and rendered later
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+use crate::command::CommandOptions;
+use crate::display::{DisplayZone, TimeDisplay};
+use crate::status::{self, StatusQuery, StatusSnapshot};
+use crate::utils::write_atomic;
+
+#[derive(StructOpt)]
+pub enum SnapshotCommand {
+    /// Collect the status and save it as JSON
+    Save {
+        file: PathBuf,
+        #[structopt(flatten)]
+        query: StatusQuery,
+    },
+    /// Render a saved snapshot the way `status` showed it
+    Show {
+        file: PathBuf,
+        /// Show absolute timestamps instead of relative ones ("12 min ago")
+        #[structopt(long)]
+        absolute: bool,
+        /// Time zone for absolute timestamps, eg. "Europe/Warsaw" (defaults to local)
+        #[structopt(long, env = "YAGNA_USD_TZ")]
+        tz: Option<DisplayZone>,
+        /// Plain ASCII output without box-drawing characters and colors
+        #[structopt(long)]
+        ascii: bool,
+    },
+}
+
+/// Snapshot file contents. The query is kept so that the same sections are shown again.
+#[derive(Deserialize, Serialize)]
+struct SavedSnapshot {
+    /// yagna-usd version which saved the snapshot
+    saved_by: String,
+    query: StatusQuery,
+    snapshot: StatusSnapshot,
+}
+
+pub async fn run(options: &CommandOptions, command: SnapshotCommand) -> Result</*exit code*/ i32> {
+    match command {
+        SnapshotCommand::Save { file, query } => {
+            let snapshot = status::collect_status(options, &query).await?;
+            let saved = SavedSnapshot {
+                saved_by: env!("CARGO_PKG_VERSION").to_string(),
+                query,
+                snapshot,
+            };
+            write_atomic(&file, serde_json::to_string_pretty(&saved)?.as_bytes())?;
+            println!("{}", tr!("snapshot-saved", path = file.display()));
+            Ok(0)
+        }
+        SnapshotCommand::Show {
+            file,
+            absolute,
+            tz,
+            ascii,
+        } => {
+            let content = std::fs::read_to_string(&file)
+                .map_err(|e| anyhow!("reading {}: {}", file.display(), e))?;
+            let saved: SavedSnapshot = serde_json::from_str(&content)
+                .map_err(|e| anyhow!(tr!("snapshot-invalid", path = file.display(), error = e)))?;
+            let ascii = ascii || env::var("TERM").map(|t| t == "dumb").unwrap_or(false);
+            let time_display = TimeDisplay {
+                absolute,
+                zone: tz.unwrap_or_else(DisplayZone::system),
+                now: saved.snapshot.collected_at,
+            };
+            let header = tr!(
+                "snapshot-header",
+                date = time_display.render_absolute(saved.snapshot.collected_at),
+                version = saved.saved_by
+            );
+            println!("{}", header);
+            print!(
+                "{}",
+                status::render(&saved.snapshot, &saved.query, &time_display, ascii)
+            );
+            Ok(0)
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! Client of the public Golem network stats API
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::http;
+
+const DEFAULT_STATS_URL: &str = "https://api.stats.golem.network";
+
+/// Base URL of the stats API, from `YAGNA_USD_STATS_URL`.
+pub fn stats_url() -> String {
+    std::env::var("YAGNA_USD_STATS_URL").unwrap_or_else(|_| DEFAULT_STATS_URL.into())
+}
+
+/// Prices of a single provider seen on the network, in GLM.
+#[derive(Deserialize)]
+pub struct ProviderPricing {
+    pub cpu_per_hour: f64,
+    pub env_per_hour: f64,
+    pub start_price: f64,
+}
+
+/// Provider currently online, with its lifetime statistics.
+#[derive(Deserialize, Serialize)]
+pub struct OnlineNode {
+    pub node_id: String,
+    #[serde(default)]
+    pub tasks_total: u64,
+    /// Total earnings in GLM
+    #[serde(default)]
+    pub earnings_total: f64,
+    /// Percentage of time the node was seen online
+    #[serde(default)]
+    pub uptime: f64,
+}
+
+/// Provider as last seen by the stats API market scans.
+#[derive(Deserialize, Serialize)]
+pub struct NodeInfo {
+    #[serde(default)]
+    pub online: bool,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Fetches `path`, mapping 404 responses to `None`.
+async fn get_optional<T: DeserializeOwned + Send + 'static>(path: String) -> Result<Option<T>> {
+    let url = format!("{}{}", stats_url(), path);
+    tokio::task::spawn_blocking(move || -> Result<Option<T>> {
+        tracing::debug!("fetching {}", url);
+        match http::get(&url)?.call() {
+            Ok(response) => Ok(Some(response.into_json()?)),
+            Err(ureq::Error::Status(404, _)) => Ok(None),
+            Err(e) => Err(http::explain(e)),
+        }
+    })
+    .await?
+}
+
+async fn get<T: DeserializeOwned + Send + 'static>(path: &str) -> Result<T> {
+    get_optional(path.to_string())
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("{}{} not found", stats_url(), path))
+}
+
+/// Current prices of all online providers.
+pub async fn pricing() -> Result<Vec<ProviderPricing>> {
+    get("/v2/network/pricing/dump").await
+}
+
+/// All providers currently online.
+pub async fn online_nodes() -> Result<Vec<OnlineNode>> {
+    get("/v2/network/online").await
+}
+
+/// Visibility of a single node, `None` if the stats API never saw it.
+pub async fn node(node_id: &str) -> Result<Option<NodeInfo>> {
+    get_optional(format!("/v2/provider/node/{}", node_id)).await
+}
+
+/// Value below which `p` percent of `values` fall (nearest rank).
+pub fn percentile(values: &[f64], p: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// Percentage of `values` not greater than `value`.
+pub fn percentile_rank(values: &[f64], value: f64) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let below = values.iter().filter(|v| **v <= value).count();
+    below as f64 * 100.0 / values.len() as f64
+}
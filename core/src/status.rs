@@ -0,0 +1,2969 @@
+use std::collections::BTreeMap;
+use std::env;
+
+use ansi_term::{Colour, Style};
+use anyhow::{anyhow, Result};
+use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
+use byte_unit::Byte;
+use chrono::{DateTime, Utc};
+use futures::prelude::*;
+use prettytable::{cell, format, row, Cell, Row, Table};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::path::PathBuf;
+use std::time::Duration;
+use structopt::StructOpt;
+use strum::VariantNames;
+use strum_macros::{Display, EnumString, EnumVariantNames};
+
+use ya_core_model::payment::local::{Account, InvoiceStats, NetworkName, StatusResult};
+use ya_core_model::NodeId;
+
+use crate::address;
+use crate::appkey;
+use crate::command::{
+    ActivityStatus, CommandOptions, Id, NetStatus, NetworkGroup, PaymentSummary, ProviderConfig,
+    VersionRaw, YaCommand, ERC20_DRIVER, NETWORK_GROUP_MAP, PUBLIC_SUBNET, ZKSYNC_DRIVER,
+};
+use crate::diff::{self, Change};
+use crate::display::{
+    self, format_duration, format_relative, strip_styles, DisplayZone, TimeDisplay,
+};
+use crate::drivers::{self, DriverStatus};
+use crate::gpu::{self, GpuStatus};
+use crate::hardware::{self, Hardware};
+use crate::logs::{self, Problem};
+use crate::market::{self, MarketActivity};
+use crate::payments::{
+    acceptance_latency, debit_note_stats, failed_payments, AcceptanceLatency, DebitNoteStats,
+    FailedPayments, FAILURES_WINDOW_HOURS,
+};
+use crate::platform::{self, Environment, ServiceState, Status as KvmStatus};
+use crate::presets;
+use crate::processes::{self, ProcessMetrics};
+use crate::profit::{self, PowerCost, Profitability};
+use crate::progress::Progress;
+use crate::rates::{self, FiatPrice, CURRENCY};
+use crate::releases;
+use crate::requestor::{self, RequestorStatus};
+use crate::stats::{self, NodeInfo, OnlineNode};
+use crate::terms::{self, TermsStatus};
+use crate::uptime;
+use crate::utils::{payment_account, write_atomic, yagna_api_url};
+use crate::wallet::{self, BackupState};
+use crate::workload::{self, RuntimeActivity};
+
+/// Blocks of the status output which can be selected with `--sections` and `--skip`
+#[derive(
+    Clone, Copy, Debug, Display, EnumString, EnumVariantNames, Eq, PartialEq, Deserialize, Serialize,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum Section {
+    Node,
+    Versions,
+    Payments,
+    Activity,
+    P2p,
+    Processes,
+    Service,
+    Hardware,
+    Gpu,
+    Ranking,
+    Market,
+    Rejections,
+    Problems,
+    Uptime,
+    Profit,
+    Requestor,
+    #[strum(serialize = "payment-failures")]
+    #[serde(rename = "payment-failures")]
+    PaymentFailures,
+    Drivers,
+}
+
+/// Sections shown by `--requestor`, the provider ones are left out.
+const REQUESTOR_SECTIONS: &[Section] = &[
+    Section::Versions,
+    Section::Requestor,
+    Section::PaymentFailures,
+    Section::Drivers,
+    Section::P2p,
+    Section::Processes,
+    Section::Service,
+    Section::Problems,
+];
+
+#[derive(StructOpt)]
+pub struct StatusCommand {
+    /// Show absolute timestamps instead of relative ones ("12 min ago")
+    #[structopt(long)]
+    absolute: bool,
+    /// Time zone for absolute timestamps, eg. "Europe/Warsaw" (defaults to local)
+    #[structopt(long, env = "YAGNA_USD_TZ")]
+    tz: Option<DisplayZone>,
+    /// Plain ASCII output without box-drawing characters and colors
+    /// (implied when TERM=dumb)
+    #[structopt(long)]
+    ascii: bool,
+    /// Print the collected data as JSON instead of tables
+    #[structopt(long)]
+    json: bool,
+    /// Write the status to a file (replaced atomically) instead of stdout
+    #[structopt(long, short, parse(from_os_str))]
+    output: Option<PathBuf>,
+    /// Append one JSON line to the --output file instead of replacing it
+    #[structopt(long, requires_all = &["json", "output"])]
+    append: bool,
+    /// Highlight what changed since the previous status run
+    #[structopt(long, conflicts_with = "json")]
+    diff: bool,
+    /// Collect the status again every SECONDS until interrupted (at least every 5 s)
+    #[structopt(long, value_name = "SECONDS")]
+    watch: Option<u64>,
+    /// Run COMMAND with the changed values as JSON on stdin whenever one of them changes
+    #[structopt(long, value_name = "COMMAND", requires = "watch")]
+    on_change: Option<String>,
+    /// Print nothing unless there is a problem, then only to stderr (for cron)
+    #[structopt(long, short, conflicts_with_all = &["json", "output", "diff"])]
+    quiet: bool,
+    /// Exit with a code reflecting the node's health: 4 payments failing,
+    /// 5 no task for longer than --max-idle (3 when yagna is not running, as always)
+    #[structopt(long)]
+    check: bool,
+    /// Minutes without a task after which --check reports the provider as idle
+    #[structopt(
+        long,
+        default_value = "1440",
+        value_name = "MINUTES",
+        parse(try_from_str = parse_minutes)
+    )]
+    max_idle: i64,
+    /// With --quiet, also alert when GLM trades below the node's break-even price
+    #[structopt(long, requires = "quiet")]
+    break_even_alert: bool,
+    #[structopt(flatten)]
+    query: StatusQuery,
+}
+
+/// Longest span in minutes taken by the minute flags, a year.
+const MAX_MINUTES: i64 = 366 * 24 * 60;
+
+/// Positive number of minutes up to [`MAX_MINUTES`].
+fn parse_minutes(minutes: &str) -> Result<i64> {
+    match minutes.trim().parse::<i64>() {
+        Ok(count) if (1..=MAX_MINUTES).contains(&count) => Ok(count),
+        _ => Err(anyhow!(tr!(
+            "minutes-invalid",
+            value = minutes,
+            max = MAX_MINUTES
+        ))),
+    }
+}
+
+/// Duration of `count` minutes, `None` when negative or beyond what chrono represents.
+fn minutes(count: i64) -> Option<chrono::Duration> {
+    let secs = u64::try_from(count.checked_mul(60)?).ok()?;
+    chrono::Duration::from_std(Duration::from_secs(secs)).ok()
+}
+
+/// Selection of what [`collect_status`] collects
+#[derive(StructOpt, Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct StatusQuery {
+    /// Comma separated list of sections to show (all by default)
+    #[structopt(
+        long,
+        use_delimiter = true,
+        possible_values = Section::VARIANTS
+    )]
+    pub sections: Vec<Section>,
+    /// Comma separated list of sections to leave out
+    #[structopt(
+        long,
+        use_delimiter = true,
+        possible_values = Section::VARIANTS
+    )]
+    pub skip: Vec<Section>,
+    /// Don't check GitHub for a newer yagna release
+    #[structopt(long)]
+    pub no_update_check: bool,
+    /// Minutes of daemon logs scanned for recent errors and warnings
+    #[structopt(
+        long,
+        env = "YAGNA_USD_PROBLEMS_WINDOW",
+        default_value = "60",
+        parse(try_from_str = parse_minutes)
+    )]
+    pub problems_window: i64,
+    /// Days of invoices the requestors' acceptance time is measured over
+    #[structopt(long, env = "YAGNA_USD_LATENCY_DAYS", default_value = "7")]
+    pub latency_days: i64,
+    /// Show the node as a requestor: spending, allocations, debit notes and demands
+    /// instead of provider earnings
+    #[structopt(long)]
+    pub requestor: bool,
+    /// Comma separated fiat currencies the wallet amounts are converted to, eg. "usd,eur,pln"
+    #[structopt(
+        long,
+        env = "YAGNA_USD_CURRENCY",
+        use_delimiter = true,
+        default_value = "usd"
+    )]
+    pub currency: Vec<String>,
+}
+
+impl Default for StatusQuery {
+    fn default() -> Self {
+        StatusQuery {
+            sections: vec![],
+            skip: vec![],
+            no_update_check: false,
+            problems_window: 60,
+            latency_days: 7,
+            requestor: false,
+            currency: vec![CURRENCY.to_string()],
+        }
+    }
+}
+
+impl StatusQuery {
+    pub fn wants(&self, section: Section) -> bool {
+        let selected = if !self.sections.is_empty() {
+            self.sections.contains(&section)
+        } else if self.requestor {
+            REQUESTOR_SECTIONS.contains(&section)
+        } else {
+            section != Section::Requestor
+        };
+        selected && !self.skip.contains(&section)
+    }
+}
+
+/// Provider status collected in one go.
+///
+/// Sections which were not requested are `None`, sections which could not be
+/// collected hold the error. In JSON failed sections become `{"error": "..."}`.
+#[derive(Deserialize, Serialize)]
+pub struct StatusSnapshot {
+    pub collected_at: DateTime<Utc>,
+    pub is_running: bool,
+    #[serde(
+        serialize_with = "serialize_section",
+        deserialize_with = "deserialize_section"
+    )]
+    pub config: Option<Result<NodeConfig>>,
+    #[serde(
+        serialize_with = "serialize_section",
+        deserialize_with = "deserialize_section"
+    )]
+    pub version: Option<Result<VersionRaw>>,
+    /// Newer yagna release, announced by yagna itself or found on GitHub
+    pub update: Option<String>,
+    /// How the node is seen by the stats API
+    #[serde(
+        serialize_with = "serialize_section",
+        deserialize_with = "deserialize_observation"
+    )]
+    pub observed: Option<Result<Option<NodeInfo>>>,
+    pub kvm_status: KvmStatus,
+    pub environment: Environment,
+    /// Terms of use accepted with the installer or golemsp
+    #[serde(default)]
+    pub terms: Option<TermsStatus>,
+    #[serde(
+        serialize_with = "serialize_section",
+        deserialize_with = "deserialize_section"
+    )]
+    pub payments: Option<Result<Payments>>,
+    #[serde(
+        serialize_with = "serialize_section",
+        deserialize_with = "deserialize_section"
+    )]
+    pub profit: Option<Result<Profitability>>,
+    /// Spending of the node as a requestor
+    #[serde(
+        default,
+        serialize_with = "serialize_section",
+        deserialize_with = "deserialize_section"
+    )]
+    pub requestor: Option<Result<RequestorStatus>>,
+    /// Failed invoices and payment errors logged by yagna
+    #[serde(
+        default,
+        serialize_with = "serialize_section",
+        deserialize_with = "deserialize_section"
+    )]
+    pub failed_payments: Option<Result<FailedPayments>>,
+    /// RPC endpoints and errors of the payment drivers
+    #[serde(
+        default,
+        serialize_with = "serialize_section",
+        deserialize_with = "deserialize_section"
+    )]
+    pub drivers: Option<Result<DriverStatus>>,
+    #[serde(
+        serialize_with = "serialize_section",
+        deserialize_with = "deserialize_section"
+    )]
+    pub activity: Option<Result<ActivityStatus>>,
+    /// Activity broken down by runtime
+    #[serde(
+        default,
+        serialize_with = "serialize_section",
+        deserialize_with = "deserialize_section"
+    )]
+    pub workload: Option<Result<Vec<RuntimeActivity>>>,
+    #[serde(
+        serialize_with = "serialize_section",
+        deserialize_with = "deserialize_section"
+    )]
+    pub market: Option<Result<MarketActivity>>,
+    #[serde(
+        serialize_with = "serialize_section",
+        deserialize_with = "deserialize_section"
+    )]
+    pub problems: Option<Result<Vec<Problem>>>,
+    #[serde(
+        serialize_with = "serialize_section",
+        deserialize_with = "deserialize_section"
+    )]
+    pub rejections: Option<Result<Vec<(String, usize)>>>,
+    #[serde(
+        serialize_with = "serialize_section",
+        deserialize_with = "deserialize_section"
+    )]
+    pub ranking: Option<Result<Ranking>>,
+    #[serde(
+        serialize_with = "serialize_section",
+        deserialize_with = "deserialize_section"
+    )]
+    pub uptime: Option<Result<Vec<(i64, Option<f64>)>>>,
+    pub services: Option<Vec<ServiceState>>,
+    #[serde(
+        serialize_with = "serialize_section",
+        deserialize_with = "deserialize_section"
+    )]
+    pub gpu: Option<Result<GpuStatus>>,
+    pub hardware: Option<Hardware>,
+    pub processes: Option<Vec<ProcessMetrics>>,
+    #[serde(
+        serialize_with = "serialize_section",
+        deserialize_with = "deserialize_section"
+    )]
+    pub p2p: Option<Result<P2pStatus>>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct Failure {
+    error: String,
+}
+
+/// Section read back from JSON, either the collected value or the failure.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Stored<T> {
+    Failed(Failure),
+    Collected(T),
+}
+
+impl<T> From<Stored<T>> for Result<T> {
+    fn from(stored: Stored<T>) -> Self {
+        match stored {
+            Stored::Failed(failure) => Err(anyhow!(failure.error)),
+            Stored::Collected(value) => Ok(value),
+        }
+    }
+}
+
+fn serialize_result<S: Serializer, T: Serialize>(
+    result: &Result<T>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    match result {
+        Ok(value) => value.serialize(serializer),
+        Err(e) => Failure {
+            error: format!("{:#}", e),
+        }
+        .serialize(serializer),
+    }
+}
+
+fn serialize_section<S: Serializer, T: Serialize>(
+    section: &Option<Result<T>>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    match section {
+        Some(result) => serialize_result(result, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn deserialize_result<'de, D: Deserializer<'de>, T: Deserialize<'de>>(
+    deserializer: D,
+) -> std::result::Result<Result<T>, D::Error> {
+    Stored::deserialize(deserializer).map(Result::from)
+}
+
+fn deserialize_section<'de, D: Deserializer<'de>, T: Deserialize<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<Result<T>>, D::Error> {
+    Option::<Stored<T>>::deserialize(deserializer).map(|stored| stored.map(Result::from))
+}
+
+/// `null` is an observation too: the stats API doesn't know the node.
+fn deserialize_observation<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<Result<Option<NodeInfo>>>, D::Error> {
+    Stored::deserialize(deserializer).map(|stored| Some(Result::from(stored)))
+}
+
+impl StatusSnapshot {
+    /// Some of the requested sections could not be collected.
+    pub fn is_partial(&self) -> bool {
+        is_failed(&self.config)
+            || is_failed(&self.version)
+            || is_failed(&self.observed)
+            || is_failed(&self.payments)
+            || is_failed(&self.profit)
+            || is_failed(&self.requestor)
+            || is_failed(&self.failed_payments)
+            || is_failed(&self.drivers)
+            || is_failed(&self.activity)
+            || is_failed(&self.workload)
+            || is_failed(&self.market)
+            || is_failed(&self.problems)
+            || is_failed(&self.rejections)
+            || is_failed(&self.ranking)
+            || is_failed(&self.uptime)
+            || is_failed(&self.gpu)
+            || is_failed(&self.p2p)
+    }
+
+    /// Problems worth a notification, for `--quiet` runs.
+    pub fn alerts(&self) -> Vec<String> {
+        let mut alerts = vec![];
+        if !self.is_running {
+            alerts.push(tr!("alert-not-running"));
+        }
+        let failures = [
+            (Section::Node, failure(&self.config)),
+            (Section::Versions, failure(&self.version)),
+            (Section::Node, failure(&self.observed)),
+            (Section::Payments, failure(&self.payments)),
+            (Section::Profit, failure(&self.profit)),
+            (Section::Requestor, failure(&self.requestor)),
+            (Section::PaymentFailures, failure(&self.failed_payments)),
+            (Section::Drivers, failure(&self.drivers)),
+            (Section::Activity, failure(&self.activity)),
+            (Section::Activity, failure(&self.workload)),
+            (Section::Market, failure(&self.market)),
+            (Section::Problems, failure(&self.problems)),
+            (Section::Rejections, failure(&self.rejections)),
+            (Section::Ranking, failure(&self.ranking)),
+            (Section::Uptime, failure(&self.uptime)),
+            (Section::Gpu, failure(&self.gpu)),
+            (Section::P2p, failure(&self.p2p)),
+        ];
+        for (section, e) in failures {
+            if let Some(e) = e {
+                alerts.push(tr!(
+                    "alert-section-failed",
+                    section = section,
+                    error = format!("{:#}", e)
+                ));
+            }
+        }
+        if let Some(Ok(requestor)) = &self.requestor {
+            alerts.extend(requestor.warnings(self.collected_at));
+        }
+        if matches!(&self.terms, Some(terms) if terms.waiting) {
+            alerts.push(tr!("terms-waiting"));
+        }
+        alerts.extend(self.account_warning());
+        alerts.extend(self.subnet_warning());
+        if let Some(Ok(Payments {
+            backup: Some(BackupState { last: None }),
+            ..
+        })) = &self.payments
+        {
+            alerts.push(tr!("wallet-no-backup"));
+        }
+        if let Some(hardware) = &self.hardware {
+            alerts.extend(hardware.overcommitted());
+        }
+        if let Some(Ok(drivers)) = &self.drivers {
+            alerts.extend(drivers.warnings(self.collected_at));
+        }
+        if let Some(Ok(failed)) = &self.failed_payments {
+            if !failed.is_empty() {
+                alerts.push(tr!(
+                    "alert-payment-failures",
+                    invoices = failed.provider_failed + failed.requestor_failed,
+                    errors = failed.failures.iter().map(|f| f.count).sum::<usize>(),
+                    reason = failed
+                        .failures
+                        .first()
+                        .map(|f| f.reason.clone())
+                        .unwrap_or_default()
+                ));
+            }
+        }
+        alerts
+    }
+
+    /// Health of the node, idle meaning no task in the last `max_idle`, or since yagna
+    /// started when it never ran one.
+    ///
+    /// Sections which were not collected, or failed to be, are not taken into account.
+    pub fn health(&self, max_idle: chrono::Duration) -> Health {
+        let payments_failing = matches!(
+            &self.payments,
+            Some(Ok(payments)) if payments.invoice_status.provider.failed.agreements_count > 0
+        );
+        let last_activity = match &self.activity {
+            Some(Ok(activity)) => Some(activity.last_activity_ts),
+            _ => None,
+        };
+        node_health(
+            self.is_running,
+            payments_failing,
+            last_activity,
+            self.yagna_started_at(),
+            self.collected_at,
+            max_idle,
+        )
+    }
+
+    /// Start of the longest running yagna process, the daemon rather than the commands
+    /// run by the collectors meanwhile.
+    fn yagna_started_at(&self) -> Option<DateTime<Utc>> {
+        self.processes
+            .iter()
+            .flatten()
+            .filter(|process| process.name == "yagna")
+            .filter_map(|process| process.started_at)
+            .min()
+    }
+
+    /// Payment account configured in ya-provider.
+    fn account(&self) -> Option<NodeId> {
+        self.config
+            .as_ref()
+            .and_then(|config| config.as_ref().ok())
+            .and_then(|config| config.provider.account)
+    }
+
+    /// The account configured in ya-provider is not the yagna identity, is written with
+    /// a casing which breaks its checksum or yagna doesn't receive payments on it.
+    pub fn account_warning(&self) -> Option<String> {
+        let configured = self
+            .config
+            .as_ref()
+            .and_then(|config| config.as_ref().ok())
+            .and_then(|config| config.account_as_configured.as_deref());
+        let payments = self
+            .payments
+            .as_ref()
+            .and_then(|payments| payments.as_ref().ok());
+        account_mismatch(
+            configured,
+            payments.map(|payments| payments.id.node_id.as_str()),
+            payments.and_then(|payments| payments.accounts.as_deref()),
+        )
+    }
+
+    /// GLM price in the display currency, when the wallet section got it.
+    pub fn glm_price(&self) -> Option<f64> {
+        let payments = self.payments.as_ref()?.as_ref().ok()?;
+        payments.glm_price.as_ref().ok().copied().flatten()
+    }
+
+    /// Footnote with the rates behind the fiat values shown, `None` when none were.
+    pub fn rates_footnote(&self, time_display: &TimeDisplay) -> Option<String> {
+        let payments = self.payments.as_ref()?.as_ref().ok()?;
+        let mut prices = payments.glm_prices.clone();
+        // the header and profit figures are in CURRENCY even when it wasn't asked for
+        if let Ok(Some(price)) = payments.glm_price {
+            if !prices.iter().any(|quote| quote.currency == CURRENCY) {
+                prices.push(FiatPrice {
+                    currency: CURRENCY.to_string(),
+                    price: Some(price),
+                    fetched_at: prices.first().and_then(|quote| quote.fetched_at),
+                    stale: prices.iter().any(|quote| quote.stale),
+                });
+            }
+        }
+        let quotes = prices
+            .iter()
+            .map(|quote| ("GLM", quote))
+            .collect::<Vec<_>>();
+        rates::footnote(&quotes, self.collected_at, time_display)
+    }
+
+    /// Agreements ya-provider takes at the same time, when its config was collected.
+    pub fn max_agreements(&self) -> Option<u32> {
+        self.config.as_ref()?.as_ref().ok()?.max_agreements
+    }
+
+    /// The provider is not in the public subnet, with the proposals which reached it there
+    /// when the market section was collected.
+    pub fn subnet_warning(&self) -> Option<String> {
+        let config = &self.config.as_ref()?.as_ref().ok()?.provider;
+        if config.is_public_subnet() {
+            return None;
+        }
+        let subnet = config.subnet.clone().unwrap_or_default();
+        Some(match &self.market {
+            Some(Ok(market)) => tr!(
+                "subnet-not-public-proposals",
+                subnet = subnet,
+                public = PUBLIC_SUBNET,
+                count = market.last_day.proposals
+            ),
+            _ => tr!("subnet-not-public", subnet = subnet, public = PUBLIC_SUBNET),
+        })
+    }
+}
+
+/// Why the earnings may not reach the node: the ya-provider account (the identity when
+/// none is set) has a broken checksum, is not the identity or is not among the accounts yagna
+/// was initialized to receive payments on. Checks lacking their input are skipped.
+pub(crate) fn account_mismatch(
+    configured: Option<&str>,
+    identity: Option<&str>,
+    accounts: Option<&[Account]>,
+) -> Option<String> {
+    if let Some(configured) = configured {
+        if !address::has_valid_checksum(configured) {
+            return Some(tr!(
+                "account-bad-checksum",
+                account = configured.to_string(),
+                expected = address::checksummed(configured)
+            ));
+        }
+        if let Some(identity) =
+            identity.filter(|identity| !configured.eq_ignore_ascii_case(identity))
+        {
+            return Some(tr!(
+                "account-differs",
+                account = address::checksummed(configured),
+                identity = address::checksummed(identity)
+            ));
+        }
+    }
+    let paid_to = configured.or(identity)?;
+    let receiving = accounts?
+        .iter()
+        .filter(|account| account.receive)
+        .collect::<Vec<_>>();
+    if !receiving.is_empty()
+        && !receiving
+            .iter()
+            .any(|account| account.address.eq_ignore_ascii_case(paid_to))
+    {
+        return Some(tr!(
+            "account-not-receiving",
+            account = address::checksummed(paid_to),
+            receiving = receiving
+                .iter()
+                .map(|account| address::checksummed(&account.address))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    None
+}
+
+/// ya-provider config with what its offers advertise on the market.
+#[derive(Deserialize, Serialize)]
+pub struct NodeConfig {
+    #[serde(flatten)]
+    pub provider: ProviderConfig,
+    /// `account` as written in the config, with its original casing
+    #[serde(default)]
+    pub account_as_configured: Option<String>,
+    /// Presets offers are published for, when they were asked for
+    #[serde(default)]
+    pub active_presets: Option<Vec<String>>,
+    /// Payment networks of `YA_PAYMENT_NETWORK_GROUP` the provider accepts payments on
+    #[serde(default)]
+    pub payment_networks: Vec<String>,
+    /// Agreements ya-provider takes at the same time
+    #[serde(default)]
+    pub max_agreements: Option<u32>,
+    /// GLM an hour the active presets earn at full utilization of the offered resources
+    #[serde(default)]
+    pub earning_potential: Option<f64>,
+}
+
+async fn provider_config(cmd: &YaCommand) -> Result<NodeConfig> {
+    let (provider, account_as_configured) = cmd.ya_provider()?.get_config_with_account().await?;
+    let active_presets = cmd
+        .ya_provider()?
+        .active_presets()
+        .await
+        .map_err(|e| tracing::debug!("ya-provider preset active failed: {:?}", e))
+        .ok();
+    let earning_potential = presets::earning_potential(cmd)
+        .await
+        .map_err(|e| tracing::debug!("earning potential unavailable: {:?}", e))
+        .ok()
+        .flatten();
+    Ok(NodeConfig {
+        provider,
+        account_as_configured,
+        active_presets,
+        payment_networks: drivers::networks()
+            .iter()
+            .map(|network| network.to_string().to_lowercase())
+            .collect(),
+        max_agreements: Some(processes::max_simultaneous_agreements()),
+        earning_potential,
+    })
+}
+
+async fn payment_status(
+    cmd: &YaCommand,
+    network: &NetworkName,
+    account: &Option<NodeId>,
+    progress: &Progress,
+) -> anyhow::Result<BTreeMap<String, StatusResult>> {
+    let address = progress
+        .step(tr!("progress-identity"), payment_account(cmd, account))
+        .await?;
+
+    let network_group = get_network_group(network);
+
+    let mut result = BTreeMap::new();
+    let (futures, labels) = {
+        let mut f = vec![];
+        let mut l = vec![];
+        for nn in NETWORK_GROUP_MAP[&network_group].iter() {
+            if let Ok(_) = ZKSYNC_DRIVER.platform(&nn) {
+                l.push("zksync".to_string());
+                f.push(progress.step(
+                    tr!("progress-payment-status", network = "zksync"),
+                    cmd.yagna()?.payment_status(&address, nn, &ZKSYNC_DRIVER),
+                ));
+            }
+            let label = if nn == &NetworkName::Mainnet {
+                "on-chain".to_string()
+            } else {
+                nn.to_string().to_lowercase()
+            };
+            f.push(progress.step(
+                tr!("progress-payment-status", network = label),
+                cmd.yagna()?.payment_status(&address, nn, &ERC20_DRIVER),
+            ));
+            l.push(label);
+        }
+        (f, l)
+    };
+    let fr = future::join_all(futures).await;
+    let mut n = 0;
+    for r in fr {
+        result.insert(
+            labels[n].clone(),
+            r.unwrap_or_else(|e| {
+                tracing::warn!("yagna payment status for {} failed: {}", labels[n], e);
+                StatusResult::default()
+            }),
+        );
+        n += 1;
+    }
+    Ok(result)
+}
+
+fn get_network_group(network: &NetworkName) -> NetworkGroup {
+    if NETWORK_GROUP_MAP[&NetworkGroup::Mainnet].contains(network) {
+        NetworkGroup::Mainnet
+    } else {
+        NetworkGroup::Testnet
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct Payments {
+    pub network: NetworkName,
+    pub id: Id,
+    pub invoice_status: InvoiceStats,
+    pub statuses: BTreeMap<String, StatusResult>,
+    #[serde(
+        serialize_with = "serialize_result",
+        deserialize_with = "deserialize_result"
+    )]
+    pub glm_price: Result<Option<f64>>,
+    /// GLM price in each of the `--currency` currencies, empty when it could not be fetched
+    #[serde(default)]
+    pub glm_prices: Vec<FiatPrice>,
+    /// Left out when yagna could not list the debit notes
+    #[serde(default)]
+    pub debit_notes: Option<DebitNoteStats>,
+    /// Left out when yagna could not list the invoice events
+    #[serde(default)]
+    pub acceptance: Option<AcceptanceLatency>,
+    /// Backups of the key made with `wallet backup`, left out when unknown
+    #[serde(default)]
+    pub backup: Option<BackupState>,
+    /// Accounts yagna was initialized with, left out when they could not be listed
+    #[serde(default)]
+    pub accounts: Option<Vec<Account>>,
+}
+
+async fn collect_payments(
+    cmd: &YaCommand,
+    account: &Option<NodeId>,
+    latency_days: i64,
+    currencies: &[String],
+    progress: &Progress,
+) -> Result<Payments> {
+    let network = match progress
+        .step(tr!("progress-payment-network"), get_payment_network(cmd))
+        .await
+    {
+        Ok((_offers_cnt, network)) => network,
+        // without offers to read it from (eg. in replays) the configured network group tells
+        Err(e) => {
+            tracing::debug!("payment network of the offers unknown: {:?}", e);
+            drivers::networks().into_iter().next().ok_or(e)?
+        }
+    };
+    let currencies = currencies
+        .iter()
+        .map(|currency| currency.trim().to_lowercase())
+        .collect::<Vec<_>>();
+    // the price in CURRENCY is always needed for the profit and gas figures
+    let mut wanted = currencies.clone();
+    if !wanted.iter().any(|currency| currency == CURRENCY) {
+        wanted.push(CURRENCY.to_string());
+    }
+    let (id, invoice_status, statuses, prices) = future::try_join4(
+        progress.step(tr!("progress-identity"), cmd.yagna()?.default_id()),
+        progress.step(tr!("progress-invoices"), cmd.yagna()?.invoice_status()),
+        payment_status(cmd, &network, account, progress),
+        progress.step(tr!("progress-glm-price"), rates::glm_prices(wanted).map(Ok)),
+    )
+    .await?;
+    let glm_prices = prices
+        .as_ref()
+        .map(|prices| {
+            prices
+                .iter()
+                .filter(|price| currencies.contains(&price.currency))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+    let glm_price = prices.map(|prices| {
+        prices
+            .into_iter()
+            .find(|price| price.currency == CURRENCY)
+            .and_then(|price| price.price)
+    });
+    let (debit_notes, acceptance) = future::join(
+        progress.step(
+            tr!("progress-debit-notes"),
+            debit_note_stats(cmd, &id.node_id),
+        ),
+        progress.step(
+            tr!("progress-acceptance"),
+            acceptance_latency(cmd, &id.node_id, latency_days),
+        ),
+    )
+    .await;
+    let debit_notes = debit_notes
+        .map_err(|e| tracing::debug!("debit note statistics failed: {:?}", e))
+        .ok();
+    let acceptance = acceptance
+        .map_err(|e| tracing::debug!("invoice acceptance latency failed: {:?}", e))
+        .ok();
+    let accounts = cmd
+        .yagna()?
+        .payment_accounts()
+        .await
+        .map_err(|e| tracing::debug!("yagna payment accounts failed: {:?}", e))
+        .ok();
+    let backup = wallet::backup_state(&id.node_id)
+        .map_err(|e| tracing::debug!("wallet backup history unavailable: {:?}", e))
+        .ok();
+    Ok(Payments {
+        network,
+        id,
+        invoice_status,
+        statuses,
+        glm_price,
+        glm_prices,
+        debit_notes,
+        acceptance,
+        backup,
+        accounts,
+    })
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct P2pStatus {
+    pub net: NetStatus,
+    pub peers: usize,
+}
+
+async fn collect_p2p(cmd: &YaCommand, progress: &Progress) -> Result<P2pStatus> {
+    let (net, sessions) = future::try_join(
+        progress.step(tr!("progress-net-status"), cmd.yagna()?.net_status()),
+        progress.step(tr!("progress-net-sessions"), cmd.yagna()?.net_sessions()),
+    )
+    .await?;
+    Ok(P2pStatus {
+        net,
+        peers: sessions.values.len(),
+    })
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct Ranking {
+    pub node: Option<OnlineNode>,
+    pub network: Vec<OnlineNode>,
+}
+
+/// How the node is seen from the outside, by the stats API.
+async fn collect_observation(cmd: &YaCommand, progress: &Progress) -> Result<Option<NodeInfo>> {
+    let id = progress
+        .step(tr!("progress-identity"), cmd.yagna()?.default_id())
+        .await?;
+    progress
+        .step(tr!("progress-observation"), stats::node(&id.node_id))
+        .await
+}
+
+async fn collect_ranking(cmd: &YaCommand, progress: &Progress) -> Result<Ranking> {
+    let (id, mut network) = future::try_join(
+        progress.step(tr!("progress-identity"), cmd.yagna()?.default_id()),
+        progress.step(tr!("progress-ranking"), stats::online_nodes()),
+    )
+    .await?;
+    let node = network
+        .iter()
+        .position(|node| node.node_id.eq_ignore_ascii_case(&id.node_id))
+        .map(|index| network.swap_remove(index));
+    Ok(Ranking { node, network })
+}
+
+/// Shortest `--watch` interval, a collection runs a dozen yagna invocations.
+const MIN_WATCH_SECS: u64 = 5;
+
+/// Values of the last snapshot taken while yagna was running shown on the offline
+/// screen: JSON pointer and label id.
+const LAST_KNOWN: &[(&str, &str)] = &[
+    ("/version/version", "changes-version"),
+    ("/config/node_name", "changes-node-name"),
+    ("/config/subnet", "changes-subnet"),
+    ("/activity/total/Terminated", "changes-tasks"),
+    ("/ranking/node/earnings_total", "changes-earnings"),
+];
+
+/// Number of distinct problems listed and the width their messages are cut to.
+const TOP_PROBLEMS: usize = 5;
+const PROBLEM_WIDTH: usize = 80;
+
+/// Exit code used when some of the status sections could not be collected.
+pub const EXIT_PARTIAL_RESULTS: i32 = 2;
+/// Exit code used when yagna is not running.
+pub const EXIT_NOT_RUNNING: i32 = 3;
+/// Exit code used by `--check` runs when invoices failed or payments could not be checked.
+pub const EXIT_PAYMENTS_FAILING: i32 = 4;
+/// Exit code used by `--check` runs when the provider had no task for too long.
+pub const EXIT_IDLE: i32 = 5;
+
+/// Overall condition of the node, the worst one found wins.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Health {
+    Healthy,
+    NotRunning,
+    PaymentsFailing,
+    Idle,
+}
+
+impl Health {
+    pub fn exit_code(self) -> Option<i32> {
+        match self {
+            Health::Healthy => None,
+            Health::NotRunning => Some(EXIT_NOT_RUNNING),
+            Health::PaymentsFailing => Some(EXIT_PAYMENTS_FAILING),
+            Health::Idle => Some(EXIT_IDLE),
+        }
+    }
+
+    pub fn describe(self) -> Option<String> {
+        match self {
+            Health::Healthy => None,
+            Health::NotRunning => Some(tr!("alert-not-running")),
+            Health::PaymentsFailing => Some(tr!("alert-payments-failing")),
+            Health::Idle => Some(tr!("alert-idle")),
+        }
+    }
+}
+
+/// [`StatusSnapshot::health`] from the figures it depends on. `last_activity` is `None`
+/// when the activity wasn't collected and `Some(None)` when the node never ran a task, the
+/// idle time then counts from `started_at`, unknown meaning the node isn't taken for idle.
+fn node_health(
+    is_running: bool,
+    payments_failing: bool,
+    last_activity: Option<Option<DateTime<Utc>>>,
+    started_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    max_idle: chrono::Duration,
+) -> Health {
+    if !is_running {
+        return Health::NotRunning;
+    }
+    if payments_failing {
+        return Health::PaymentsFailing;
+    }
+    let idle_since = match last_activity {
+        Some(last_activity) => last_activity.or(started_at),
+        None => None,
+    };
+    match idle_since {
+        Some(since) if now - since > max_idle => Health::Idle,
+        _ => Health::Healthy,
+    }
+}
+
+pub async fn run(options: &CommandOptions, args: StatusCommand) -> Result</*exit code*/ i32> {
+    let cmd = YaCommand::new(options)?;
+    let ascii = args.ascii || env::var("TERM").map(|t| t == "dumb").unwrap_or(false);
+    let progress = if args.quiet {
+        Progress::hidden()
+    } else {
+        Progress::new(ascii)
+    };
+
+    let mut previous = diff::load_last().unwrap_or_else(|e| {
+        tracing::debug!("failed to load the previous snapshot: {:?}", e);
+        None
+    });
+    let mut last_online = diff::load_last_online().unwrap_or_else(|e| {
+        tracing::debug!("failed to load the last online snapshot: {:?}", e);
+        None
+    });
+    let watch = args.watch.map(|interval| {
+        if interval < MIN_WATCH_SECS {
+            tracing::debug!(
+                "--watch {} is too short, collecting every {} s",
+                interval,
+                MIN_WATCH_SECS
+            );
+        }
+        Duration::from_secs(interval.max(MIN_WATCH_SECS))
+    });
+    // collections start on a fixed schedule, whatever rendering or the hook took
+    let schedule = tokio::time::Instant::now();
+    // last output with the time of its snapshot, shown again while a collection overruns
+    let mut shown: Option<(DateTime<Utc>, String)> = None;
+    loop {
+        let snapshot = match (watch, &shown) {
+            (Some(interval), Some((collected_at, output))) => {
+                let collection = collect(&cmd, &args.query, &progress);
+                futures::pin_mut!(collection);
+                loop {
+                    let timer = Box::pin(tokio::time::delay_for(interval));
+                    match future::select(collection.as_mut(), timer).await {
+                        future::Either::Left((snapshot, _)) => break snapshot?,
+                        future::Either::Right(_) => {
+                            if let Some(stale) = stale_output(&args, *collected_at, output) {
+                                show(&args, &stale)?;
+                            }
+                        }
+                    }
+                }
+            }
+            _ => collect(&cmd, &args.query, &progress).await?,
+        };
+        let time_display = TimeDisplay {
+            absolute: args.absolute,
+            zone: args.tz.unwrap_or_else(DisplayZone::system),
+            now: snapshot.collected_at,
+        };
+        let current = serde_json::to_value(&snapshot)?;
+        // a replayed snapshot would become what the next real run is compared against
+        if !cmd.is_replay() {
+            if let Err(e) = diff::store_last(&current) {
+                tracing::debug!("failed to store the snapshot: {:?}", e);
+            }
+        }
+        if snapshot.is_running {
+            last_online = Some(current.clone());
+        }
+        let changes = previous
+            .as_ref()
+            .map(|previous| diff::changes(previous, &current));
+        let max_idle = minutes(args.max_idle).unwrap_or_else(chrono::Duration::max_value);
+        let health = snapshot.health(max_idle);
+        let output = if args.quiet {
+            let mut alerts = snapshot.alerts();
+            // not running is already among the alerts
+            if let Some(alert) = health
+                .describe()
+                .filter(|_| args.check && snapshot.is_running)
+            {
+                alerts.push(alert);
+            }
+            if let Some(Ok(profit)) = snapshot.profit.as_ref().filter(|_| args.break_even_alert) {
+                if profit.below_break_even() {
+                    alerts.push(tr!(
+                        "alert-below-break-even",
+                        price = display::fiat_f64(profit.glm_price.unwrap_or_default()),
+                        break_even = display::fiat_f64(profit.break_even_price.unwrap_or_default()),
+                        currency = CURRENCY.to_uppercase()
+                    ));
+                }
+            }
+            for alert in alerts {
+                eprintln!("{}", alert);
+            }
+            String::new()
+        } else if args.append {
+            format!("{}\n", serde_json::to_string(&snapshot)?)
+        } else if args.json {
+            format!("{}\n", serde_json::to_string_pretty(&snapshot)?)
+        } else {
+            let mut rendered = if snapshot.is_running {
+                render(&snapshot, &args.query, &time_display, ascii)
+            } else {
+                let installed = cmd.resolve("yagna").is_some();
+                render_offline(
+                    &snapshot,
+                    last_online.as_ref(),
+                    installed,
+                    &args.query,
+                    &time_display,
+                    ascii,
+                )
+            };
+            if args.diff {
+                let since = previous.as_ref().and_then(diff::collected_at);
+                let table = match &changes {
+                    Some(changes) => changes_table(changes, since, ascii),
+                    None => {
+                        let mut table = section_table(tr!("changes-title"));
+                        table.add_empty_row();
+                        table.add_row(row![Style::new().dimmed().paint(tr!("changes-first-run"))]);
+                        table
+                    }
+                };
+                rendered.push('\n');
+                rendered.push_str(&table.to_string());
+            }
+            if args.output.is_some() {
+                strip_styles(&rendered)
+            } else {
+                rendered
+            }
+        };
+        show(&args, &output)?;
+        shown = Some((snapshot.collected_at, output));
+        if let (Some(hook), Some(changes)) = (&args.on_change, &changes) {
+            if !changes.is_empty() {
+                if let Err(e) = diff::run_hook(hook, snapshot.collected_at, changes).await {
+                    tracing::warn!("--on-change command failed: {:#}", e);
+                }
+            }
+        }
+
+        let interval = match watch {
+            Some(interval) => interval,
+            None => {
+                return Ok(match health.exit_code() {
+                    Some(code) if args.check => code,
+                    _ if !snapshot.is_running => EXIT_NOT_RUNNING,
+                    _ if snapshot.is_partial() => EXIT_PARTIAL_RESULTS,
+                    _ => 0,
+                })
+            }
+        };
+        previous = Some(current);
+        // the next slot of the schedule, the ones a long collection ran over are skipped
+        let slots = schedule.elapsed().as_millis() / interval.as_millis() + 1;
+        tokio::time::delay_until(schedule + interval * slots as u32).await;
+    }
+}
+
+/// Writes a rendered status where `args` send it.
+fn show(args: &StatusCommand, output: &str) -> Result<()> {
+    match &args.output {
+        Some(path) if args.append => append_line(path, output)?,
+        Some(path) => write_atomic(path, output.as_bytes())?,
+        None if args.watch.is_some()
+            && !args.json
+            && !args.quiet
+            && atty::is(atty::Stream::Stdout) =>
+        {
+            // redraw in place
+            print!("\x1b[2J\x1b[H{}", output)
+        }
+        None => print!("{}", output),
+    }
+    Ok(())
+}
+
+/// `output` of the snapshot collected at `collected_at` headed by how old it is, shown while
+/// the next collection takes longer than the watch interval. `None` when there is nothing
+/// to show again: JSON carries `collected_at` itself, `--quiet` prints only alerts.
+fn stale_output(args: &StatusCommand, collected_at: DateTime<Utc>, output: &str) -> Option<String> {
+    if args.json || args.quiet {
+        return None;
+    }
+    let notice = tr!(
+        "status-stale",
+        age = format_duration(Utc::now() - collected_at)
+    );
+    Some(if args.output.is_some() {
+        format!("{}\n{}", notice, output)
+    } else {
+        format!(
+            "{}\n{}",
+            Style::new().fg(Colour::Fixed(220)).paint(notice),
+            output
+        )
+    })
+}
+
+/// Collects the status sections selected by `query`, the same way `yagna-usd status` does.
+///
+/// Records uptime and earnings samples as a side effect, unless replaying recorded outputs.
+pub async fn collect_status(
+    options: &CommandOptions,
+    query: &StatusQuery,
+) -> Result<StatusSnapshot> {
+    let cmd = YaCommand::new(options)?;
+    collect(&cmd, query, &Progress::hidden()).await
+}
+
+async fn collect(
+    cmd: &YaCommand,
+    args: &StatusQuery,
+    progress: &Progress,
+) -> Result<StatusSnapshot> {
+    let collected_at = Utc::now();
+    let kvm_status = platform::kvm_status();
+    let environment = platform::environment();
+    let terms = args.wants(Section::Node).then(terms::status).flatten();
+
+    // the query may come from a library user or a saved JSON, bypassing the flag's bounds
+    let problems_since = minutes(args.problems_window)
+        .and_then(|window| collected_at.checked_sub_signed(window))
+        .ok_or_else(|| {
+            anyhow!(tr!(
+                "problems-window-too-large",
+                minutes = args.problems_window
+            ))
+        });
+    let (
+        (config, is_running, version, gpu, latest_release),
+        (processes, services, hardware, rejections, problems),
+    ) = progress
+        .drive(future::join(
+            future::join5(
+                optional(
+                    args.wants(Section::Node) || args.wants(Section::Payments),
+                    progress.step(tr!("progress-provider-config"), provider_config(cmd)),
+                ),
+                cmd.is_yagna_running(),
+                optional(
+                    args.wants(Section::Versions),
+                    progress.step(tr!("progress-yagna-version"), cmd.yagna()?.version_raw()),
+                ),
+                optional(
+                    args.wants(Section::Gpu),
+                    progress.step(tr!("progress-gpu"), gpu::collect(cmd)),
+                ),
+                optional(
+                    args.wants(Section::Versions) && !args.no_update_check,
+                    progress.step(tr!("progress-latest-release"), releases::latest_version()),
+                ),
+            ),
+            future::join5(
+                optional(
+                    args.wants(Section::Processes),
+                    progress.step(tr!("progress-processes"), processes::collect()),
+                ),
+                optional(
+                    args.wants(Section::Service),
+                    progress.step(tr!("progress-services"), platform::service_states()),
+                ),
+                optional(
+                    args.wants(Section::Hardware),
+                    progress.step(tr!("progress-hardware"), hardware::collect_with_offer(cmd)),
+                ),
+                optional(
+                    args.wants(Section::Rejections),
+                    progress.step(tr!("progress-rejections"), market::rejection_reasons()),
+                ),
+                optional(
+                    args.wants(Section::Problems),
+                    progress.step(tr!("progress-problems"), async move {
+                        logs::problems(problems_since?).await
+                    }),
+                ),
+            ),
+        ))
+        .await;
+    let is_running = is_running?;
+    // replayed outputs would leave made up samples in the history of this machine
+    let record_samples = !cmd.is_replay();
+    if record_samples {
+        if let Err(e) = uptime::record(is_running) {
+            tracing::debug!("failed to record uptime sample: {:?}", e);
+        }
+    }
+    let account = config
+        .as_ref()
+        .and_then(|config| config.as_ref().ok())
+        .and_then(|config| config.provider.account);
+
+    let (
+        pending_version,
+        observed,
+        payments,
+        activity,
+        workload,
+        p2p,
+        ranking,
+        market,
+        requestor,
+        failed_payments,
+        drivers,
+    ) = if is_running {
+        let (
+            (version_info, observed, payments, activity, failed_payments),
+            (workload, p2p, ranking, market, requestor),
+            drivers,
+        ) = progress
+            .drive(future::join3(
+                future::join5(
+                    optional(
+                        args.wants(Section::Versions),
+                        progress.step(tr!("progress-yagna-version"), cmd.yagna()?.version()),
+                    ),
+                    optional(
+                        args.wants(Section::Node),
+                        collect_observation(cmd, progress),
+                    ),
+                    optional(
+                        args.wants(Section::Payments),
+                        collect_payments(
+                            cmd,
+                            &account,
+                            args.latency_days,
+                            &args.currency,
+                            progress,
+                        ),
+                    ),
+                    optional(
+                        args.wants(Section::Activity),
+                        progress.step(tr!("progress-activity"), cmd.yagna()?.activity_status()),
+                    ),
+                    optional(
+                        args.wants(Section::PaymentFailures),
+                        progress.step(tr!("progress-payment-failures"), failed_payments(cmd)),
+                    ),
+                ),
+                future::join5(
+                    optional(
+                        args.wants(Section::Activity),
+                        progress.step(tr!("progress-workload"), workload::collect(cmd)),
+                    ),
+                    optional(args.wants(Section::P2p), collect_p2p(cmd, progress)),
+                    optional(args.wants(Section::Ranking), collect_ranking(cmd, progress)),
+                    optional(
+                        args.wants(Section::Market),
+                        progress.step(tr!("progress-market"), market::collect(cmd)),
+                    ),
+                    optional(
+                        args.wants(Section::Requestor),
+                        progress.step(tr!("progress-requestor"), requestor::collect(cmd)),
+                    ),
+                ),
+                optional(
+                    args.wants(Section::Drivers),
+                    progress.step(tr!("progress-drivers"), drivers::collect(cmd, &account)),
+                ),
+            ))
+            .await;
+        let pending_version = version_info
+            .and_then(|info| {
+                info.map_err(|e| tracing::debug!("yagna version show failed: {:?}", e))
+                    .ok()
+            })
+            .and_then(|info| info.pending);
+        (
+            pending_version,
+            observed,
+            payments,
+            activity,
+            workload,
+            p2p,
+            ranking,
+            market,
+            requestor,
+            failed_payments,
+            drivers,
+        )
+    } else {
+        (
+            None, None, None, None, None, None, None, None, None, None, None,
+        )
+    };
+
+    // yagna announces releases it learned about itself, GitHub is asked for the rest
+    let installed = version
+        .as_ref()
+        .and_then(|version| version.as_ref().ok())
+        .map(|version| version.version.as_str());
+    let update = pending_version.map(|pending| pending.version).or_else(|| {
+        let latest = latest_release?
+            .map_err(|e| tracing::debug!("latest release check failed: {:?}", e))
+            .ok()?;
+        let installed = installed?;
+        Some(latest).filter(|latest| releases::is_newer(latest, installed))
+    });
+    let uptime = args.wants(Section::Uptime).then(uptime::report);
+
+    let payments_ok = payments
+        .as_ref()
+        .and_then(|payments| payments.as_ref().ok());
+    if let Some(earned) = payments_ok.filter(|_| record_samples).map(|payments| {
+        payments
+            .statuses
+            .values()
+            .map(|status| status.incoming.accepted.total_amount.clone())
+            .sum::<BigDecimal>()
+    }) {
+        if let Err(e) = profit::record(earned.to_f64().unwrap_or_default()) {
+            tracing::debug!("failed to record earnings sample: {:?}", e);
+        }
+    }
+    let glm_price =
+        payments_ok.and_then(|payments| payments.glm_price.as_ref().ok().copied().flatten());
+    let profit = PowerCost::from_env()
+        .filter(|_| args.wants(Section::Profit))
+        .map(|cost| profit::report(cost, glm_price));
+
+    Ok(StatusSnapshot {
+        collected_at,
+        is_running,
+        config,
+        version,
+        update,
+        observed,
+        kvm_status,
+        environment,
+        terms,
+        payments,
+        profit,
+        requestor,
+        failed_payments,
+        drivers,
+        activity,
+        workload,
+        market,
+        problems,
+        rejections,
+        ranking,
+        uptime,
+        services,
+        gpu,
+        hardware,
+        processes,
+        p2p,
+    })
+}
+
+fn append_line(path: &std::path::Path, line: &str) -> Result<()> {
+    use std::io::Write;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| anyhow!("opening {}: {}", path.display(), e))?;
+    // a single write keeps lines of concurrent writers from interleaving
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// Status tables with the notes printed below them.
+pub(crate) fn render(
+    snapshot: &StatusSnapshot,
+    args: &StatusQuery,
+    time_display: &TimeDisplay,
+    ascii: bool,
+) -> String {
+    let mut sections = vec![];
+    if args.wants(Section::Node) || args.wants(Section::Versions) {
+        let node = if args.wants(Section::Node) {
+            snapshot.config.as_ref()
+        } else {
+            None
+        };
+        sections.push(status_table(snapshot, node));
+    }
+    if let Some(payments) = &snapshot.payments {
+        sections.push(match payments {
+            Ok(payments) => payments_table(
+                &snapshot.account(),
+                snapshot.account_warning().as_deref(),
+                payments,
+                ascii,
+            ),
+            Err(e) => unavailable_table(tr!("wallet-title"), e),
+        });
+    }
+    if let Some(profit) = &snapshot.profit {
+        sections.push(match profit {
+            Ok(profit) => profit_table(profit),
+            Err(e) => unavailable_table(tr!("profit-title"), e),
+        });
+    }
+    if let Some(requestor) = &snapshot.requestor {
+        sections.push(match requestor {
+            Ok(requestor) => requestor_table(requestor, snapshot.collected_at),
+            Err(e) => unavailable_table(tr!("requestor-title"), e),
+        });
+    }
+    if let Some(failed) = &snapshot.failed_payments {
+        match failed {
+            Ok(failed) if failed.is_empty() => (),
+            Ok(failed) => sections.push(payment_failures_table(failed, ascii)),
+            Err(e) => sections.push(unavailable_table(
+                tr!("payment-failures-title", hours = FAILURES_WINDOW_HOURS),
+                e,
+            )),
+        }
+    }
+    if let Some(drivers) = &snapshot.drivers {
+        sections.push(match drivers {
+            Ok(drivers) => drivers_table(drivers, snapshot.collected_at),
+            Err(e) => unavailable_table(tr!("drivers-title"), e),
+        });
+    }
+    if let Some(activity) = &snapshot.activity {
+        sections.push(match activity {
+            Ok(activity) => activity_table(
+                activity,
+                snapshot.workload.as_ref(),
+                snapshot.max_agreements(),
+                time_display,
+            ),
+            Err(e) => unavailable_table(tr!("tasks-title"), e),
+        });
+    }
+    if let Some(market) = &snapshot.market {
+        sections.push(match market {
+            Ok(market) => market_table(market),
+            Err(e) => unavailable_table(tr!("market-title"), e),
+        });
+    }
+    if let Some(problems) = &snapshot.problems {
+        sections.push(match problems {
+            Ok(problems) => problems_table(problems, args.problems_window, ascii),
+            Err(e) => unavailable_table(tr!("problems-title", minutes = args.problems_window), e),
+        });
+    }
+    if let Some(rejections) = &snapshot.rejections {
+        sections.push(match rejections {
+            Ok(reasons) => rejections_table(reasons),
+            Err(e) => unavailable_table(tr!("rejections-title"), e),
+        });
+    }
+    if let Some(ranking) = &snapshot.ranking {
+        sections.push(match ranking {
+            Ok(ranking) => ranking_table(ranking),
+            Err(e) => unavailable_table(tr!("ranking-title"), e),
+        });
+    }
+    if let Some(uptime) = &snapshot.uptime {
+        sections.push(match uptime {
+            Ok(report) => uptime_table(report),
+            Err(e) => unavailable_table(tr!("uptime-title"), e),
+        });
+    }
+    if let Some(services) = snapshot
+        .services
+        .as_ref()
+        .filter(|services| !services.is_empty())
+    {
+        sections.push(services_table(services, time_display));
+    }
+    if let Some(gpu) = &snapshot.gpu {
+        match gpu {
+            Ok(gpu) if gpu.is_empty() => (),
+            Ok(gpu) => sections.push(gpu_table(gpu)),
+            Err(e) => sections.push(unavailable_table(tr!("gpu-title"), e)),
+        }
+    }
+    if let Some(hardware) = &snapshot.hardware {
+        sections.push(hardware_table(hardware, time_display));
+    }
+    if let Some(processes) = &snapshot.processes {
+        sections.push(processes_table(processes));
+    }
+    if let Some(p2p) = &snapshot.p2p {
+        sections.push(match p2p {
+            Ok(p2p) => p2p_table(p2p),
+            Err(e) => unavailable_table(tr!("p2p-title"), e),
+        });
+    }
+
+    let mut output = layout(&sections, ascii);
+    if let Some(msg) = snapshot
+        .kvm_status
+        .problem()
+        .filter(|_| args.wants(Section::Node))
+    {
+        output.push_str(&format!("\n {}\n", tr!("status-vm-problem", problem = msg)));
+    }
+    if args.wants(Section::Node) {
+        for limitation in snapshot.environment.limitations(&snapshot.kvm_status) {
+            output.push_str(&format!(
+                " {}\n",
+                tr!("status-env-limitation", limitation = limitation)
+            ));
+        }
+    }
+    if let Some(footnote) = snapshot.rates_footnote(time_display) {
+        output.push_str(&format!("\n {}\n", footnote));
+    }
+    if ascii {
+        strip_styles(&output)
+    } else {
+        output
+    }
+}
+
+/// Sections side by side on wide terminals, one below the other otherwise.
+fn layout(sections: &[Table], ascii: bool) -> String {
+    let size = crossterm::terminal::size().ok().unwrap_or_else(|| (80, 50));
+    let mut table = Table::new();
+    if ascii {
+        table.set_format(*format::consts::FORMAT_DEFAULT);
+    } else {
+        table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    }
+
+    if size.0 > 120 {
+        table.add_row(Row::new(sections.iter().map(section_cell).collect()));
+    } else {
+        for section in sections {
+            table.add_row(Row::new(vec![section_cell(section)]));
+        }
+    }
+    table.to_string()
+}
+
+/// Screen shown instead of the status when yagna is not running: why, how to start it
+/// and what was known while it ran.
+fn render_offline(
+    snapshot: &StatusSnapshot,
+    last_online: Option<&serde_json::Value>,
+    installed: bool,
+    args: &StatusQuery,
+    time_display: &TimeDisplay,
+    ascii: bool,
+) -> String {
+    let mut sections = vec![offline_table(snapshot, last_online, installed)];
+    if let Some(Ok(problems)) = &snapshot.problems {
+        sections.push(problems_table(problems, args.problems_window, ascii));
+    }
+    if let Some(services) = snapshot
+        .services
+        .as_ref()
+        .filter(|services| !services.is_empty())
+    {
+        sections.push(services_table(services, time_display));
+    }
+    if let Some(processes) = &snapshot.processes {
+        sections.push(processes_table(processes));
+    }
+    let output = layout(&sections, ascii);
+    if ascii {
+        strip_styles(&output)
+    } else {
+        output
+    }
+}
+
+/// Yagna services registered in the service manager.
+fn yagna_services(snapshot: &StatusSnapshot) -> impl Iterator<Item = &ServiceState> + '_ {
+    snapshot
+        .services
+        .iter()
+        .flatten()
+        .filter(|service| !service.name.contains("provider"))
+}
+
+/// Most likely reason yagna doesn't answer, from what was collected without it.
+fn offline_cause(snapshot: &StatusSnapshot, installed: bool) -> String {
+    if !installed {
+        return tr!("offline-cause-not-installed");
+    }
+    if matches!(&snapshot.terms, Some(terms) if terms.waiting) {
+        return tr!("terms-waiting");
+    }
+    let yagna_process = snapshot
+        .processes
+        .iter()
+        .flatten()
+        .any(|process| process.name == "yagna");
+    if yagna_process {
+        return tr!("offline-cause-api", url = yagna_api_url());
+    }
+    if let Some(service) = yagna_services(snapshot).find(|service| !service.is_running()) {
+        return match service.recent_errors.last() {
+            Some(error) => tr!(
+                "offline-cause-service-failed",
+                name = service.name.clone(),
+                error = error.clone()
+            ),
+            None => tr!("offline-cause-service-stopped", name = service.name.clone()),
+        };
+    }
+    let error = snapshot
+        .problems
+        .iter()
+        .flatten()
+        .flatten()
+        .find(|problem| matches!(problem.daemon, logs::Daemon::Yagna) && problem.level == "ERROR");
+    match error {
+        Some(problem) => tr!("offline-cause-error", message = problem.message.clone()),
+        None => tr!("offline-cause-stopped"),
+    }
+}
+
+fn offline_table(
+    snapshot: &StatusSnapshot,
+    last_online: Option<&serde_json::Value>,
+    installed: bool,
+) -> Table {
+    let mut table = section_table(tr!("offline-title"));
+    table.add_empty_row();
+    table.add_row(row![
+        tr!("offline-cause"),
+        Style::new()
+            .fg(Colour::Red)
+            .paint(offline_cause(snapshot, installed))
+    ]);
+    let start = match yagna_services(snapshot).next() {
+        Some(service) => service.start_command(),
+        None => tr!("offline-start-manual"),
+    };
+    table.add_row(row![tr!("offline-start"), start]);
+    table.add_empty_row();
+
+    let last_known = last_online.and_then(|last| Some((last, diff::collected_at(last)?)));
+    let (last, collected_at) = match last_known {
+        Some(last_known) => last_known,
+        None => {
+            table.add_row(row![H2->Style::new().dimmed().paint(tr!("offline-no-history"))]);
+            return table;
+        }
+    };
+    table.add_row(row![H2->Style::new().fg(Colour::Fixed(220)).paint(tr!(
+        "offline-last-known",
+        ago = format_relative(collected_at, snapshot.collected_at)
+    ))]);
+    for (pointer, label) in LAST_KNOWN {
+        let value = match last.pointer(pointer) {
+            None | Some(serde_json::Value::Null) => continue,
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(value) => value.to_string(),
+        };
+        table.add_row(row![
+            Style::new().dimmed().paint(tr!(label)),
+            Style::new().dimmed().paint(value)
+        ]);
+    }
+
+    table
+}
+
+/// Awaits `fut` only when the corresponding section was requested.
+async fn optional<T>(enabled: bool, fut: impl Future<Output = T>) -> Option<T> {
+    if enabled {
+        Some(fut.await)
+    } else {
+        None
+    }
+}
+
+fn is_failed<T>(result: &Option<Result<T>>) -> bool {
+    matches!(result, Some(Err(_)))
+}
+
+fn failure<T>(result: &Option<Result<T>>) -> Option<&anyhow::Error> {
+    result.as_ref().and_then(|result| result.as_ref().err())
+}
+
+fn section_cell(section: &Table) -> Cell {
+    Cell::new(&section.to_string())
+}
+
+/// Short, single line description of a collector failure.
+fn unavailable_reason(err: &anyhow::Error) -> String {
+    let reason = format!("{:#}", err);
+    let reason = reason.lines().next().unwrap_or_default().to_string();
+    tr!("section-unavailable", reason = reason)
+}
+
+fn unavailable_table(title: String, err: &anyhow::Error) -> Table {
+    tracing::debug!("{} unavailable: {:?}", title, err);
+    let mut table = section_table(title);
+    table.add_empty_row();
+    table.add_row(row![Style::new()
+        .fg(Colour::Red)
+        .paint(unavailable_reason(err))]);
+    table
+}
+
+pub(crate) fn section_table(title: String) -> Table {
+    let mut table = Table::new();
+    let format = format::FormatBuilder::new().padding(1, 1).build();
+    table.set_format(format);
+    table.add_row(row![Style::new()
+        .fg(Colour::Yellow)
+        .underline()
+        .paint(title)]);
+    table
+}
+
+fn status_table(snapshot: &StatusSnapshot, node: Option<&Result<NodeConfig>>) -> Table {
+    let mut table = section_table(tr!("status-title"));
+    table.add_empty_row();
+    if snapshot.is_running {
+        table.add_row(row![
+            tr!("status-service"),
+            Style::new().fg(Colour::Green).paint(tr!("status-running"))
+        ]);
+        // only looked at for the node section
+        match snapshot.observed.as_ref().filter(|_| node.is_some()) {
+            Some(Ok(Some(info))) if info.online => {
+                let seen = match info.updated_at {
+                    Some(ts) => tr!(
+                        "status-seen-online-at",
+                        ago = format_relative(ts, snapshot.collected_at)
+                    ),
+                    None => tr!("status-seen-online"),
+                };
+                table.add_row(row![
+                    tr!("status-network-view"),
+                    Style::new().fg(Colour::Green).paint(seen)
+                ]);
+            }
+            Some(Ok(_)) => {
+                table.add_row(row![
+                    tr!("status-network-view"),
+                    Style::new()
+                        .fg(Colour::Red)
+                        .paint(tr!("status-not-seen-online"))
+                ]);
+            }
+            Some(Err(e)) => {
+                table.add_row(row![tr!("status-network-view"), unavailable_reason(e)]);
+            }
+            None => (),
+        }
+    } else {
+        table.add_row(row![
+            tr!("status-service"),
+            Style::new()
+                .fg(Colour::Red)
+                .paint(tr!("status-not-running"))
+        ]);
+    }
+    if let Some(terms) = &snapshot.terms {
+        let accepted = match terms.accepted.last() {
+            _ if terms.waiting => Style::new().fg(Colour::Red).paint(tr!("terms-waiting")),
+            Some(version) => Style::new()
+                .fg(Colour::Green)
+                .paint(tr!("terms-accepted", version = version.clone())),
+            None => Style::new()
+                .fg(Colour::Red)
+                .paint(tr!("terms-not-accepted")),
+        };
+        table.add_row(row![tr!("terms-title"), accepted]);
+    }
+    if let Some(update) = &snapshot.update {
+        let ver = tr!("status-new-version-released", version = update);
+        table.add_row(row![
+            tr!("status-new-version"),
+            Style::new().fg(Colour::Fixed(220)).paint(ver)
+        ]);
+    }
+    match &snapshot.version {
+        Some(Ok(version)) => {
+            table.add_row(row![tr!("status-version"), version.version]);
+            table.add_row(row![tr!("status-commit"), version.sha]);
+            table.add_row(row![tr!("status-date"), version.date]);
+            table.add_row(row![tr!("status-build"), version.build]);
+        }
+        Some(Err(e)) => {
+            table.add_row(row![tr!("status-version"), unavailable_reason(e)]);
+        }
+        None => (),
+    }
+
+    let config = match node {
+        Some(config) => config,
+        None => return table,
+    };
+    table.add_empty_row();
+    match config {
+        Ok(config) => {
+            table.add_row(row![
+                tr!("status-node-name"),
+                config.provider.node_name.clone().unwrap_or_default()
+            ]);
+            let subnet = config.provider.subnet.clone().unwrap_or_default();
+            if config.provider.is_public_subnet() {
+                table.add_row(row![tr!("status-subnet"), subnet]);
+            } else {
+                table.add_row(row![
+                    tr!("status-subnet"),
+                    Style::new().fg(Colour::Fixed(220)).bold().paint(subnet)
+                ]);
+            }
+            if let Some(warning) = snapshot.subnet_warning() {
+                table.add_row(row!["", Style::new().fg(Colour::Fixed(220)).paint(warning)]);
+            }
+            let account = match &config.account_as_configured {
+                Some(account) => address::checksummed(account),
+                None => tr!("status-account-identity"),
+            };
+            table.add_row(row![tr!("status-account"), account]);
+            if let Some(presets) = &config.active_presets {
+                let presets = if presets.is_empty() {
+                    Style::new().fg(Colour::Red).paint(tr!("status-no-presets"))
+                } else {
+                    Style::new().paint(presets.join(", "))
+                };
+                table.add_row(row![tr!("status-presets"), presets]);
+            }
+            if let Some(glm) = config.earning_potential {
+                let glm_text = display::glm_f64(glm);
+                let potential = match snapshot.glm_price() {
+                    Some(rate) => tr!(
+                        "status-earning-value-fiat",
+                        glm = glm_text,
+                        fiat = display::fiat_f64(glm * rate),
+                        currency = CURRENCY.to_uppercase()
+                    ),
+                    None => tr!("status-earning-value", glm = glm_text),
+                };
+                table.add_row(row![tr!("status-earning-potential"), potential]);
+            }
+            if !config.payment_networks.is_empty() {
+                table.add_row(row![
+                    tr!("status-payment-networks"),
+                    config.payment_networks.join(", ")
+                ]);
+            }
+        }
+        Err(e) => {
+            table.add_row(row![tr!("status-node-name"), unavailable_reason(e)]);
+        }
+    }
+    table.add_row(row![
+        tr!("status-environment"),
+        snapshot.environment.describe()
+    ]);
+    if snapshot.kvm_status.is_implemented() {
+        let status = match &snapshot.kvm_status {
+            KvmStatus::Valid => Style::new().fg(Colour::Green).paint(tr!("status-vm-valid")),
+            KvmStatus::Permission(_) => Style::new()
+                .fg(Colour::Red)
+                .paint(tr!("status-vm-no-access")),
+            KvmStatus::NotImplemented => Style::new().paint(""),
+            KvmStatus::InvalidEnv(_) => Style::new()
+                .fg(Colour::Red)
+                .paint(tr!("status-vm-invalid-env")),
+        };
+        table.add_row(row![tr!("status-vm"), status]);
+    }
+
+    table
+}
+
+/// `amount` GLM in each currency with a known price.
+fn fiat_values(amount: &BigDecimal, prices: &[FiatPrice]) -> Vec<(String, String)> {
+    prices
+        .iter()
+        .filter_map(|price| {
+            let rate = BigDecimal::from_f64(price.price?)?;
+            Some((
+                price.currency.to_uppercase(),
+                display::fiat(&(amount * rate)),
+            ))
+        })
+        .collect()
+}
+
+/// " ≈ 1.20 USD, 1.10 EUR" for amounts shown in GLM (" ~ " with `ascii`), empty without prices.
+fn fiat_suffix(amount: &BigDecimal, prices: &[FiatPrice], ascii: bool) -> String {
+    let values = fiat_values(amount, prices)
+        .into_iter()
+        .map(|(currency, value)| format!("{} {}", value, currency))
+        .collect::<Vec<_>>();
+    if values.is_empty() {
+        String::new()
+    } else {
+        let approx = if ascii { "~" } else { "≈" };
+        format!(" {} {}", approx, values.join(", "))
+    }
+}
+
+fn payments_table(
+    account: &Option<NodeId>,
+    warning: Option<&str>,
+    payments: &Payments,
+    ascii: bool,
+) -> Table {
+    let network_group = get_network_group(&payments.network);
+    let token = payments
+        .statuses
+        .values()
+        .next()
+        .map(|status| status.token.clone())
+        .unwrap_or_default();
+
+    let mut table = section_table(tr!("wallet-title"));
+    let account = account
+        .map(|a| a.to_string())
+        .unwrap_or_else(|| payments.id.node_id.clone());
+    table.add_row(
+        row![H2->Style::new().fg(Colour::Fixed(63)).paint(address::checksummed(&account))],
+    );
+    if let Some(warning) = warning {
+        table.add_row(row![H2->Style::new().fg(Colour::Red).bold().paint(warning)]);
+    }
+    if let Some(BackupState { last: None }) = &payments.backup {
+        table.add_row(row![H2->Style::new().fg(Colour::Red).paint(tr!("wallet-no-backup"))]);
+    }
+    table.add_empty_row();
+
+    let net_color = match network_group {
+        NetworkGroup::Mainnet => Colour::Purple,
+        NetworkGroup::Testnet => Colour::Cyan,
+    };
+
+    table.add_row(row![
+        tr!("wallet-network"),
+        Style::new().fg(net_color).paint(network_group.to_string())
+    ]);
+    let total_amount: BigDecimal = payments
+        .statuses
+        .values()
+        .cloned()
+        .map(|ps| ps.amount)
+        .sum();
+
+    // snapshots taken before `--currency` carry only the price in CURRENCY
+    let prices = match &payments.glm_price {
+        Ok(price) if payments.glm_prices.is_empty() => vec![FiatPrice {
+            currency: CURRENCY.to_string(),
+            price: *price,
+            fetched_at: None,
+            stale: false,
+        }],
+        Ok(_) => payments.glm_prices.clone(),
+        Err(_) => {
+            table.add_row(row![
+                tr!("wallet-glm-price"),
+                tr!("wallet-prices-unavailable")
+            ]);
+            vec![]
+        }
+    };
+    for price in &prices {
+        table.add_row(row![
+            tr!("wallet-glm-price"),
+            match price.price {
+                Some(glm_price) => format!(
+                    "{} {}{}",
+                    display::fiat_f64(glm_price),
+                    price.currency.to_uppercase(),
+                    price.stale_marker()
+                ),
+                None => tr!(
+                    "wallet-unsupported-currency",
+                    currency = price.currency.clone()
+                ),
+            }
+        ]);
+    }
+    for (currency, value) in fiat_values(&total_amount, &prices) {
+        table.add_row(row![
+            tr!("wallet-total-value"),
+            format!("{} {}", value, currency)
+        ]);
+    }
+    table.add_empty_row();
+
+    table.add_row(row![
+        tr!("wallet-amount-total"),
+        format!("{} {}", display::glm(&total_amount), token)
+    ]);
+    for (label, status) in &payments.statuses {
+        table.add_row(row![
+            format!("    ({})", label),
+            format!("{} {}", display::glm(&status.amount), token)
+        ]);
+    }
+    table.add_empty_row();
+    {
+        let (pending, pending_cnt) = payments.invoice_status.provider.total_pending();
+        table.add_row(row![
+            tr!("wallet-pending"),
+            format!(
+                "{} {} ({}){}",
+                display::glm(&pending),
+                token,
+                pending_cnt,
+                fiat_suffix(&pending, &prices, ascii)
+            )
+        ]);
+    }
+    let (unconfirmed, unconfirmed_cnt) = payments.invoice_status.provider.unconfirmed();
+    table.add_row(row![
+        tr!("wallet-issued"),
+        format!(
+            "{} {} ({}){}",
+            display::glm(&unconfirmed),
+            token,
+            unconfirmed_cnt,
+            fiat_suffix(&unconfirmed, &prices, ascii)
+        )
+    ]);
+    if let Some(debit_notes) = &payments.debit_notes {
+        table.add_empty_row();
+        table.add_row(row![tr!("wallet-debit-notes")]);
+        let rows = [
+            (tr!("wallet-debit-notes-issued"), &debit_notes.issued),
+            (tr!("wallet-debit-notes-accepted"), &debit_notes.accepted),
+            (tr!("wallet-debit-notes-rejected"), &debit_notes.rejected),
+        ];
+        for (label, notes) in rows {
+            table.add_row(row![
+                format!("    {}", label),
+                format!(
+                    "{} {} ({})",
+                    display::glm(&notes.amount),
+                    token,
+                    notes.count
+                )
+            ]);
+        }
+    }
+    if let Some(acceptance) = payments.acceptance.as_ref().filter(|a| a.accepted > 0) {
+        table.add_empty_row();
+        table.add_row(row![
+            tr!("wallet-acceptance", days = acceptance.days),
+            tr!(
+                "wallet-acceptance-value",
+                median = format_duration(chrono::Duration::seconds(
+                    acceptance.median.unwrap_or_default()
+                )),
+                count = acceptance.accepted
+            )
+        ]);
+        for (requestor, median) in &acceptance.slowest {
+            table.add_row(row![
+                format!("    {}", requestor),
+                format_duration(chrono::Duration::seconds(*median))
+            ]);
+        }
+    }
+
+    table
+}
+
+fn activity_table(
+    status: &ActivityStatus,
+    workload: Option<&Result<Vec<RuntimeActivity>>>,
+    max_agreements: Option<u32>,
+    time_display: &TimeDisplay,
+) -> Table {
+    let mut table = section_table(tr!("tasks-title"));
+    table.add_empty_row();
+    if let Some(limit) = max_agreements.filter(|limit| *limit > 0) {
+        let running = status.in_progress();
+        let utilization = running as f64 * 100.0 / limit as f64;
+        let colour = if running >= limit as u64 {
+            Colour::Green
+        } else {
+            Colour::Fixed(220)
+        };
+        table.add_row(row![
+            tr!("tasks-utilization"),
+            Style::new().fg(colour).paint(tr!(
+                "tasks-utilization-value",
+                running = running,
+                limit = limit,
+                percent = format!("{:.0}", utilization)
+            ))
+        ]);
+    }
+    table.add_row(row![
+        tr!("tasks-last-1h-processed"),
+        status.last1h_processed()
+    ]);
+    table.add_row(row![tr!("tasks-last-1h-in-progress"), status.in_progress()]);
+    table.add_row(row![tr!("tasks-total-processed"), status.total_processed()]);
+    table.add_row(row![tr!("tasks-including-failures")]);
+    if let Some(ts) = status.last_activity_ts {
+        table.add_empty_row();
+        table.add_row(row![tr!("tasks-last-activity"), time_display.render(ts)]);
+    }
+    match workload {
+        Some(Ok(runtimes)) if !runtimes.is_empty() => {
+            table.add_empty_row();
+            table.add_row(row![tr!("tasks-by-runtime")]);
+            for runtime in runtimes {
+                table.add_row(row![
+                    format!("  {}", runtime.runtime),
+                    tr!(
+                        "tasks-runtime-value",
+                        activities = runtime.activities,
+                        glm = display::glm(&runtime.invoiced)
+                    )
+                ]);
+            }
+        }
+        Some(Err(e)) => {
+            table.add_empty_row();
+            table.add_row(row![tr!("tasks-by-runtime"), unavailable_reason(e)]);
+        }
+        _ => (),
+    }
+
+    table
+}
+
+fn requestor_table(status: &RequestorStatus, now: DateTime<Utc>) -> Table {
+    let mut table = section_table(tr!("requestor-title"));
+    table.add_empty_row();
+    let invoices = &status.invoices;
+    table.add_row(row![
+        tr!("requestor-spent"),
+        tr!(
+            "requestor-glm-value",
+            glm = display::glm(&invoices.settled.total_amount),
+            count = invoices.settled.agreements_count
+        )
+    ]);
+    table.add_row(row![
+        tr!("requestor-to-pay"),
+        tr!(
+            "requestor-glm-value",
+            glm = display::glm(&invoices.accepted.total_amount),
+            count = invoices.accepted.agreements_count
+        )
+    ]);
+    table.add_row(row![
+        tr!("requestor-to-accept"),
+        tr!(
+            "requestor-glm-value",
+            glm = display::glm(&invoices.received.total_amount),
+            count = invoices.received.agreements_count
+        )
+    ]);
+    table.add_row(row![
+        tr!("requestor-debit-notes"),
+        tr!(
+            "requestor-debit-notes-value",
+            glm = display::glm(&status.pending_debit_notes_amount),
+            count = status.pending_debit_notes
+        )
+    ]);
+    table.add_empty_row();
+    let remaining = status
+        .allocations
+        .iter()
+        .map(|allocation| allocation.remaining.clone())
+        .sum::<BigDecimal>();
+    table.add_row(row![
+        tr!("requestor-allocations"),
+        tr!(
+            "requestor-allocations-value",
+            count = status.allocations.len(),
+            glm = display::glm(&remaining)
+        )
+    ]);
+    table.add_row(row![tr!("requestor-demands"), status.demands]);
+    let warnings = status.warnings(now);
+    if !warnings.is_empty() {
+        table.add_empty_row();
+        for warning in warnings {
+            table.add_row(row![Style::new().fg(Colour::Red).paint(warning)]);
+        }
+    }
+
+    table
+}
+
+async fn get_payment_network(cmd: &YaCommand) -> Result<(usize, NetworkName)> {
+    // Dirty hack: we determine currently used payment network by checking latest offer properties
+    let app_key = appkey::get_app_key(cmd).await?;
+    let mkt_api: ya_client::market::MarketProviderApi =
+        ya_client::web::WebClient::with_token(&app_key).interface()?;
+    let offers = mkt_api.get_offers().await?;
+
+    let latest_offer = offers
+        .iter()
+        .max_by_key(|o| o.timestamp)
+        .ok_or_else(|| anyhow!(tr!("error-no-offers")))?;
+    let mut network = None;
+    for net in NetworkName::VARIANTS {
+        let net_to_check = net.parse()?;
+        let platform = ERC20_DRIVER
+            .platform(&net_to_check)
+            .or_else(|_e| ZKSYNC_DRIVER.platform(&net_to_check))?;
+        let platform_property =
+            &format!("golem.com.payment.platform.{}.address", platform.platform,);
+        if latest_offer.properties.get(platform_property).is_some() {
+            network = Some(net_to_check)
+        };
+    }
+
+    let network = network.ok_or_else(|| anyhow!(tr!("error-unknown-payment-network")))?;
+    Ok((offers.len(), network))
+}
+
+fn p2p_table(status: &P2pStatus) -> Table {
+    let mut table = section_table(tr!("p2p-title"));
+    table.add_empty_row();
+    table.add_row(row![tr!("p2p-mode"), crate::net::net_type()]);
+    table.add_row(row![tr!("p2p-peers"), status.peers]);
+    table.add_row(row![tr!("p2p-relay"), crate::net::relay_host()]);
+    let public = match &status.net.public_address {
+        Some(address) => Style::new().fg(Colour::Green).paint(address.clone()),
+        None => Style::new()
+            .fg(Colour::Fixed(220))
+            .paint(tr!("p2p-no-public-address")),
+    };
+    table.add_row(row![tr!("p2p-public-address"), public]);
+
+    table
+}
+
+fn market_table(market: &MarketActivity) -> Table {
+    let mut table = section_table(tr!("market-title"));
+    table.add_empty_row();
+    let offers_colour = if market.active_offers > 0 {
+        Colour::Green
+    } else {
+        Colour::Red
+    };
+    table.add_row(row![
+        tr!("market-active-offers"),
+        Style::new()
+            .fg(offers_colour)
+            .paint(market.active_offers.to_string())
+    ]);
+    table.add_empty_row();
+    table.add_row(row!["", tr!("market-last-hour"), tr!("market-last-day")]);
+    let (hour, day) = (&market.last_hour, &market.last_day);
+    table.add_row(row![tr!("market-offers"), r->hour.offers, r->day.offers]);
+    table.add_row(row![tr!("market-proposals"), r->hour.proposals, r->day.proposals]);
+    table.add_row(row![tr!("market-rejected"), r->hour.rejected, r->day.rejected]);
+    table.add_row(row![tr!("market-agreements"), r->hour.agreements, r->day.agreements]);
+    if market.active_offers > 0 && day.proposals == 0 {
+        table.add_empty_row();
+        table.add_row(row![H3->Style::new()
+            .fg(Colour::Fixed(220))
+            .paint(tr!("market-no-proposals"))]);
+    }
+
+    table
+}
+
+fn problems_table(problems: &[Problem], window: i64, ascii: bool) -> Table {
+    let mut table = section_table(tr!("problems-title", minutes = window));
+    table.add_empty_row();
+    if problems.is_empty() {
+        table.add_row(row![Style::new()
+            .fg(Colour::Green)
+            .paint(tr!("problems-none"))]);
+    }
+    let times = if ascii { "x" } else { "×" };
+    for problem in problems.iter().take(TOP_PROBLEMS) {
+        let colour = if problem.level == "ERROR" {
+            Colour::Red
+        } else {
+            Colour::Yellow
+        };
+        let message: String = problem.message.chars().take(PROBLEM_WIDTH).collect();
+        table.add_row(row![
+            r->format!("{}{}", problem.count, times),
+            Style::new().fg(colour).paint(problem.daemon.name()),
+            message
+        ]);
+    }
+
+    table
+}
+
+fn payment_failures_table(failed: &FailedPayments, ascii: bool) -> Table {
+    let mut table = section_table(tr!("payment-failures-title", hours = FAILURES_WINDOW_HOURS));
+    table.add_empty_row();
+    if failed.provider_failed > 0 {
+        table.add_row(row![
+            r->failed.provider_failed,
+            Style::new().fg(Colour::Red).paint(tr!("payment-failures-provider"))
+        ]);
+    }
+    if failed.requestor_failed > 0 {
+        table.add_row(row![
+            r->failed.requestor_failed,
+            Style::new().fg(Colour::Red).paint(tr!("payment-failures-requestor"))
+        ]);
+    }
+    let times = if ascii { "x" } else { "×" };
+    for failure in failed.failures.iter().take(TOP_PROBLEMS) {
+        let reason: String = failure.reason.chars().take(PROBLEM_WIDTH).collect();
+        table.add_row(row![r->format!("{}{}", failure.count, times), reason]);
+    }
+
+    table
+}
+
+fn drivers_table(status: &DriverStatus, now: DateTime<Utc>) -> Table {
+    let mut table = section_table(tr!("drivers-title"));
+    table.add_empty_row();
+    table.add_row(row![
+        "",
+        tr!("drivers-endpoint"),
+        tr!("drivers-block"),
+        tr!("drivers-pending")
+    ]);
+    for network in &status.networks {
+        let block = match (&network.error, network.last_block, network.block_time) {
+            _ if network.skipped => Style::new().paint(tr!("drivers-skipped")),
+            (Some(_), _, _) => Style::new()
+                .fg(Colour::Red)
+                .paint(tr!("drivers-unreachable")),
+            (None, Some(block), Some(time)) => {
+                let colour = if network.is_stale(now) {
+                    Colour::Red
+                } else {
+                    Colour::Green
+                };
+                Style::new()
+                    .fg(colour)
+                    .paint(format!("{} ({})", block, format_relative(time, now)))
+            }
+            _ => Style::new().paint("-"),
+        };
+        let pending = match network.pending {
+            Some(0) => Style::new().paint("0"),
+            Some(pending) => Style::new()
+                .fg(Colour::Fixed(220))
+                .paint(pending.to_string()),
+            None => Style::new().paint("-"),
+        };
+        table.add_row(row![
+            network.network,
+            // snapshots saved before endpoints were cut to their origin hold the full url
+            crate::http::origin(&network.endpoint),
+            block,
+            r->pending
+        ]);
+    }
+    for problem in &status.problems {
+        table.add_row(row![
+            problem.network.as_deref().unwrap_or("-"),
+            H3->Style::new().fg(Colour::Red).paint(match &problem.details {
+                serde_json::Value::Object(fields) if fields.is_empty() => problem.kind.clone(),
+                details => format!("{} {}", problem.kind, details),
+            })
+        ]);
+    }
+
+    table
+}
+
+fn changes_table(changes: &[Change], since: Option<DateTime<Utc>>, ascii: bool) -> Table {
+    let title = match since {
+        Some(ts) => tr!("changes-title-since", ago = format_relative(ts, Utc::now())),
+        None => tr!("changes-title"),
+    };
+    let mut table = section_table(title);
+    table.add_empty_row();
+    if changes.is_empty() {
+        table.add_row(row![Style::new().dimmed().paint(tr!("changes-none"))]);
+    }
+    let show = |value: &serde_json::Value| match value {
+        serde_json::Value::String(s) => s.clone(),
+        value => value.to_string(),
+    };
+    let arrow = if ascii { "->" } else { "→" };
+    for change in changes {
+        let delta = match &change.delta {
+            Some(delta) if delta.starts_with('-') => Style::new().fg(Colour::Red).paint(delta),
+            Some(delta) => Style::new().fg(Colour::Green).paint(delta),
+            None => Style::new().paint(""),
+        };
+        table.add_row(row![
+            change.label,
+            format!("{} {} {}", show(&change.before), arrow, show(&change.after)),
+            r->delta
+        ]);
+    }
+
+    table
+}
+
+fn profit_table(profit: &Profitability) -> Table {
+    let currency = CURRENCY.to_uppercase();
+    let mut table = section_table(tr!("profit-title"));
+    table.add_empty_row();
+    table.add_row(row![
+        tr!("profit-power"),
+        tr!(
+            "profit-power-value",
+            watts = profit.watts,
+            price = profit.price,
+            currency = currency
+        )
+    ]);
+    table.add_row(row![
+        tr!("profit-energy-cost"),
+        tr!(
+            "profit-energy-cost-value",
+            hour = display::fiat_f64(profit.cost_per_hour),
+            day = display::fiat_f64(profit.cost_per_day),
+            currency = currency
+        )
+    ]);
+    let earned = match (profit.earned_per_day, profit.earned_fiat_per_day) {
+        (Some(glm), Some(fiat)) => tr!(
+            "profit-earned-value",
+            glm = display::glm_f64(glm),
+            fiat = display::fiat_f64(fiat),
+            currency = currency
+        ),
+        (Some(glm), None) => tr!("profit-earned-glm", glm = display::glm_f64(glm)),
+        (None, _) => tr!("profit-no-data"),
+    };
+    table.add_row(row![tr!("profit-earned"), earned]);
+    if let Some(break_even) = profit.break_even_price {
+        let colour = if profit.below_break_even() {
+            Colour::Red
+        } else {
+            Colour::Green
+        };
+        table.add_row(row![
+            tr!("profit-break-even"),
+            Style::new().fg(colour).paint(tr!(
+                "profit-break-even-value",
+                price = display::fiat_f64(break_even),
+                currency = currency
+            ))
+        ]);
+    }
+    if let Some(net) = profit.net_per_day {
+        let colour = if net >= 0.0 {
+            Colour::Green
+        } else {
+            Colour::Red
+        };
+        table.add_row(row![
+            tr!("profit-net"),
+            Style::new().fg(colour).paint(tr!(
+                "profit-net-value",
+                net = format!(
+                    "{}{}",
+                    if net >= 0.0 { "+" } else { "-" },
+                    display::fiat_f64(net.abs())
+                ),
+                currency = currency
+            ))
+        ]);
+    }
+
+    table
+}
+
+fn uptime_table(report: &[(i64, Option<f64>)]) -> Table {
+    let mut table = section_table(tr!("uptime-title"));
+    table.add_empty_row();
+    for (days, availability) in report {
+        let label = if *days == 1 {
+            tr!("uptime-24h")
+        } else {
+            tr!("uptime-days", days = days)
+        };
+        let value = match availability {
+            Some(percent) => {
+                let colour = if *percent >= 95.0 {
+                    Colour::Green
+                } else if *percent >= 80.0 {
+                    Colour::Yellow
+                } else {
+                    Colour::Red
+                };
+                Style::new().fg(colour).paint(format!("{:.1}%", percent))
+            }
+            None => Style::new().dimmed().paint(tr!("uptime-no-data")),
+        };
+        table.add_row(row![label, r->value]);
+    }
+
+    table
+}
+
+fn rejections_table(reasons: &[(String, usize)]) -> Table {
+    let mut table = section_table(tr!("rejections-title"));
+    table.add_empty_row();
+    if reasons.is_empty() {
+        table.add_row(row![Style::new()
+            .fg(Colour::Green)
+            .paint(tr!("rejections-none"))]);
+    }
+    for (reason, count) in reasons {
+        table.add_row(row![r->count, reason]);
+    }
+
+    table
+}
+
+fn ranking_table(ranking: &Ranking) -> Table {
+    let mut table = section_table(tr!("ranking-title"));
+    table.add_empty_row();
+    let node = match &ranking.node {
+        Some(node) => node,
+        None => {
+            table.add_row(row![Style::new()
+                .fg(Colour::Fixed(220))
+                .paint(tr!("ranking-not-online"))]);
+            return table;
+        }
+    };
+    table.add_row(row![tr!("ranking-online"), ranking.network.len() + 1]);
+
+    let mut add_metric = |label: String, value: f64, network: Vec<f64>, precision: usize| {
+        let median = stats::percentile(&network, 50.0).unwrap_or_default();
+        let top = 100.0 - stats::percentile_rank(&network, value);
+        let colour = if value >= median {
+            Colour::Green
+        } else {
+            Colour::Fixed(220)
+        };
+        table.add_row(row![
+            label,
+            Style::new()
+                .fg(colour)
+                .paint(format!("{:.*}", precision, value))
+        ]);
+        table.add_row(row![
+            "",
+            tr!(
+                "ranking-compared",
+                median = format!("{:.*}", precision, median),
+                top = format!("{:.0}", top.max(1.0))
+            )
+        ]);
+    };
+    let network = |extract: fn(&OnlineNode) -> f64| ranking.network.iter().map(extract).collect();
+    add_metric(
+        tr!("ranking-tasks"),
+        node.tasks_total as f64,
+        network(|n| n.tasks_total as f64),
+        0,
+    );
+    add_metric(
+        tr!("ranking-earnings"),
+        node.earnings_total,
+        network(|n| n.earnings_total),
+        2,
+    );
+    add_metric(tr!("ranking-uptime"), node.uptime, network(|n| n.uptime), 1);
+
+    table
+}
+
+fn processes_table(processes: &[ProcessMetrics]) -> Table {
+    let mut table = section_table(tr!("processes-title"));
+    table.add_empty_row();
+    for daemon in processes::DAEMONS {
+        if !processes.iter().any(|process| process.name == *daemon) {
+            table.add_row(row![
+                daemon,
+                Style::new()
+                    .fg(Colour::Red)
+                    .paint(tr!("processes-not-running"))
+            ]);
+        }
+    }
+    for process in processes {
+        table.add_row(row![
+            tr!("processes-name-pid", name = process.name, pid = process.pid),
+            Byte::from_bytes(process.rss_bytes as u128).get_appropriate_unit(true)
+        ]);
+        table.add_row(row![
+            "",
+            tr!("processes-cpu", cpu = format!("{:.1}", process.cpu_percent))
+        ]);
+        if let Some(fds) = process.open_fds {
+            table.add_row(row!["", tr!("processes-fds", count = fds)]);
+        }
+    }
+
+    table
+}
+
+fn services_table(services: &[ServiceState], time_display: &TimeDisplay) -> Table {
+    let mut table = section_table(tr!("service-title"));
+    for service in services {
+        table.add_empty_row();
+        table
+            .add_row(row![H2->tr!("service-unit", name = service.name, manager = service.manager)]);
+        let colour = if service.is_running() {
+            Colour::Green
+        } else {
+            Colour::Red
+        };
+        let state = match &service.sub_state {
+            Some(sub_state) => format!("{} ({})", service.state, sub_state),
+            None => service.state.clone(),
+        };
+        table.add_row(row![
+            tr!("service-state"),
+            Style::new().fg(colour).paint(state)
+        ]);
+        if let Some(since) = service.since {
+            table.add_row(row![tr!("service-since"), time_display.render(since)]);
+        }
+        if let Some(restarts) = service.restarts {
+            table.add_row(row![tr!("service-restarts"), restarts]);
+        }
+        for error in &service.recent_errors {
+            table.add_row(row![
+                tr!("service-recent-error"),
+                Style::new().fg(Colour::Red).paint(error.clone())
+            ]);
+        }
+    }
+
+    table
+}
+
+pub(crate) fn hardware_table(hardware: &Hardware, time_display: &TimeDisplay) -> Table {
+    let bytes = |n: u64| Byte::from_bytes(n as u128).get_appropriate_unit(true);
+    let mut table = section_table(tr!("hardware-title"));
+    table.add_empty_row();
+    if let Some(model) = &hardware.cpu_model {
+        table.add_row(row![tr!("hardware-cpu"), model]);
+    }
+    let cores = match hardware.physical_cores {
+        Some(physical) => tr!(
+            "hardware-cores-threads",
+            cores = physical,
+            threads = hardware.logical_cores
+        ),
+        None => tr!("hardware-threads", threads = hardware.logical_cores),
+    };
+    table.add_row(row![tr!("hardware-cores"), cores]);
+    table.add_row(row![
+        tr!("hardware-memory"),
+        tr!(
+            "hardware-free-of",
+            free = bytes(hardware.available_memory),
+            total = bytes(hardware.total_memory)
+        )
+    ]);
+    if let Some(disk) = &hardware.disk {
+        table.add_row(row![
+            tr!("hardware-disk"),
+            tr!(
+                "hardware-free-of",
+                free = bytes(disk.free),
+                total = bytes(disk.total)
+            )
+        ]);
+        table.add_row(row!["", disk.path.display()]);
+    }
+    if let Some(offered) = &hardware.offered {
+        table.add_row(row![
+            tr!("hardware-offered", profile = offered.profile.clone()),
+            tr!(
+                "hardware-offered-resources",
+                threads = offered.resources.cpu_threads,
+                memory = format!("{:.1}", offered.resources.mem_gib),
+                storage = format!("{:.1}", offered.resources.storage_gib)
+            )
+        ]);
+        for warning in hardware.overcommitted() {
+            table.add_row(row!["", Style::new().fg(Colour::Red).paint(warning)]);
+        }
+        if let Some(warning) = hardware.underused() {
+            table.add_row(row!["", Style::new().fg(Colour::Fixed(220)).paint(warning)]);
+        }
+    }
+    if hardware.gpus.is_empty() {
+        table.add_row(row![tr!("hardware-gpu"), tr!("hardware-no-gpu")]);
+    }
+    for (n, gpu) in hardware.gpus.iter().enumerate() {
+        let label = if n == 0 {
+            tr!("hardware-gpu")
+        } else {
+            String::new()
+        };
+        table.add_row(row![label, gpu]);
+    }
+    if let Some(bench) = &hardware.bench {
+        let scores = &bench.record;
+        table.add_empty_row();
+        table.add_row(row![
+            tr!("hardware-bench"),
+            time_display.render_absolute(bench.ts)
+        ]);
+        table.add_row(row![
+            tr!("hardware-cpu"),
+            tr!(
+                "hardware-bench-cpu",
+                single = format!("{:.0}", scores.cpu_single),
+                multi = format!("{:.0}", scores.cpu_multi),
+                threads = scores.threads
+            )
+        ]);
+        table.add_row(row![
+            tr!("hardware-memory"),
+            tr!(
+                "hardware-bench-mb-s",
+                value = format!("{:.0}", scores.memory)
+            )
+        ]);
+        table.add_row(row![
+            tr!("hardware-disk"),
+            tr!(
+                "hardware-bench-disk",
+                value = format!("{:.0}", scores.disk_write)
+            )
+        ]);
+    }
+
+    table
+}
+
+fn gpu_table(gpu: &GpuStatus) -> Table {
+    let mut table = section_table(tr!("gpu-title"));
+    table.add_empty_row();
+    if gpu.runtimes.is_empty() {
+        table.add_row(row![
+            tr!("gpu-runtime"),
+            Style::new().fg(Colour::Red).paint(tr!("gpu-no-runtime"))
+        ]);
+    }
+    for (n, runtime) in gpu.runtimes.iter().enumerate() {
+        let label = if n == 0 {
+            tr!("gpu-runtime")
+        } else {
+            String::new()
+        };
+        let name = match &runtime.version {
+            Some(version) => format!("{} {}", runtime.name, version),
+            None => runtime.name.clone(),
+        };
+        table.add_row(row![label, name]);
+    }
+    let not_detected = || Style::new().fg(Colour::Red).paint(tr!("gpu-not-detected"));
+    table.add_row(match &gpu.driver_version {
+        Some(version) => row![tr!("gpu-driver"), version],
+        None => row![tr!("gpu-driver"), not_detected()],
+    });
+    table.add_row(match &gpu.cuda_version {
+        Some(version) => row![tr!("gpu-cuda"), version],
+        None => row![tr!("gpu-cuda"), not_detected()],
+    });
+    if !gpu.runtimes.is_empty() {
+        let presets = if gpu.active_presets.is_empty() {
+            Style::new()
+                .fg(Colour::Red)
+                .paint(tr!("gpu-preset-inactive"))
+        } else {
+            Style::new()
+                .fg(Colour::Green)
+                .paint(gpu.active_presets.join(", "))
+        };
+        table.add_row(row![tr!("gpu-preset"), presets]);
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hours: i64) -> DateTime<Utc> {
+        Utc.timestamp(1_600_000_000, 0) + chrono::Duration::hours(hours)
+    }
+
+    fn health(
+        last_activity: Option<Option<DateTime<Utc>>>,
+        started_at: Option<DateTime<Utc>>,
+    ) -> Health {
+        node_health(
+            true,
+            false,
+            last_activity,
+            started_at,
+            at(48),
+            chrono::Duration::hours(24),
+        )
+    }
+
+    #[test]
+    fn health_outcomes() {
+        let max_idle = chrono::Duration::hours(24);
+        assert_eq!(
+            node_health(false, true, Some(None), None, at(48), max_idle),
+            Health::NotRunning
+        );
+        assert_eq!(
+            node_health(true, true, Some(Some(at(47))), None, at(48), max_idle),
+            Health::PaymentsFailing
+        );
+        assert_eq!(health(Some(Some(at(47))), Some(at(0))), Health::Healthy);
+        assert_eq!(health(Some(Some(at(12))), Some(at(0))), Health::Idle);
+        assert_eq!(health(Some(Some(at(24))), None), Health::Healthy);
+    }
+
+    #[test]
+    fn idle_counts_from_start_without_tasks() {
+        assert_eq!(health(Some(None), Some(at(47))), Health::Healthy);
+        assert_eq!(health(Some(None), Some(at(12))), Health::Idle);
+        assert_eq!(health(Some(None), None), Health::Healthy);
+        assert_eq!(health(None, Some(at(0))), Health::Healthy);
+    }
+
+    #[test]
+    fn max_idle_bounds() {
+        assert_eq!(parse_minutes("1").unwrap(), 1);
+        assert_eq!(parse_minutes(" 60 ").unwrap(), 60);
+        assert_eq!(
+            parse_minutes(&MAX_MINUTES.to_string()).unwrap(),
+            MAX_MINUTES
+        );
+        for bad in ["0", "-5", "abc", "", "99999999999999999999"] {
+            assert!(parse_minutes(bad).is_err(), "{}", bad);
+        }
+        assert!(parse_minutes(&(MAX_MINUTES + 1).to_string()).is_err());
+
+        assert_eq!(minutes(90), Some(chrono::Duration::minutes(90)));
+        assert_eq!(minutes(0), Some(chrono::Duration::zero()));
+        assert_eq!(minutes(-1), None);
+        assert_eq!(minutes(i64::MAX), None);
+    }
+}
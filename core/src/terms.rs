@@ -0,0 +1,77 @@
+//! Acceptance of the Golem terms of use
+//!
+//! The installer and `golemsp` ask for the terms before the node is set up and record each
+//! accepted version as a `<version>.tag` file in the installer's `terms` directory. A fresh
+//! `golemsp run` without them sits on that prompt and never starts yagna, which looks like
+//! a silently broken install.
+
+use directories::BaseDirs;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use sysinfo::{ProcessExt, System, SystemExt};
+
+/// golemsp running this long without a yagna daemon is taken as stuck on the prompt.
+const PROMPT_WAIT_SECS: u64 = 60;
+
+/// Terms accepted on this machine
+#[derive(Deserialize, Serialize)]
+pub struct TermsStatus {
+    /// Versions accepted, eg. "testnet-01"
+    pub accepted: Vec<String>,
+    /// golemsp waits for the terms to be accepted instead of starting yagna
+    pub waiting: bool,
+}
+
+/// `$YA_INSTALLER_DATA/terms`, `~/.local/share/ya-installer/terms` by default.
+fn terms_dir() -> Option<PathBuf> {
+    let data = match std::env::var_os("YA_INSTALLER_DATA") {
+        Some(data) => PathBuf::from(data),
+        None => BaseDirs::new()?
+            .home_dir()
+            .join(".local/share/ya-installer"),
+    };
+    Some(data.join("terms"))
+}
+
+fn accepted_versions() -> Option<Vec<String>> {
+    let entries = std::fs::read_dir(terms_dir()?).ok()?;
+    let mut accepted = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "tag"))
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+        .collect::<Vec<_>>();
+    accepted.sort();
+    Some(accepted)
+}
+
+/// golemsp has been running for a while and yagna was never started by it.
+fn golemsp_waiting() -> bool {
+    let mut sys = System::new();
+    sys.refresh_processes();
+    let name = |process: &sysinfo::Process| process.name().trim_end_matches(".exe").to_string();
+    let yagna = sys
+        .processes()
+        .values()
+        .any(|process| name(process) == "yagna");
+    let now = chrono::Utc::now().timestamp() as u64;
+    !yagna
+        && sys.processes().values().any(|process| {
+            name(process) == "golemsp"
+                && process.cmd().iter().any(|arg| arg == "run")
+                && now.saturating_sub(process.start_time()) >= PROMPT_WAIT_SECS
+        })
+}
+
+/// `None` when the node was not set up with the installer and nothing waits for the terms.
+pub fn status() -> Option<TermsStatus> {
+    let accepted = accepted_versions();
+    let waiting = accepted.as_ref().map_or(true, Vec::is_empty) && golemsp_waiting();
+    if accepted.is_none() && !waiting {
+        return None;
+    }
+    Some(TermsStatus {
+        accepted: accepted.unwrap_or_default(),
+        waiting,
+    })
+}
@@ -0,0 +1,228 @@
+//! Live view of the activities the provider is running, like `top`
+
+use ansi_term::Colour;
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use prettytable::{format, row, Table};
+use std::collections::HashMap;
+use std::time::Duration;
+use structopt::StructOpt;
+
+use ya_client::activity::ActivityProviderApi;
+use ya_client::model::activity::State;
+use ya_client::payment::PaymentApi;
+use ya_client::web::WebClient;
+
+use crate::appkey;
+use crate::command::{AgreementState, CommandOptions, YaCommand};
+use crate::display::{self, format_duration};
+use crate::workload;
+
+/// Characters of agreement and activity ids shown, enough to tell them apart.
+const ID_WIDTH: usize = 12;
+/// Longest delay between refreshes while they keep failing.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(StructOpt)]
+pub struct TopCommand {
+    /// Seconds between refreshes
+    #[structopt(short, long, default_value = "1")]
+    delay: u64,
+}
+
+/// Activity in progress and what it cost the requestor so far.
+pub struct RunningActivity {
+    pub activity_id: String,
+    pub agreement_id: String,
+    pub runtime: String,
+    pub state: State,
+    pub started: DateTime<Utc>,
+    /// Amount due in the latest debit note
+    pub cost: Option<BigDecimal>,
+}
+
+/// Amounts due of the latest debit note per activity, fetched incrementally.
+#[derive(Default)]
+struct DebitNotes {
+    latest: HashMap<String, (DateTime<Utc>, BigDecimal)>,
+    after: Option<DateTime<Utc>>,
+}
+
+impl DebitNotes {
+    async fn update(&mut self, api: &PaymentApi, since: DateTime<Utc>) -> Result<()> {
+        let after = self.after.unwrap_or(since);
+        for note in api.get_debit_notes(Some(after), None).await? {
+            match self.latest.get(&note.activity_id) {
+                Some((ts, _)) if *ts >= note.timestamp => (),
+                _ => {
+                    self.latest.insert(
+                        note.activity_id.clone(),
+                        (note.timestamp, note.total_amount_due.clone()),
+                    );
+                }
+            }
+            self.after = self.after.max(Some(note.timestamp));
+        }
+        Ok(())
+    }
+}
+
+async fn running(
+    cmd: &YaCommand,
+    activity_api: &ActivityProviderApi,
+    payment_api: &PaymentApi,
+    debit_notes: &mut DebitNotes,
+) -> Result<Vec<RunningActivity>> {
+    let agreements = cmd
+        .yagna()?
+        .agreements()
+        .await?
+        .into_iter()
+        .filter(|entry| entry.state == Some(AgreementState::Approved))
+        .filter(
+            |entry| !matches!(&entry.role, Some(role) if role.eq_ignore_ascii_case("requestor")),
+        )
+        .collect::<Vec<_>>();
+
+    let mut activities = vec![];
+    for entry in &agreements {
+        let id = entry.id.clone();
+        let (work, started) = tokio::task::spawn_blocking(move || {
+            Ok::<_, anyhow::Error>((workload::agreement(&id)?, workload::activities(&id)?))
+        })
+        .await??;
+        let runtime = work
+            .map(|work| work.runtime)
+            .unwrap_or_else(|| "-".to_string());
+        for (activity_id, started) in started {
+            activities.push((entry.id.clone(), runtime.clone(), activity_id, started));
+        }
+    }
+
+    let states = futures::future::join_all(
+        activities
+            .iter()
+            .map(|(_, _, activity_id, _)| activity_api.get_activity_state(activity_id)),
+    )
+    .await;
+    if let Some(since) = activities.iter().map(|(_, _, _, started)| *started).min() {
+        debit_notes.update(payment_api, since).await?;
+    }
+
+    Ok(activities
+        .into_iter()
+        .zip(states)
+        .filter_map(|((agreement_id, runtime, activity_id, started), state)| {
+            let state = state
+                .map_err(|e| tracing::debug!("state of {}: {}", activity_id, e))
+                .ok()?
+                .state
+                .0;
+            if matches!(state, State::Terminated) {
+                return None;
+            }
+            Some(RunningActivity {
+                cost: debit_notes
+                    .latest
+                    .get(&activity_id)
+                    .map(|(_, amount)| amount.clone()),
+                activity_id,
+                agreement_id,
+                runtime,
+                state,
+                started,
+            })
+        })
+        .collect())
+}
+
+fn short(id: &str) -> &str {
+    id.get(..ID_WIDTH).unwrap_or(id)
+}
+
+fn top_table(activities: &[RunningActivity], now: DateTime<Utc>) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    table.set_titles(row![
+        tr!("top-activity"),
+        tr!("top-agreement"),
+        tr!("top-runtime"),
+        tr!("top-state"),
+        tr!("top-elapsed"),
+        tr!("top-cost"),
+    ]);
+    for activity in activities {
+        table.add_row(row![
+            short(&activity.activity_id),
+            short(&activity.agreement_id),
+            activity.runtime,
+            format!("{:?}", activity.state),
+            r->format_duration(now - activity.started),
+            r->activity
+                .cost
+                .as_ref()
+                .map(display::glm)
+                .unwrap_or_else(|| "-".to_string()),
+        ]);
+    }
+    table
+}
+
+pub async fn run(options: &CommandOptions, args: TopCommand) -> Result</*exit code*/ i32> {
+    let cmd = YaCommand::new(options)?;
+    let app_key = appkey::get_app_key(&cmd).await?;
+    let client = WebClient::with_token(&app_key);
+    let activity_api: ActivityProviderApi = client.interface()?;
+    let payment_api: PaymentApi = client.interface()?;
+    let mut debit_notes = DebitNotes::default();
+    let redraw = atty::is(atty::Stream::Stdout);
+    let delay = Duration::from_secs(args.delay.max(1));
+
+    // the last activities fetched stay on screen while refreshing fails
+    let mut activities = vec![];
+    let mut failures = 0;
+    loop {
+        let error = match running(&cmd, &activity_api, &payment_api, &mut debit_notes).await {
+            Ok(fresh) => {
+                activities = fresh;
+                activities.sort_by_key(|activity| activity.started);
+                failures = 0;
+                None
+            }
+            Err(e) => {
+                tracing::debug!("refreshing activities failed: {:?}", e);
+                failures += 1;
+                Some(e)
+            }
+        };
+        let now = Utc::now();
+        let total = activities
+            .iter()
+            .filter_map(|activity| activity.cost.as_ref())
+            .fold(BigDecimal::default(), |sum, cost| sum + cost);
+        let mut header = tr!(
+            "top-header",
+            time = now.with_timezone(&chrono::Local).format("%H:%M:%S"),
+            count = activities.len(),
+            total = display::glm(&total)
+        );
+        if let Some(e) = &error {
+            let error = tr!("top-error", error = format!("{:#}", e));
+            header = format!("{}\n{}", header, Colour::Red.paint(error));
+        }
+        let body = if activities.is_empty() {
+            format!("{}\n", tr!("top-idle"))
+        } else {
+            top_table(&activities, now).to_string()
+        };
+        if redraw {
+            print!("\x1b[2J\x1b[H{}\n\n{}", header, body);
+        } else {
+            println!("{}\n{}", header, body);
+        }
+        // fewer yagna calls while it doesn't answer, eg. during a restart
+        let backoff = (delay * 2u32.pow(failures.min(6))).min(MAX_BACKOFF.max(delay));
+        tokio::time::delay_for(backoff).await;
+    }
+}
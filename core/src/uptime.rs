@@ -0,0 +1,59 @@
+//! Availability of the node computed from samples recorded by `status`
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::history::{self, Entry};
+
+const HISTORY: &str = "uptime";
+
+/// Samples further apart than this don't tell anything about the time between them.
+const MAX_SAMPLE_GAP_MINUTES: i64 = 15;
+
+/// Windows availability is reported for, in days.
+const WINDOWS: &[i64] = &[1, 7, 30];
+
+#[derive(Serialize, Deserialize)]
+pub struct Sample {
+    /// yagna accepted connections on its API port
+    pub running: bool,
+}
+
+pub fn record(running: bool) -> Result<()> {
+    history::append(HISTORY, Sample { running })
+}
+
+/// Percentage of the observed part of the `window` before `now` during which the node was up,
+/// `None` when there are no samples in that window.
+fn availability(samples: &[Entry<Sample>], now: DateTime<Utc>, window: Duration) -> Option<f64> {
+    let start = now - window;
+    let max_gap = Duration::minutes(MAX_SAMPLE_GAP_MINUTES);
+    let mut observed = Duration::zero();
+    let mut up = Duration::zero();
+    for (n, sample) in samples.iter().enumerate() {
+        let end = samples.get(n + 1).map(|next| next.ts).unwrap_or(now);
+        if end <= start || end - sample.ts > max_gap {
+            continue;
+        }
+        let span = end - sample.ts.max(start);
+        observed = observed + span;
+        if sample.record.running {
+            up = up + span;
+        }
+    }
+    if observed.num_milliseconds() == 0 {
+        return None;
+    }
+    Some(up.num_milliseconds() as f64 * 100.0 / observed.num_milliseconds() as f64)
+}
+
+/// Availability in each of the `WINDOWS`.
+pub fn report() -> Result<Vec<(i64, Option<f64>)>> {
+    let samples = history::load::<Sample>(HISTORY)?;
+    let now = Utc::now();
+    Ok(WINDOWS
+        .iter()
+        .map(|days| (*days, availability(&samples, now, Duration::days(*days))))
+        .collect())
+}
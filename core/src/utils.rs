@@ -0,0 +1,204 @@
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::{net::TcpStream, process::Command};
+use url::Url;
+
+use ya_core_model::NodeId;
+
+use crate::command::{output_with_retry, ExecPolicy, YaCommand};
+
+/// How long system tools (systemctl, nvidia-smi...) may take, they are not retried.
+const SYSTEM_COMMAND_POLICY: ExecPolicy = ExecPolicy {
+    timeout: Duration::from_secs(10),
+    retries: 0,
+    echo: false,
+};
+
+pub async fn get_command_raw_output(program: &str, args: &[&str]) -> Result<Vec<u8>> {
+    let mut command = Command::new(program);
+    command.args(args);
+    tracing::debug!("executing {:?} {:?}", program, args);
+    let command_output = output_with_retry(&mut command, SYSTEM_COMMAND_POLICY).await?;
+    tracing::debug!(
+        "subcommand output: {:?}",
+        String::from_utf8_lossy(&command_output.stdout)
+    );
+    Ok(command_output.stdout)
+}
+
+pub async fn get_command_output(program: &str, args: &[&str]) -> Result<String> {
+    let output = get_command_raw_output(program, args).await?;
+    Ok(String::from_utf8(output)?)
+}
+
+pub async fn get_command_json_output(program: &str, args: &[&str]) -> Result<serde_json::Value> {
+    let output = get_command_raw_output(program, args).await?;
+    Ok(serde_json::from_slice(&output)?)
+}
+
+/// Data directory of the yagna daemon, honoring `YAGNA_DATADIR`.
+pub fn yagna_datadir() -> Option<PathBuf> {
+    match std::env::var_os("YAGNA_DATADIR") {
+        Some(datadir) => Some(PathBuf::from(datadir)),
+        None => default_yagna_datadir(),
+    }
+}
+
+/// Data directory yagna uses when not given one.
+pub fn default_yagna_datadir() -> Option<PathBuf> {
+    ProjectDirs::from("", "GolemFactory", "yagna").map(|dirs| dirs.data_dir().into())
+}
+
+/// Data directory of ya-provider, honoring `DATA_DIR`.
+pub fn provider_datadir() -> Option<PathBuf> {
+    match std::env::var_os("DATA_DIR") {
+        Some(datadir) => Some(PathBuf::from(datadir)),
+        None => default_provider_datadir(),
+    }
+}
+
+/// Data directory ya-provider uses when not given one.
+pub fn default_provider_datadir() -> Option<PathBuf> {
+    ProjectDirs::from("", "GolemFactory", "ya-provider").map(|dirs| dirs.data_dir().into())
+}
+
+/// Directories where yagna-usd keeps its own configuration and state.
+pub fn project_dirs() -> Option<ProjectDirs> {
+    ProjectDirs::from("", "GolemFactory", "yagna-usd")
+}
+
+pub fn move_string_out_of_json(value: serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(s),
+        _ => None,
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_socket(addr: std::net::SocketAddr) -> Result<()> {
+    use std::io;
+    use tokio::time;
+
+    let mut timeout_remaining = 10;
+
+    loop {
+        match TcpStream::connect(addr).await {
+            Ok(_) => {
+                tracing::debug!("socket found, addr: {}", addr);
+                return Ok(());
+            }
+            Err(err) => match err.kind() {
+                io::ErrorKind::ConnectionRefused => {
+                    tracing::debug!("Waiting for socket ...");
+                    if timeout_remaining > 0 {
+                        time::delay_for(time::Duration::from_secs(1)).await;
+                        timeout_remaining -= 1;
+                    } else {
+                        anyhow::bail!("Could not connect to the yagna socket");
+                    }
+                }
+                _ => break Err(err.into()),
+            },
+        }
+    }
+}
+
+/// REST API of yagna, honoring `YAGNA_API_URL`.
+pub fn yagna_api_url() -> String {
+    std::env::var("YAGNA_API_URL").unwrap_or_else(|_| "http://127.0.0.1:7465".to_string())
+}
+
+fn yagna_addr() -> Result<std::net::SocketAddr> {
+    Ok(Url::parse(&yagna_api_url())
+        .context("Failed to parse yagna API URL")?
+        .socket_addrs(|| None)
+        .context("Failed to resolve yagna API URL")?
+        .drain(..)
+        .next()
+        .unwrap())
+}
+
+#[cfg(not(unix))]
+pub async fn wait_for_yagna() -> Result<()> {
+    wait_for_socket(yagna_addr()?).await
+}
+
+pub async fn is_yagna_running() -> Result<bool> {
+    Ok(TcpStream::connect(yagna_addr()?).await.is_ok())
+}
+
+pub async fn payment_account(cmd: &YaCommand, address: &Option<NodeId>) -> Result<String> {
+    Ok(match address {
+        Some(address) => address.to_string(),
+        _ => cmd.yagna()?.default_id().await?.node_id,
+    })
+}
+
+/// Replaces `path` with `content` through a temporary file in the same directory,
+/// so readers never see a partially written file.
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<()> {
+    replace_through_staged(path, content, false)
+}
+
+/// [`write_atomic`] for secrets: on unix the staged file is created readable by the owner
+/// only before anything is written to it.
+pub fn write_atomic_private(path: &Path, content: &[u8]) -> Result<()> {
+    replace_through_staged(path, content, true)
+}
+
+/// New file next to `path` for its content to be staged in. The name is unique to this
+/// process and call, so concurrent writers of `path` (eg. a watch and a cron run) never
+/// share one.
+fn create_staged(path: &Path, private: bool) -> Result<(PathBuf, fs::File)> {
+    static STAGED: AtomicUsize = AtomicUsize::new(0);
+
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("{} is not a file path", path.display()))?;
+    loop {
+        let staged = path.with_file_name(format!(
+            ".{}.{}-{}.tmp",
+            file_name.to_string_lossy(),
+            std::process::id(),
+            STAGED.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        if private {
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+                options.mode(0o600);
+            }
+        }
+        match options.open(&staged) {
+            Ok(file) => return Ok((staged, file)),
+            // left over by an interrupted process which had the same pid
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => {
+                return Err(e).with_context(|| format!("creating {}", staged.display()));
+            }
+        }
+    }
+}
+
+fn replace_through_staged(path: &Path, content: &[u8], private: bool) -> Result<()> {
+    use std::io::Write;
+
+    let (staged, mut file) = create_staged(path, private)?;
+    let replaced = file
+        .write_all(content)
+        .and_then(|_| file.sync_all())
+        .with_context(|| format!("writing {}", staged.display()))
+        .and_then(|_| {
+            fs::rename(&staged, path).with_context(|| format!("replacing {}", path.display()))
+        });
+    if replaced.is_err() {
+        fs::remove_file(&staged).ok();
+    }
+    replaced
+}
@@ -0,0 +1,119 @@
+//! Backup of the node's key, which holds the GLM earned
+//!
+//! The key is exported with `yagna id export` and encrypted with the identity's password,
+//! when it has none `yagna id lock` is offered first. Backups made are recorded in the
+//! local history, so `status` can tell when there was never one.
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+use crate::command::{CommandOptions, YaCommand};
+use crate::history;
+use crate::utils::write_atomic_private;
+
+const BACKUP_HISTORY: &str = "wallet-backup";
+
+#[derive(StructOpt)]
+pub enum WalletCommand {
+    /// Export the node's key to a file, to be restored with `yagna id import`
+    Backup {
+        /// File to write the key to (yagna-key-<node id>-<date>.json by default)
+        #[structopt(long, parse(from_os_str))]
+        out: Option<PathBuf>,
+    },
+}
+
+#[derive(Deserialize, Serialize)]
+struct WalletBackup {
+    node_id: String,
+    path: PathBuf,
+}
+
+/// Backups known for the node, `last` is `None` when none was ever made.
+#[derive(Deserialize, Serialize)]
+pub struct BackupState {
+    pub last: Option<DateTime<Utc>>,
+}
+
+/// When the key of `node_id` was last backed up with `wallet backup`.
+pub fn backup_state(node_id: &str) -> Result<BackupState> {
+    let last = history::load::<WalletBackup>(BACKUP_HISTORY)?
+        .into_iter()
+        .filter(|entry| entry.record.node_id.eq_ignore_ascii_case(node_id))
+        .map(|entry| entry.ts)
+        .max();
+    Ok(BackupState { last })
+}
+
+pub async fn run(options: &CommandOptions, command: WalletCommand) -> Result</*exit code*/ i32> {
+    match command {
+        WalletCommand::Backup { out } => backup(options, out).await,
+    }
+}
+
+/// Lets yagna ask for a new password, the terminal is handed over to it.
+async fn lock(cmd: &YaCommand) -> Result<()> {
+    let yagna = cmd
+        .resolve("yagna")
+        .ok_or_else(|| anyhow!(tr!("service-not-installed")))?;
+    let status = tokio::process::Command::new(yagna)
+        .args(&["id", "lock", "--new-password"])
+        .status()
+        .await?;
+    if !status.success() {
+        return Err(anyhow!("yagna id lock failed with {}", status));
+    }
+    Ok(())
+}
+
+async fn backup(options: &CommandOptions, out: Option<PathBuf>) -> Result<i32> {
+    let cmd = YaCommand::new(options)?;
+    // a replayed keystore is no backup, and locking would change the real identity
+    if cmd.is_replay() {
+        bail!(tr!("wallet-backup-replay"));
+    }
+    let id = cmd.yagna()?.default_id().await?;
+    let path = out.unwrap_or_else(|| {
+        PathBuf::from(format!(
+            "yagna-key-{}-{}.json",
+            id.node_id,
+            Utc::now().format("%Y-%m-%d")
+        ))
+    });
+    // the file may hold an older backup, possibly of another key
+    if path.exists() {
+        bail!(tr!(
+            "wallet-backup-exists",
+            path = path.display().to_string()
+        ));
+    }
+    if !id.is_locked {
+        eprintln!("{}", tr!("wallet-backup-unlocked"));
+        let set_password: bool = promptly::prompt_default(tr!("wallet-backup-lock"), true)?;
+        if set_password {
+            lock(&cmd).await?;
+        }
+    }
+
+    let keystore = cmd.yagna()?.export_id().await?;
+    write_atomic_private(&path, serde_json::to_string_pretty(&keystore)?.as_bytes())?;
+
+    let path = path.canonicalize().unwrap_or(path);
+    if let Err(e) = history::append(
+        BACKUP_HISTORY,
+        WalletBackup {
+            node_id: id.node_id,
+            path: path.clone(),
+        },
+    ) {
+        tracing::warn!("failed to record the backup: {:?}", e);
+    }
+    println!(
+        "{}",
+        tr!("wallet-backup-written", path = path.display().to_string())
+    );
+    Ok(0)
+}
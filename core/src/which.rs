@@ -0,0 +1,84 @@
+//! Binaries yagna-usd invokes, for telling coexisting installations apart
+
+use ansi_term::{Colour, Style};
+use anyhow::Result;
+use prettytable::{cell, format, row, Table};
+
+use crate::command::{CommandOptions, YaCommand};
+use crate::doctor::is_executable;
+use crate::processes;
+
+pub async fn run(options: &CommandOptions) -> Result</*exit code*/ i32> {
+    let cmd = YaCommand::new(options)?;
+    let (yagna_version, provider_version) =
+        futures::future::join(cmd.yagna()?.version_raw(), cmd.ya_provider()?.version()).await;
+    let versions = [
+        ("yagna", yagna_version.map(|version| version.version)),
+        ("ya-provider", provider_version),
+    ];
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    table.set_titles(row![
+        tr!("which-program"),
+        tr!("which-path"),
+        tr!("which-install"),
+        tr!("which-version"),
+        tr!("which-executable")
+    ]);
+    let mut missing = false;
+    for (program, version) in versions {
+        let path = match cmd.resolve(program) {
+            Some(path) => path,
+            None => {
+                missing = true;
+                table.add_row(row![
+                    program,
+                    Style::new().fg(Colour::Red).paint(tr!("which-not-found"))
+                ]);
+                continue;
+            }
+        };
+        // chase symlinks, eg. ~/.local/bin/yagna -> ~/.local/share/yagna/bin/yagna
+        let shown = match path.canonicalize() {
+            Ok(target) if target != path => format!("{} -> {}", path.display(), target.display()),
+            _ => path.display().to_string(),
+        };
+        let version = version.unwrap_or_else(|e| {
+            tracing::debug!("{} version: {}", program, e);
+            "-".to_string()
+        });
+        let executable = if is_executable(&path) {
+            Style::new().fg(Colour::Green).paint(tr!("which-yes"))
+        } else {
+            missing = true;
+            Style::new().fg(Colour::Red).paint(tr!("which-no"))
+        };
+        table.add_row(row![
+            program,
+            shown,
+            cmd.install_method(program)
+                .map(|method| method.describe())
+                .unwrap_or_default(),
+            version,
+            executable
+        ]);
+    }
+    table.printstd();
+    if let Some(running) = processes::detect_yagna() {
+        let default = || tr!("which-default");
+        println!(
+            "\n{}",
+            tr!(
+                "which-running",
+                pid = running.pid,
+                api = running.api_url.unwrap_or_else(default),
+                datadir = running
+                    .datadir
+                    .map(|datadir| datadir.display().to_string())
+                    .unwrap_or_else(default)
+            )
+        );
+    }
+    Ok(if missing { 1 } else { 0 })
+}
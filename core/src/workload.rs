@@ -0,0 +1,202 @@
+//! Work the provider did, broken down by runtime
+//!
+//! ya-provider keeps every agreement it worked on in
+//! `<DATA_DIR>/exe-unit/work/<agreement id>/agreement.json`, next to a directory per activity.
+
+use anyhow::{anyhow, Result};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ya_client::model::payment::DocumentStatus;
+use ya_client::payment::PaymentApi;
+use ya_client::web::WebClient;
+
+use crate::appkey;
+use crate::command::{Preset, YaCommand};
+use crate::utils::provider_datadir;
+
+/// Activities and invoiced GLM of a single runtime, eg. "vm".
+#[derive(Deserialize, Serialize)]
+pub struct RuntimeActivity {
+    pub runtime: String,
+    pub agreements: usize,
+    pub activities: usize,
+    /// GLM invoiced for the agreements
+    pub invoiced: BigDecimal,
+}
+
+/// Agreement found in the ya-provider work directory.
+pub struct AgreementWork {
+    pub agreement_id: String,
+    pub runtime: String,
+    pub activities: usize,
+    /// Properties of the provider's offer
+    pub offer: Value,
+}
+
+/// GLM of the invoices for an agreement, confirmed meaning paid.
+#[derive(Default)]
+pub struct AgreementPayments {
+    pub invoiced: BigDecimal,
+    pub confirmed: BigDecimal,
+}
+
+/// Offer property `name`, properties may be flat ("golem.runtime.name") or nested.
+pub(crate) fn property<'a>(properties: &'a Value, name: &str) -> Option<&'a Value> {
+    properties
+        .get(name)
+        .or_else(|| properties.pointer(&format!("/{}", name.replace('.', "/"))))
+}
+
+/// Preset the offer was published from, recognized by its runtime and pricing.
+pub fn preset_name(offer: &Value, runtime: &str, presets: &[Preset]) -> Option<String> {
+    let coeffs = property(offer, "golem.com.pricing.model.linear.coeffs")?
+        .as_array()?
+        .iter()
+        .map(Value::as_f64)
+        .collect::<Option<Vec<_>>>()?;
+    let vector = property(offer, "golem.com.usage.vector")?
+        .as_array()?
+        .iter()
+        .map(Value::as_str)
+        .collect::<Option<Vec<_>>>()?;
+    // the fixed price follows the coefficients of the usage counters
+    let (initial_price, usage) = coeffs.split_last()?;
+    if usage.len() != vector.len() {
+        return None;
+    }
+    let same = |a: f64, b: f64| (a - b).abs() <= f64::EPSILON * a.abs().max(b.abs()).max(1.0);
+    let counter = |name: &str| name.rsplit('.').next().unwrap_or(name).to_string();
+    presets
+        .iter()
+        .filter(|preset| preset.exeunit_name == runtime)
+        .find(|preset| {
+            same(preset.initial_price, *initial_price)
+                && vector.iter().zip(usage).all(|(name, coeff)| {
+                    let price = preset
+                        .usage_coeffs
+                        .iter()
+                        .find(|(key, _)| counter(key) == counter(name))
+                        .map(|(_, price)| *price)
+                        .unwrap_or_default();
+                    same(price, *coeff)
+                })
+        })
+        .map(|preset| preset.name.clone())
+}
+
+fn read_agreement(dir: &Path) -> Option<AgreementWork> {
+    let agreement: Value =
+        serde_json::from_slice(&fs::read(dir.join("agreement.json")).ok()?).ok()?;
+    let activities = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .count();
+    let offer = agreement.pointer("/offer/properties")?.clone();
+    Some(AgreementWork {
+        agreement_id: dir.file_name()?.to_string_lossy().to_string(),
+        runtime: property(&offer, "golem.runtime.name")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown")
+            .to_string(),
+        activities,
+        offer,
+    })
+}
+
+fn work_dir() -> Result<PathBuf> {
+    Ok(provider_datadir()
+        .ok_or_else(|| anyhow!("unable to determine ya-provider data directory"))?
+        .join("exe-unit")
+        .join("work"))
+}
+
+/// Agreements from the ya-provider work directory, empty when there is none yet.
+pub fn agreements() -> Result<Vec<AgreementWork>> {
+    let work_dir = work_dir()?;
+    let entries = match fs::read_dir(&work_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(anyhow!("reading {}: {}", work_dir.display(), e)),
+    };
+    Ok(entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| read_agreement(&entry.path()))
+        .collect())
+}
+
+/// A single agreement, `None` until ya-provider started working on it.
+pub fn agreement(agreement_id: &str) -> Result<Option<AgreementWork>> {
+    Ok(read_agreement(&work_dir()?.join(agreement_id)))
+}
+
+/// Activities of the agreement with the time ya-provider created them.
+pub fn activities(agreement_id: &str) -> Result<Vec<(String, DateTime<Utc>)>> {
+    let dir = work_dir()?.join(agreement_id);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(anyhow!("reading {}: {}", dir.display(), e)),
+    };
+    Ok(entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok().filter(|m| m.is_dir())?;
+            // creation time is not available on every filesystem
+            let created = metadata.created().or_else(|_| metadata.modified()).ok()?;
+            Some((
+                entry.file_name().to_string_lossy().to_string(),
+                DateTime::<Utc>::from(created),
+            ))
+        })
+        .collect())
+}
+
+/// Invoiced and confirmed GLM per agreement.
+pub async fn payments(cmd: &YaCommand) -> Result<HashMap<String, AgreementPayments>> {
+    let app_key = appkey::get_app_key(cmd).await?;
+    let payment_api: PaymentApi = WebClient::with_token(&app_key).interface()?;
+    let mut payments = HashMap::<String, AgreementPayments>::new();
+    for invoice in payment_api.get_invoices::<Utc>(None, None).await? {
+        let agreement = payments.entry(invoice.agreement_id).or_default();
+        if matches!(invoice.status, DocumentStatus::Settled) {
+            agreement.confirmed += invoice.amount.clone();
+        }
+        agreement.invoiced += invoice.amount;
+    }
+    Ok(payments)
+}
+
+/// Activity counts and invoiced GLM per runtime, busiest first.
+pub async fn collect(cmd: &YaCommand) -> Result<Vec<RuntimeActivity>> {
+    let (agreements, payments) = futures::future::try_join(
+        async { tokio::task::spawn_blocking(agreements).await? },
+        payments(cmd),
+    )
+    .await?;
+    let mut runtimes = BTreeMap::<String, RuntimeActivity>::new();
+    for work in agreements {
+        let runtime = runtimes
+            .entry(work.runtime.clone())
+            .or_insert_with(|| RuntimeActivity {
+                runtime: work.runtime.clone(),
+                agreements: 0,
+                activities: 0,
+                invoiced: BigDecimal::default(),
+            });
+        runtime.agreements += 1;
+        runtime.activities += work.activities;
+        if let Some(payments) = payments.get(&work.agreement_id) {
+            runtime.invoiced += payments.invoiced.clone();
+        }
+    }
+    let mut runtimes = runtimes.into_values().collect::<Vec<_>>();
+    runtimes.sort_by(|a, b| b.activities.cmp(&a.activities));
+    Ok(runtimes)
+}
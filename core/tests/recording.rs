@@ -0,0 +1,55 @@
+//! What `--record` writes to disk
+
+use std::path::PathBuf;
+use yagna_usd_core::command::{CommandRunner, ExecPolicy, Invocation, RecordingRunner};
+
+fn recordings(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("yagna-usd-recording-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join(name)
+}
+
+fn run(runner: &RecordingRunner, invocation: &Invocation) -> Vec<u8> {
+    let policy = ExecPolicy {
+        timeout: std::time::Duration::from_secs(10),
+        retries: 0,
+        echo: false,
+    };
+    tokio::runtime::Builder::new()
+        .basic_scheduler()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(runner.run(invocation, policy))
+        .unwrap()
+}
+
+#[cfg(unix)]
+#[test]
+fn secret_output_is_not_recorded() {
+    let path = recordings("secret.json");
+    let runner = RecordingRunner::new(path.clone()).unwrap();
+    let mut invocation = Invocation::new("echo");
+    invocation.secret().arg("0123456789abcdef");
+
+    let stdout = run(&runner, &invocation);
+
+    assert_eq!(stdout, b"0123456789abcdef\n");
+    assert!(!path.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn output_is_recorded() {
+    let path = recordings("plain.json");
+    let runner = RecordingRunner::new(path.clone()).unwrap();
+    let mut invocation = Invocation::new("echo");
+    invocation.arg("hello");
+
+    run(&runner, &invocation);
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+    assert!(content.contains("\"echo hello\""));
+    assert!(content.contains("hello\\n"));
+}
@@ -0,0 +1,157 @@
+//! `status` collected from recorded yagna outputs, without a daemon running
+
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+use structopt::StructOpt;
+use ya_core_model::NodeId;
+use yagna_usd_core::command::CommandOptions;
+use yagna_usd_core::status::Section;
+use yagna_usd_core::{collect_status, StatusQuery, StatusSnapshot};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+const NODE_ID: &str = "0x1a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d";
+
+/// Keeps the history, caches and config of whoever runs the tests out of reach.
+fn isolate() {
+    static ISOLATE: Once = Once::new();
+    ISOLATE.call_once(|| {
+        let home =
+            std::env::temp_dir().join(format!("yagna-usd-replay-home-{}", std::process::id()));
+        for var in &["XDG_DATA_HOME", "XDG_CACHE_HOME", "XDG_CONFIG_HOME"] {
+            std::env::set_var(var, home.join(var.to_lowercase()));
+        }
+        // the GLM price isn't part of the recordings: with an empty rates cache and every
+        // request sent to a closed local port it fails at once, instead of reaching out
+        std::env::set_var("YAGNA_USD_RATE_RETRIES", "0");
+        std::env::set_var("YAGNA_USD_PROXY", "http://127.0.0.1:9");
+        for var in &["NO_PROXY", "no_proxy"] {
+            std::env::remove_var(var);
+        }
+        // recordings replay whatever datadir the replaying machine uses
+        std::env::set_var("YAGNA_DATADIR", home.join("yagna"));
+    });
+}
+
+fn replay(recordings: &Path) -> CommandOptions {
+    isolate();
+    let mut options = CommandOptions::from_iter(&["yagna-usd"]);
+    options.replay = Some(recordings.to_path_buf());
+    options.record = None;
+    options
+}
+
+fn collect(options: &CommandOptions, sections: Vec<Section>) -> StatusSnapshot {
+    let query = StatusQuery {
+        sections,
+        no_update_check: true,
+        ..StatusQuery::default()
+    };
+    tokio::runtime::Builder::new()
+        .basic_scheduler()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(collect_status(options, &query))
+        .unwrap()
+}
+
+#[test]
+fn status_from_recordings() {
+    let snapshot = collect(
+        &replay(&fixture("status-replay.json")),
+        vec![Section::Activity],
+    );
+
+    assert!(snapshot.is_running);
+    let activity = snapshot.activity.unwrap().unwrap();
+    assert_eq!(activity.last1h_processed(), 3);
+    assert_eq!(activity.in_progress(), 1);
+    assert_eq!(activity.total_processed(), 42);
+}
+
+#[test]
+fn status_from_recordings_with_datadir() {
+    let options = replay(&fixture("status-replay.json"));
+    assert!(options.datadir.is_some());
+
+    let snapshot = collect(&options, vec![Section::Activity]);
+    assert_eq!(snapshot.activity.unwrap().unwrap().total_processed(), 42);
+}
+
+#[test]
+fn status_of_stopped_daemon_from_recordings() {
+    let dir = std::env::temp_dir().join(format!("yagna-usd-replay-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let recordings = dir.join("stopped.json");
+    std::fs::write(
+        &recordings,
+        r#"{"yagna <api reachable>": {"stdout": "false"}}"#,
+    )
+    .unwrap();
+
+    let snapshot = collect(&replay(&recordings), vec![Section::Activity]);
+    std::fs::remove_dir_all(&dir).ok();
+
+    assert!(!snapshot.is_running);
+    assert!(snapshot.activity.is_none());
+}
+
+#[test]
+fn payments_from_recordings() {
+    let snapshot = collect(
+        &replay(&fixture("payments-replay.json")),
+        vec![Section::Payments],
+    );
+
+    let payments = snapshot.payments.unwrap().unwrap();
+    assert_eq!(payments.id.node_id, NODE_ID);
+    assert!(!payments.id.is_locked);
+    let issued = &payments.invoice_status.provider.issued;
+    assert_eq!(issued.agreements_count, 2);
+    assert_eq!(issued.total_amount.to_string(), "1.5");
+    assert_eq!(payments.invoice_status.provider.settled.agreements_count, 9);
+    assert_eq!(payments.invoice_status.requestor.issued.agreements_count, 0);
+}
+
+#[test]
+fn provider_config_from_recordings() {
+    let snapshot = collect(
+        &replay(&fixture("provider-replay.json")),
+        vec![Section::Node],
+    );
+
+    let config = snapshot.config.unwrap().unwrap();
+    assert_eq!(config.provider.node_name.as_deref(), Some("demo-node"));
+    assert_eq!(config.provider.subnet.as_deref(), Some("devnet-beta"));
+    assert!(!config.provider.is_public_subnet());
+    assert_eq!(
+        config.provider.account,
+        Some(NODE_ID.parse::<NodeId>().unwrap())
+    );
+}
+
+#[test]
+fn offers_from_recordings() {
+    let snapshot = collect(
+        &replay(&fixture("provider-replay.json")),
+        vec![Section::Node],
+    );
+
+    let config = snapshot.config.unwrap().unwrap();
+    assert_eq!(config.active_presets, Some(vec!["vm".to_string()]));
+    // the inactive default preset pays more per cpu second, only vm counts
+    let agreements = config.max_agreements.unwrap() as f64;
+    let expected = (0.00002 * agreements + 0.0001 * 4.0) * 3600.0;
+    let potential = config.earning_potential.unwrap();
+    assert!(
+        (potential - expected).abs() < 1e-9,
+        "{} != {}",
+        potential,
+        expected
+    );
+}
@@ -0,0 +1,54 @@
+//! Tracing subscriber setup
+//!
+//! Spans of collectors and subprocess calls are logged when they close, with their
+//! `time.busy` and `time.idle`, so `--log-level debug` shows what a slow run waited for.
+
+use std::str::FromStr;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::EnvFilter;
+
+const DEFAULT_FILTER: &str = "info";
+/// Filter used with -v: debug logs of yagna-usd itself, without the noise of its dependencies
+pub const VERBOSE_FILTER: &str = "info,yagna_usd=debug,yagna_usd_core=debug";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human readable lines (the default)
+    Text,
+    /// One JSON object per line, for log collectors
+    Json,
+}
+
+impl LogFormat {
+    pub const VARIANTS: &'static [&'static str] = &["text", "json"];
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("unknown log format: {}", s)),
+        }
+    }
+}
+
+/// Installs the global subscriber. `level` takes precedence over RUST_LOG.
+pub fn init(format: LogFormat, level: Option<&str>) {
+    let filter = match level {
+        Some(level) => EnvFilter::new(level),
+        None => {
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER))
+        }
+    };
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .with_span_events(FmtSpan::CLOSE);
+    match format {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder.json().with_current_span(true).init(),
+    }
+}
@@ -1,21 +1,92 @@
-#![recursion_limit = "512"]
-
 use anyhow::Result;
 
 use std::env;
 use structopt::{clap, StructOpt};
 
-mod appkey;
-mod command;
-mod platform;
-mod status;
-mod utils;
+mod logging;
+
+use yagna_usd_core::{
+    agreements, allocations, bench, command, display, doctor, earnings, exe_units, hardware, logs,
+    net, outbound, payments, presets, price, processes, profile, provider, resources, runtime,
+    self_update, selftest, service, snapshot, status, top, wallet, which,
+};
 
 #[allow(clippy::large_enum_variant)]
 #[derive(StructOpt)]
 enum Commands {
     /// Show provider status
-    Status,
+    Status(status::StatusCommand),
+
+    /// Save the status to a file or show a saved one
+    Snapshot(snapshot::SnapshotCommand),
+
+    /// Check the provider setup and suggest fixes
+    Doctor(doctor::DoctorCommand),
+
+    /// Show hardware resources the node could offer
+    Hardware,
+
+    /// List ya-provider presets with estimated fiat prices, activate or deactivate them
+    #[structopt(alias = "preset")]
+    Presets(presets::PresetsCommand),
+
+    /// Pricing advice based on network statistics
+    Price(price::PriceCommand),
+
+    /// Show yagna or ya-provider logs
+    Logs(logs::LogsCommand),
+
+    /// Network diagnostics
+    Net(net::NetCommand),
+
+    /// Outbound internet access of payloads: rules, whitelist and keystore
+    Outbound(outbound::OutboundCommand),
+
+    /// Runtime (exe-unit) diagnostics
+    Runtime(runtime::RuntimeCommand),
+
+    /// List the exe-units registered in ya-provider and check their binaries
+    ExeUnits,
+
+    /// Benchmark resources offered by the node
+    Bench(bench::BenchCommand),
+
+    /// Update yagna-usd to the latest release
+    SelfUpdate(self_update::SelfUpdateCommand),
+
+    /// Order a task from the local provider on a testnet and check every stage
+    Selftest(selftest::SelftestCommand),
+
+    /// Agreements with requestors
+    #[structopt(alias = "agreement")]
+    Agreements(agreements::AgreementsCommand),
+
+    /// Debit notes, invoice acceptance and payment confirmation times
+    Payments(payments::PaymentsCommand),
+
+    /// Allocations of the node as a requestor, with remaining GLM and timeout
+    Allocations(allocations::AllocationsCommand),
+
+    /// Live view of the running activities, refreshed every second
+    Top(top::TopCommand),
+
+    /// Confirmed earnings broken down by runtime and preset
+    Earnings(earnings::EarningsCommand),
+
+    /// Start, stop or restart yagna and ya-provider
+    Service(service::ServiceCommand),
+
+    /// Show or change the resources offered to requestors (ya-provider profiles)
+    Profile(resources::ResourcesCommand),
+
+    /// Change basic ya-provider settings
+    Provider(provider::ProviderCommand),
+
+    /// Back up the node's key
+    Wallet(wallet::WalletCommand),
+
+    /// Show the yagna and ya-provider binaries that are invoked
+    Which,
 
     #[structopt(setting = structopt::clap::AppSettings::Hidden)]
     Complete(CompleteCommand),
@@ -39,6 +110,30 @@ pub struct CompleteCommand {
 #[structopt(global_setting = clap::AppSettings::ColoredHelp)]
 #[structopt(global_setting = clap::AppSettings::DeriveDisplayOrder)]
 struct StartupConfig {
+    /// Profile from <config dir>/profiles/NAME.env selecting one of several yagna instances
+    #[structopt(long, env = "YAGNA_USD_PROFILE", global = true)]
+    profile: Option<String>,
+
+    /// Format of log messages written to stderr
+    #[structopt(
+        long,
+        env = "YAGNA_USD_LOG_FORMAT",
+        default_value = "text",
+        possible_values = logging::LogFormat::VARIANTS,
+        global = true
+    )]
+    log_format: logging::LogFormat,
+
+    /// Log filter, eg. "debug" or "yagna_usd_core=trace" (overrides RUST_LOG)
+    #[structopt(long, env = "YAGNA_USD_LOG_LEVEL", global = true)]
+    log_level: Option<String>,
+
+    #[structopt(flatten)]
+    amount_format: display::AmountFormat,
+
+    #[structopt(flatten)]
+    command_options: command::CommandOptions,
+
     #[structopt(flatten)]
     commands: Commands,
 }
@@ -46,15 +141,73 @@ struct StartupConfig {
 async fn my_main() -> Result</*exit code*/ i32> {
     dotenv::dotenv().ok();
 
-    if env::var_os(env_logger::DEFAULT_FILTER_ENV).is_none() {
-        env::set_var(env_logger::DEFAULT_FILTER_ENV, "info");
+    let mut cli_args: StartupConfig = StartupConfig::from_args();
+    if let Some(profile) = &cli_args.profile {
+        profile::load(profile)?;
+        // options backed by env vars (the log ones too) have to pick up the values set by the profile
+        cli_args = StartupConfig::from_args();
+    }
+    let log_level = cli_args.log_level.clone().or_else(|| {
+        (cli_args.command_options.verbose > 0).then(|| logging::VERBOSE_FILTER.to_string())
+    });
+    logging::init(cli_args.log_format, log_level.as_deref());
+    display::set_amount_format(cli_args.amount_format);
+
+    // the daemon of a datadir given with --datadir is looked for, not any running one
+    if let Some(datadir) = &cli_args.command_options.datadir {
+        env::set_var("YAGNA_DATADIR", datadir);
+    }
+    // a daemon started by hand with a custom API or datadir is followed unless set explicitly,
+    // replays show the recorded node instead
+    let replay = cli_args.command_options.replay.is_some();
+    if let Some(running) = (!replay).then(processes::detect_yagna).flatten() {
+        tracing::debug!("found yagna running as pid {}", running.pid);
+        if let Some(api_url) = running
+            .api_url
+            .filter(|_| env::var_os("YAGNA_API_URL").is_none())
+        {
+            env::set_var("YAGNA_API_URL", api_url);
+        }
+        let options = &mut cli_args.command_options;
+        if options.datadir.is_none() {
+            options.datadir = running.datadir;
+        }
     }
-    env_logger::init();
 
-    let cli_args: StartupConfig = StartupConfig::from_args();
+    // helpers locating daemon files and plain `yagna` invocations read the datadirs from env
+    let options = &cli_args.command_options;
+    if let Some(datadir) = &options.datadir {
+        env::set_var("YAGNA_DATADIR", datadir);
+    }
+    if let Some(datadir) = &options.provider_datadir {
+        env::set_var("DATA_DIR", datadir);
+    }
 
     match cli_args.commands {
-        Commands::Status => status::run().await,
+        Commands::Status(args) => status::run(&cli_args.command_options, args).await,
+        Commands::Snapshot(command) => snapshot::run(&cli_args.command_options, command).await,
+        Commands::Doctor(args) => doctor::run(&cli_args.command_options, args).await,
+        Commands::Hardware => hardware::run(&cli_args.command_options).await,
+        Commands::Presets(command) => presets::run(&cli_args.command_options, command).await,
+        Commands::Price(command) => price::run(&cli_args.command_options, command).await,
+        Commands::Logs(args) => logs::run(args).await,
+        Commands::Net(command) => net::run(&cli_args.command_options, command).await,
+        Commands::Outbound(command) => outbound::run(&cli_args.command_options, command).await,
+        Commands::Runtime(command) => runtime::run(&cli_args.command_options, command).await,
+        Commands::ExeUnits => exe_units::run(&cli_args.command_options).await,
+        Commands::Bench(command) => bench::run(command).await,
+        Commands::SelfUpdate(args) => self_update::run(args).await,
+        Commands::Selftest(args) => selftest::run(&cli_args.command_options, args).await,
+        Commands::Agreements(command) => agreements::run(&cli_args.command_options, command).await,
+        Commands::Payments(args) => payments::run(&cli_args.command_options, args).await,
+        Commands::Allocations(args) => allocations::run(&cli_args.command_options, args).await,
+        Commands::Top(args) => top::run(&cli_args.command_options, args).await,
+        Commands::Earnings(args) => earnings::run(&cli_args.command_options, args).await,
+        Commands::Service(command) => service::run(&cli_args.command_options, command).await,
+        Commands::Profile(command) => resources::run(&cli_args.command_options, command).await,
+        Commands::Provider(command) => provider::run(&cli_args.command_options, command).await,
+        Commands::Wallet(command) => wallet::run(&cli_args.command_options, command).await,
+        Commands::Which => which::run(&cli_args.command_options).await,
         Commands::Complete(complete) => {
             let binary_name = clap::crate_name!();
             println!(
@@ -76,7 +229,7 @@ async fn main() {
     std::process::exit(match my_main().await {
         Ok(code) => code,
         Err(e) => {
-            log::error!("{:?}", e);
+            tracing::error!("{:?}", e);
             1
         }
     });